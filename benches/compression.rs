@@ -0,0 +1,60 @@
+//! Serialization cost of the compressed encodings in `compress` against the
+//! uncompressed ones: `cargo bench --bench compression`.
+
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveGroup,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Validate};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::ops::Mul;
+use zkhack_puzzle_solution::compress;
+
+fn g2(c: &mut Criterion) {
+    let p = G2Affine::generator().mul(Fr::from(1337u64)).into_affine();
+    let mut uncompressed = Vec::new();
+    p.serialize_uncompressed(&mut uncompressed).unwrap();
+    let compressed = compress::compress_g2(&p);
+
+    c.bench_function("g2/serialize_uncompressed", |b| {
+        b.iter(|| {
+            let mut out = Vec::new();
+            black_box(&p).serialize_uncompressed(&mut out).unwrap();
+            out
+        })
+    });
+    c.bench_function("g2/compress", |b| b.iter(|| compress::compress_g2(black_box(&p))));
+    c.bench_function("g2/deserialize_uncompressed", |b| {
+        b.iter(|| G2Affine::deserialize_uncompressed(black_box(uncompressed.as_slice())).unwrap())
+    });
+    c.bench_function("g2/decompress", |b| b.iter(|| compress::decompress_g2(black_box(&compressed)).unwrap()));
+}
+
+fn gt(c: &mut Criterion) {
+    let x = Bls12_381::pairing(G1Affine::generator(), G2Affine::generator().mul(Fr::from(7u64)).into_affine());
+    let mut uncompressed = Vec::new();
+    x.serialize_uncompressed(&mut uncompressed).unwrap();
+    let compressed = compress::compress_gt(&x);
+
+    c.bench_function("gt/serialize_uncompressed", |b| {
+        b.iter(|| {
+            let mut out = Vec::new();
+            black_box(&x).serialize_uncompressed(&mut out).unwrap();
+            out
+        })
+    });
+    c.bench_function("gt/compress", |b| b.iter(|| compress::compress_gt(black_box(&x))));
+    c.bench_function("gt/deserialize_uncompressed", |b| {
+        b.iter(|| PairingOutput::<Bls12_381>::deserialize_uncompressed(black_box(uncompressed.as_slice())).unwrap())
+    });
+    c.bench_function("gt/decompress", |b| {
+        b.iter(|| compress::decompress_gt(black_box(&compressed), Validate::Yes).unwrap())
+    });
+    c.bench_function("gt/decompress_unchecked", |b| {
+        b.iter(|| compress::decompress_gt(black_box(&compressed), Validate::No).unwrap())
+    });
+}
+
+criterion_group!(benches, g2, gt);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+//! The pairing attack: anyone who sees a blob and knows the message space can
+//! tell which message it encrypts, without any secret key.
+//!
+//! `c.1 = m + sk * rec_pk` and `s = sk * H(c)`, so
+//! `e(c.1, H(c)) - e(rec_pk, s) = e(m, H(c))`, which is compared against every
+//! candidate message.
+//!
+//!     cargo run --example attack
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use zkhack_puzzle_solution::{fixtures, message_space::MessageSpace};
+
+fn main() {
+    let blob = fixtures::puzzle_blob();
+    let space = MessageSpace::puzzle();
+
+    let hash_c = blob.c.hash_to_curve(&[]);
+    let target = Bls12_381::pairing(blob.c.1, hash_c) - Bls12_381::pairing(blob.rec_pk, blob.s);
+
+    match space.messages().iter().position(|m| Bls12_381::pairing(m.0, hash_c) == target) {
+        Some(i) => println!("blob encrypts message {i}: {}", space.exponents()[i]),
+        None => println!("plaintext is not in the message space"),
+    }
+}
@@ -0,0 +1,27 @@
+//! Alice encrypts a message to Bob and tags it; an auditor checks the tag and
+//! signs an attestation that anyone can verify.
+//!
+//!     cargo run --example encrypt_audit
+
+use ark_bls12_381::Fr;
+use zkhack_puzzle_solution::{attest, fixtures, message_space::MessageSpace, Auditor, Blob, SchemeConfig};
+
+fn main() {
+    let (alice, bob) = (fixtures::alice(), fixtures::bob());
+    let aad = b"example-session-1";
+
+    let sender = alice.sender();
+    let m = MessageSpace::puzzle().messages()[3];
+    let c = sender.send(m, &bob.receiver());
+    let s = sender.authenticate(&c, aad);
+    let blob = Blob { sender_pk: alice.pk, c, s, rec_pk: bob.pk };
+    println!("blob: {blob}");
+
+    let auditor = Auditor::with_key(SchemeConfig::default(), Fr::from(42u64));
+    println!("tag valid: {}", auditor.verify(blob.sender_pk, &blob.c, blob.s, aad));
+    println!("tag valid under another context: {}", auditor.verify(blob.sender_pk, &blob.c, blob.s, b"other"));
+
+    let attestation = auditor.attest(&blob, aad).unwrap();
+    let auditor_pk = auditor.public_key().unwrap();
+    println!("attestation valid: {}", attest::verify_attestation(&auditor_pk, &blob, aad, &attestation));
+}
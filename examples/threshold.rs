@@ -0,0 +1,22 @@
+//! Splits Alice's secret key into 3-of-5 Shamir shares, recovers it from three
+//! of them and checks that the recovered key still produces valid tags.
+//!
+//!     cargo run --example threshold
+
+use zkhack_puzzle_solution::{fixtures, recovery, Auditor, Sender};
+
+fn main() {
+    let alice = fixtures::alice();
+    let mut rng = ark_std::test_rng();
+
+    let shares = recovery::split(&alice.sk, 3, 5, &mut rng).unwrap();
+    println!("split into {} shares, threshold 3", shares.len());
+
+    let sk = recovery::recover(&shares[1..4]).unwrap();
+    println!("recovered from shares 2..=4: {}", if sk == alice.sk { "same key" } else { "WRONG KEY" });
+    println!("two shares are not enough: {}", recovery::recover(&shares[..2]).is_err());
+
+    let blob = fixtures::blob(&alice, &fixtures::bob(), 0);
+    let s = Sender::new(sk).authenticate(&blob.c, &[]);
+    println!("tag from recovered key valid: {}", Auditor::check_auth(alice.pk, &blob.c, s, &[]));
+}
@@ -0,0 +1,12 @@
+#![no_main]
+
+use ark_serialize::CanonicalDeserialize;
+use libfuzzer_sys::fuzz_target;
+use zkhack_puzzle_solution::{Auditor, Blob};
+
+// The same path main takes on blob.bin.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(blob) = Blob::deserialize_uncompressed(data) {
+        Auditor::check_auth(blob.sender_pk, &blob.c, blob.s, &[]);
+    }
+});
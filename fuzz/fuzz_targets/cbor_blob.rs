@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zkhack_puzzle_solution::cbor;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(blob) = cbor::decode_blob(data) {
+        assert!(cbor::decode_blob(&cbor::encode_blob(&blob)).unwrap() == blob);
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zkhack_puzzle_solution::cert::Certificate;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(cert) = Certificate::from_bytes(data) {
+        assert_eq!(Certificate::from_bytes(&cert.to_bytes()).unwrap(), cert);
+    }
+});
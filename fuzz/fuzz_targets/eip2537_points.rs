@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zkhack_puzzle_solution::eip2537;
+
+// Decoding must either fail or round-trip to the same bytes.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(p) = eip2537::decode_g1(data) {
+        assert_eq!(eip2537::encode_g1(&p), data);
+    }
+    if let Ok(p) = eip2537::decode_g2(data) {
+        assert_eq!(eip2537::encode_g2(&p), data);
+    }
+});
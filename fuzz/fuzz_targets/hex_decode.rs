@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zkhack_puzzle_solution::hex;
+
+// Decoding must either fail or give back the input, up to case.
+fuzz_target!(|s: &str| {
+    if let Ok(bytes) = hex::decode(s) {
+        assert_eq!(hex::encode(&bytes), s.to_ascii_lowercase());
+    }
+});
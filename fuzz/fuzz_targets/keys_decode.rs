@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zkhack_puzzle_solution::keys::{self, KeyFormat};
+
+// Raw, hex and the hand-rolled JSON: a decoded key must decode again from
+// each of its encodings.
+fuzz_target!(|data: &[u8]| {
+    for format in [KeyFormat::Raw, KeyFormat::Hex, KeyFormat::Json] {
+        if let Ok(sk) = keys::decode_sk(data) {
+            assert_eq!(keys::decode_sk(&keys::encode_sk(&sk, format)).unwrap(), sk);
+        }
+        if let Ok(pk) = keys::decode_pk(data) {
+            assert_eq!(keys::decode_pk(&keys::encode_pk(&pk, format)).unwrap(), pk);
+        }
+    }
+});
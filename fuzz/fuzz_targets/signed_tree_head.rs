@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zkhack_puzzle_solution::transparency::SignedTreeHead;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(sth) = SignedTreeHead::from_bytes(data) {
+        assert_eq!(SignedTreeHead::from_bytes(&sth.to_bytes()).unwrap(), sth);
+    }
+});
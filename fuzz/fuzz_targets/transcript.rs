@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zkhack_puzzle_solution::transcript::Transcript;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(transcript) = Transcript::from_bytes(data) {
+        assert!(Transcript::from_bytes(&transcript.to_bytes()).unwrap() == transcript);
+    }
+});
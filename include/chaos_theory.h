@@ -0,0 +1,108 @@
+#ifndef CHAOS_THEORY_H
+#define CHAOS_THEORY_H
+
+/* Generated with cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Length of an uncompressed G1 point (public keys, messages).
+ */
+#define CHAOS_G1_LEN 96
+
+/**
+ * Length of an uncompressed G2 point (authentication tags).
+ */
+#define CHAOS_G2_LEN 192
+
+/**
+ * Length of a serialized `ElGamal` ciphertext.
+ */
+#define CHAOS_ELGAMAL_LEN (2 * CHAOS_G1_LEN)
+
+/**
+ * Length of a serialized scalar (secret keys).
+ */
+#define CHAOS_SCALAR_LEN 32
+
+typedef struct ElGamal ElGamal;
+
+typedef struct Receiver Receiver;
+
+typedef struct Sender Sender;
+
+/**
+ * Creates a sender from a serialized secret key. Returns null on malformed input.
+ */
+Sender *chaos_sender_new(const uint8_t *sk, size_t sk_len);
+
+/**
+ * Writes the sender's public key into `out`. Returns the number of bytes
+ * written, or -1 if `out` is too small.
+ */
+intptr_t chaos_sender_pk(const Sender *sender, uint8_t *out, size_t out_len);
+
+void chaos_sender_free(Sender *sender);
+
+/**
+ * Creates a receiver from a serialized public key. Returns null on malformed input.
+ */
+Receiver *chaos_receiver_new(const uint8_t *pk, size_t pk_len);
+
+void chaos_receiver_free(Receiver *receiver);
+
+/**
+ * Encrypts a serialized message point to `receiver`. Returns null on malformed input.
+ */
+ElGamal *chaos_sender_send(const Sender *sender,
+                           const uint8_t *msg,
+                           size_t msg_len,
+                           const Receiver *receiver);
+
+/**
+ * Writes the authentication tag of `c` under the context `aad` into `out`.
+ * Returns the number of bytes written, or -1 if `out` is too small.
+ */
+intptr_t chaos_sender_authenticate(const Sender *sender,
+                                   const ElGamal *c,
+                                   const uint8_t *aad_data,
+                                   size_t aad_len,
+                                   uint8_t *out,
+                                   size_t out_len);
+
+/**
+ * Parses a serialized ciphertext. Returns null on malformed input.
+ */
+ElGamal *chaos_elgamal_from_bytes(const uint8_t *data, size_t len);
+
+/**
+ * Writes the serialized ciphertext into `out`. Returns the number of bytes
+ * written, or -1 if `out` is too small.
+ */
+intptr_t chaos_elgamal_to_bytes(const ElGamal *c, uint8_t *out, size_t out_len);
+
+void chaos_elgamal_free(ElGamal *c);
+
+/**
+ * Runs the auditor check on a serialized sender key and tag under the
+ * context `aad`. Returns 1 if the tag is valid, 0 if it is not, and -1 on
+ * malformed input.
+ */
+int32_t chaos_auditor_check_auth(const uint8_t *sender_pk,
+                                 size_t pk_len,
+                                 const ElGamal *c,
+                                 const uint8_t *s,
+                                 size_t s_len,
+                                 const uint8_t *aad_data,
+                                 size_t aad_len);
+
+/**
+ * Runs the auditor check on a whole serialized blob, with an empty `aad`.
+ * Returns 1 if the tag is valid, 0 if it is not, and -1 on malformed input.
+ */
+int32_t chaos_blob_check(const uint8_t *data, size_t len);
+
+#endif /* CHAOS_THEORY_H */
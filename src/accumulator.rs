@@ -0,0 +1,206 @@
+//! Bilinear accumulator over the set of authorized sender keys.
+//!
+//! The authority holds a trapdoor `alpha` and publishes `alpha * g2`. The
+//! accumulator of the keys `pk_1..pk_n` is the single point
+//! `V = prod(alpha + x_i) * g1`, with `x_i` the key hashed to Fr, and the
+//! witness of `pk_i` is `W = V / (alpha + x_i)`, checked by
+//! `e(W, (alpha + x_i) * g2) = e(V, g2)`. Auditors only need `V` and
+//! `alpha * g2`, whatever the size of the set.
+//!
+//! Adding and removing keys changes `V`; every change is published as an
+//! `AccumulatorUpdate`, from which holders update their own witnesses without
+//! the trapdoor.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group};
+use ark_ff::{Field, UniformRand};
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::Rng;
+
+use crate::{fr_utils, Auditor, Error};
+
+pub const ACCUMULATOR_DST: &[u8] = b"chaos-theory-accumulator-v1";
+
+/// The accumulated element of `pk`.
+pub fn element(pk: &G1Affine) -> Fr {
+    let mut data = Vec::new();
+    pk.serialize_compressed(&mut data).unwrap();
+    fr_utils::hash_to_scalar(&data, ACCUMULATOR_DST).unwrap()
+}
+
+/// What auditors need to check witnesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Accumulator {
+    pub value: G1Affine,
+    /// `alpha * g2`.
+    pub public_key: G2Affine,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MembershipWitness(pub G1Affine);
+
+/// A change of the set, with the accumulator value after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccumulatorUpdate {
+    Add { pk: G1Affine, value: G1Affine },
+    Remove { pk: G1Affine, value: G1Affine },
+}
+
+/// The authority maintaining the accumulator.
+#[derive(Clone)]
+pub struct AccumulatorManager {
+    alpha: Fr,
+    accumulator: Accumulator,
+    members: Vec<G1Affine>,
+}
+
+impl AccumulatorManager {
+    /// An accumulator of the empty set, `V = g1`.
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
+        let alpha = Fr::rand(rng);
+        let accumulator = Accumulator {
+            value: G1Affine::generator(),
+            public_key: (G2Projective::generator() * alpha).into_affine(),
+        };
+        AccumulatorManager { alpha, accumulator, members: Vec::new() }
+    }
+
+    pub fn accumulator(&self) -> Accumulator {
+        self.accumulator
+    }
+
+    pub fn members(&self) -> &[G1Affine] {
+        &self.members
+    }
+
+    /// Fails with `InvalidUpdate` if `pk` is already a member.
+    pub fn add(&mut self, pk: G1Affine) -> Result<AccumulatorUpdate, Error> {
+        if self.members.contains(&pk) {
+            return Err(Error::InvalidUpdate);
+        }
+        let value = (self.accumulator.value * (self.alpha + element(&pk))).into_affine();
+        self.accumulator.value = value;
+        self.members.push(pk);
+        Ok(AccumulatorUpdate::Add { pk, value })
+    }
+
+    /// Fails with `UnknownSender` if `pk` is not a member.
+    pub fn remove(&mut self, pk: &G1Affine) -> Result<AccumulatorUpdate, Error> {
+        let i = self.members.iter().position(|m| m == pk).ok_or(Error::UnknownSender)?;
+        self.members.remove(i);
+        let value = (self.accumulator.value * (self.alpha + element(pk)).inverse().unwrap()).into_affine();
+        self.accumulator.value = value;
+        Ok(AccumulatorUpdate::Remove { pk: *pk, value })
+    }
+
+    /// Fails with `UnknownSender` if `pk` is not a member.
+    pub fn witness(&self, pk: &G1Affine) -> Result<MembershipWitness, Error> {
+        if !self.members.contains(pk) {
+            return Err(Error::UnknownSender);
+        }
+        let w = self.accumulator.value * (self.alpha + element(pk)).inverse().unwrap();
+        Ok(MembershipWitness(w.into_affine()))
+    }
+}
+
+impl MembershipWitness {
+    /// Updates the witness of `member` for a published change of the set,
+    /// given the accumulator value before it. Fails with `RevokedKey` if the
+    /// change removes `member`.
+    pub fn update(&mut self, member: &G1Affine, previous: G1Affine, update: &AccumulatorUpdate) -> Result<(), Error> {
+        let x = element(member);
+        let w = match *update {
+            // W' = (alpha + y) W = V + (y - x) W
+            AccumulatorUpdate::Add { pk, .. } => G1Projective::from(previous) + self.0 * (element(&pk) - x),
+            AccumulatorUpdate::Remove { pk, .. } if pk == *member => return Err(Error::RevokedKey),
+            // W = V' + (y - x) W', solved for W'
+            AccumulatorUpdate::Remove { pk, value } => {
+                (G1Projective::from(self.0) - value) * (element(&pk) - x).inverse().unwrap()
+            }
+        };
+        self.0 = w.into_affine();
+        Ok(())
+    }
+}
+
+impl AccumulatorUpdate {
+    /// The accumulator value after the change.
+    pub fn value(&self) -> G1Affine {
+        match *self {
+            AccumulatorUpdate::Add { value, .. } | AccumulatorUpdate::Remove { value, .. } => value,
+        }
+    }
+}
+
+impl Auditor {
+    /// Whether `witness` shows that `pk` is in the set `accumulator` commits to.
+    pub fn verify_membership_witness(
+        &self,
+        accumulator: &Accumulator,
+        pk: &G1Affine,
+        witness: &MembershipWitness,
+    ) -> bool {
+        let shifted = G2Projective::from(accumulator.public_key) + G2Projective::generator() * element(pk);
+        Bls12_381::pairing(witness.0, shifted) == Bls12_381::pairing(accumulator.value, G2Affine::generator())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls;
+
+    fn key<R: Rng>(rng: &mut R) -> G1Affine {
+        bls::public_key(&Fr::rand(rng))
+    }
+
+    #[test]
+    fn members_verify_and_others_dont() {
+        let rng = &mut ark_std::test_rng();
+        let mut manager = AccumulatorManager::new(rng);
+        let (alice, bob, eve) = (key(rng), key(rng), key(rng));
+        manager.add(alice).unwrap();
+        manager.add(bob).unwrap();
+        let accumulator = manager.accumulator();
+        let witness = manager.witness(&alice).unwrap();
+
+        let auditor = Auditor::default();
+        assert!(auditor.verify_membership_witness(&accumulator, &alice, &witness));
+        assert!(!auditor.verify_membership_witness(&accumulator, &eve, &witness));
+        assert!(!auditor.verify_membership_witness(&accumulator, &bob, &witness));
+        let tampered = MembershipWitness((witness.0 + G1Affine::generator()).into_affine());
+        assert!(!auditor.verify_membership_witness(&accumulator, &alice, &tampered));
+        assert!(matches!(manager.witness(&eve), Err(Error::UnknownSender)));
+        assert!(matches!(manager.add(alice), Err(Error::InvalidUpdate)));
+    }
+
+    #[test]
+    fn witnesses_follow_updates() {
+        let rng = &mut ark_std::test_rng();
+        let mut manager = AccumulatorManager::new(rng);
+        let (alice, bob, carol) = (key(rng), key(rng), key(rng));
+        manager.add(alice).unwrap();
+        manager.add(bob).unwrap();
+        let mut witness = manager.witness(&alice).unwrap();
+        let mut bob_witness = manager.witness(&bob).unwrap();
+        let auditor = Auditor::default();
+
+        let previous = manager.accumulator().value;
+        let update = manager.add(carol).unwrap();
+        witness.update(&alice, previous, &update).unwrap();
+        bob_witness.update(&bob, previous, &update).unwrap();
+        assert!(auditor.verify_membership_witness(&manager.accumulator(), &alice, &witness));
+
+        let previous = manager.accumulator().value;
+        let update = manager.remove(&bob).unwrap();
+        witness.update(&alice, previous, &update).unwrap();
+        assert!(auditor.verify_membership_witness(&manager.accumulator(), &alice, &witness));
+        assert!(witness == manager.witness(&alice).unwrap());
+
+        // the revoked key's old witness no longer verifies, and can't be updated
+        assert!(!auditor.verify_membership_witness(&manager.accumulator(), &bob, &bob_witness));
+        assert!(matches!(bob_witness.update(&bob, previous, &update), Err(Error::RevokedKey)));
+        assert!(matches!(manager.remove(&bob), Err(Error::UnknownSender)));
+    }
+}
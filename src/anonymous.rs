@@ -0,0 +1,103 @@
+//! Blobs without the receiver's key, encrypted key-privately.
+//!
+//! An `AnonymousBlob` is encrypted under a fresh ephemeral key `r` instead of
+//! the sender's key, `c = (r * g1, m + r * rec_pk)`, and tagged as usual,
+//! `s = sk * H(c)` under the sender's key. The pairing attack needs the key
+//! that masks `m` to be the one in the tag, so it finds nothing here, and
+//! telling which of two receivers `c` is for, even knowing `m`, means telling
+//! whether `(g1, rec_pk, c.0, c.1 - m)` is a Diffie-Hellman tuple in G1:
+//! ElGamal with fresh randomness is key-private (Bellare, Boldyreva, Desai and
+//! Pointcheval, 2001). Auditors verify the tag exactly like a blob's; the
+//! receiver finds the blobs addressed to it by trial decryption,
+//! `c.1 - rec_sk * c.0`, looking the result up in the message space with
+//! `ct::decrypt`.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::UniformRand;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+
+use crate::{bls, ct, message_space::MessageSpace, Auditor, ElGamal, Message, Receiver, Sender};
+
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AnonymousBlob {
+    pub sender_pk: G1Affine,
+    pub c: ElGamal,
+    pub s: G2Affine,
+}
+
+impl Sender {
+    /// Encrypts `m` to `receiver` under a fresh ephemeral key and tags the
+    /// ciphertext together with `aad`.
+    pub fn send_anonymous<R: Rng>(&self, m: Message, receiver: &Receiver, aad: &[u8], rng: &mut R) -> AnonymousBlob {
+        let r = Fr::rand(rng);
+        let c_1 = (G1Projective::generator() * r).into_affine();
+        let c_2 = (receiver.pk() * r + m.0).into_affine();
+        let c = ElGamal(c_1, c_2);
+        let s = self.authenticate(&c, aad);
+        AnonymousBlob { sender_pk: self.pk, c, s }
+    }
+}
+
+impl Receiver {
+    /// Index in `space` of the message `c` encrypts if it is addressed to
+    /// this receiver, whose secret key is `sk`; `None` otherwise, or if the
+    /// plaintext is not in `space`. The lookup is constant-time.
+    pub fn trial_decrypt(&self, sk: &Fr, c: &ElGamal, space: &MessageSpace) -> Option<usize> {
+        debug_assert_eq!(bls::public_key(sk), self.pk());
+        Option::from(ct::decrypt(sk, c, space.messages())).map(|i: u64| i as usize)
+    }
+
+    /// `(blob index, message index)` of every blob in `blobs` addressed to
+    /// this receiver.
+    pub fn scan(&self, sk: &Fr, blobs: &[AnonymousBlob], space: &MessageSpace) -> Vec<(usize, usize)> {
+        blobs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, blob)| Some((i, self.trial_decrypt(sk, &blob.c, space)?)))
+            .collect()
+    }
+}
+
+impl Auditor {
+    pub fn verify_anonymous(&self, blob: &AnonymousBlob, aad: &[u8]) -> bool {
+        self.verify(blob.sender_pk, &blob.c, blob.s, aad)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attack, Blob, SchemeConfig};
+
+    #[test]
+    fn receiver_finds_its_blobs() {
+        let rng = &mut ark_std::test_rng();
+        let space = MessageSpace::puzzle();
+        let sender = Sender::new(Fr::rand(rng));
+        let sks = [Fr::rand(rng), Fr::rand(rng)];
+        let receivers = sks.map(|sk| Receiver::new(bls::public_key(&sk)));
+        let blobs: Vec<AnonymousBlob> =
+            (0..4).map(|i| sender.send_anonymous(space.messages()[i], &receivers[i % 2], b"aad", rng)).collect();
+
+        assert!(blobs.iter().all(|blob| Auditor::default().verify_anonymous(blob, b"aad")));
+        assert_eq!(receivers[0].scan(&sks[0], &blobs, &space), vec![(0, 0), (2, 2)]);
+        assert_eq!(receivers[1].scan(&sks[1], &blobs, &space), vec![(1, 1), (3, 3)]);
+    }
+
+    #[test]
+    fn pairing_attack_finds_nothing() {
+        let rng = &mut ark_std::test_rng();
+        let space = MessageSpace::puzzle();
+        let sender = Sender::new(Fr::rand(rng));
+        let receiver = Receiver::new(bls::public_key(&Fr::rand(rng)));
+        for m in space.messages() {
+            let anonymous = sender.send_anonymous(*m, &receiver, &[], rng);
+            // even told the receiver, the attack can't test candidates
+            let blob = Blob { sender_pk: anonymous.sender_pk, c: anonymous.c, s: anonymous.s, rec_pk: receiver.pk() };
+            assert_eq!(attack::recover_index(&blob, &SchemeConfig::default(), &[], &space), None);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Salted hash commitments to recovered answers, for timed competitions.
+//!
+//! A solver commits to the plaintext they recovered before the deadline and
+//! reveals the opening afterwards. The commitment covers the solver's
+//! identity and the blob, so a leaked commitment can't be resubmitted by
+//! someone else or against another instance.
+
+use ark_std::rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::{Blob, Error};
+
+pub const ANSWER_DST: &[u8] = b"chaos-theory-answer-v1";
+
+pub const OPENING_LEN: usize = 16 + 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnswerCommitment(pub [u8; 32]);
+
+/// What the solver keeps secret until the reveal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opening {
+    pub answer: u128,
+    pub salt: [u8; 32],
+}
+
+impl Opening {
+    pub fn new<R: Rng>(answer: u128, rng: &mut R) -> Self {
+        Opening { answer, salt: rng.gen() }
+    }
+
+    /// `answer` as 16 big-endian bytes followed by the salt.
+    pub fn to_bytes(&self) -> [u8; OPENING_LEN] {
+        let mut out = [0u8; OPENING_LEN];
+        out[..16].copy_from_slice(&self.answer.to_be_bytes());
+        out[16..].copy_from_slice(&self.salt);
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        if data.len() != OPENING_LEN {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(Opening { answer: u128::from_be_bytes(data[..16].try_into().unwrap()), salt: data[16..].try_into().unwrap() })
+    }
+
+    pub fn commit(&self, solver: &[u8], blob: &Blob) -> AnswerCommitment {
+        let mut h = Sha256::new();
+        h.update(ANSWER_DST);
+        h.update(blob.content_hash());
+        h.update((solver.len() as u64).to_be_bytes());
+        h.update(solver);
+        h.update(self.to_bytes());
+        AnswerCommitment(h.finalize().into())
+    }
+}
+
+pub fn verify_reveal(commitment: &AnswerCommitment, solver: &[u8], blob: &Blob, opening: &Opening) -> bool {
+    opening.commit(solver, blob) == *commitment
+}
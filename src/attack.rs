@@ -0,0 +1,221 @@
+//! The pairing-cancellation attack on the puzzle's scheme.
+//!
+//! `c.1 = m + sk * rec_pk` and `s = sk * H(c)`, so anyone holding a blob can
+//! compute `e(c.1, H(c)) - e(rec_pk, s) = e(m, H(c))` and test it against
+//! candidate messages, or solve a discrete log in GT for small exponents.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Bls12_381, G1Affine, G2Affine};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr,
+};
+use core::{fmt, ops::Range};
+
+use crate::{
+    ct,
+    dlog::{self, Congruence},
+    message_space::MessageSpace,
+    pairing_utils::G2Prepared,
+    Blob, Error, SchemeConfig,
+};
+
+/// Single-core throughput, e.g. from `tuning::calibrate`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rates {
+    pub gt_mul_rate: f64,
+    pub pairing_rate: f64,
+    pub threads: usize,
+}
+
+/// Limits an attack must stay within to be started.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+    pub max_seconds: f64,
+    pub max_memory_bytes: u64,
+}
+
+/// Time budget of `attack` searches when none is given.
+pub const DEFAULT_MAX_SECONDS: f64 = 3600.0;
+
+/// Bytes of one GT element in memory.
+const GT_BYTES: u64 = 576;
+
+/// Predicted cost of recovering an exponent below `2^bound_bits` by brute
+/// force.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub bound_bits: u32,
+    /// `e(c.1, H(c))`, `e(rec_pk, s)` and the base `e(g1, H(c))`.
+    pub pairings: u64,
+    /// Worst-case GT multiplications of the scan.
+    pub gt_muls: u128,
+    /// The scan keeps no table; each thread holds a running value and the target.
+    pub table_entries: u64,
+    pub memory_bytes: u64,
+    /// Worst-case wall-clock time, if rates are known.
+    pub seconds: Option<f64>,
+}
+
+pub fn estimate(bound_bits: u32, rates: Option<&Rates>) -> Estimate {
+    let gt_muls = if bound_bits >= 128 { u128::MAX } else { 1u128 << bound_bits };
+    let pairings = 3;
+    let threads = rates.map_or(1, |r| r.threads.max(1));
+    let seconds = rates.map(|r| pairings as f64 / r.pairing_rate + gt_muls as f64 / (r.gt_mul_rate * threads as f64));
+    Estimate {
+        bound_bits,
+        pairings,
+        gt_muls,
+        table_entries: 0,
+        memory_bytes: 2 * GT_BYTES * threads as u64,
+        seconds,
+    }
+}
+
+impl Estimate {
+    /// Whether the attack stays within `budget`. An unknown running time
+    /// never fits.
+    pub fn fits(&self, budget: &Budget) -> bool {
+        self.memory_bytes <= budget.max_memory_bytes && self.seconds.is_some_and(|s| s <= budget.max_seconds)
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "exponents below 2^{}", self.bound_bits)?;
+        writeln!(f, "pairings:        {}", self.pairings)?;
+        writeln!(f, "GT mults:        {}", self.gt_muls)?;
+        writeln!(f, "table entries:   {}", self.table_entries)?;
+        writeln!(f, "memory:          {} bytes", self.memory_bytes)?;
+        match self.seconds {
+            Some(s) => write!(f, "time:            {s:.1} s"),
+            None => write!(f, "time:            unknown (run `calibrate` first)"),
+        }
+    }
+}
+
+// e(c.1, hash_c) - e(rec_pk, s), with a single final exponentiation
+fn masked(blob: &Blob, hash_c: G2Affine) -> PairingOutput<Bls12_381> {
+    Bls12_381::multi_pairing([blob.c.1, -blob.rec_pk], [hash_c, blob.s])
+}
+
+impl Blob {
+    /// `e(c.1, H(c)) - e(rec_pk, s)`, with `H(c)` hashed under `config` and
+    /// `aad`. For a well-formed blob it equals `e(m, H(c))`: `c.1 = m + sk * rec_pk`
+    /// and `s = sk * H(c)`, so by bilinearity `e(c.1, H(c)) = e(m, H(c)) +
+    /// sk * e(rec_pk, H(c)) = e(m, H(c)) + e(rec_pk, s)`. The receiver's key
+    /// cancels out, leaving the plaintext masked only by `H(c)`, which anyone
+    /// can compute; every attack in this module starts from it.
+    pub fn masked_plaintext_pairing(&self, config: &SchemeConfig, aad: &[u8]) -> PairingOutput<Bls12_381> {
+        masked(self, config.hash_to_curve(&self.c, aad))
+    }
+}
+
+/// Index in `space` of the message `blob` encrypts, found by comparing
+/// `blob.masked_plaintext_pairing()` with `e(m, H(c))` for every candidate,
+/// in constant time (`ct::lookup`).
+pub fn recover_index(blob: &Blob, config: &SchemeConfig, aad: &[u8], space: &MessageSpace) -> Option<usize> {
+    let hash_c = config.hash_to_curve(&blob.c, aad);
+    let target = masked(blob, hash_c);
+    let hash_c = G2Prepared::from(hash_c);
+    let candidates: Vec<_> = space.messages().iter().map(|m| Bls12_381::pairing(m.0, hash_c.clone())).collect();
+    Option::from(ct::lookup(&candidates, &target)).map(|i: u64| i as usize)
+}
+
+/// Hooks called at each step of `recover_index_observed`, e.g. to explain
+/// the attack. Every method defaults to doing nothing.
+pub trait AttackObserver {
+    fn hashed(&mut self, _hash_c: &G2Affine) {}
+    /// `name` is the pairing as written in the module docs, e.g. `e(c.1, H(c))`.
+    fn pairing(&mut self, _name: &str, _value: &PairingOutput<Bls12_381>) {}
+    /// `e(c.1, H(c)) - e(rec_pk, s)`, which equals `e(m, H(c))`.
+    fn target(&mut self, _target: &PairingOutput<Bls12_381>) {}
+    fn candidate(&mut self, _index: usize, _value: &PairingOutput<Bls12_381>, _matched: bool) {}
+}
+
+impl AttackObserver for () {}
+
+/// `recover_index`, reporting every intermediate value to `observer`. The two
+/// pairings of `Blob::masked_plaintext_pairing` are computed separately so
+/// the observer sees both.
+pub fn recover_index_observed<O: AttackObserver + ?Sized>(
+    blob: &Blob,
+    config: &SchemeConfig,
+    aad: &[u8],
+    space: &MessageSpace,
+    observer: &mut O,
+) -> Option<usize> {
+    let hash_c = config.hash_to_curve(&blob.c, aad);
+    observer.hashed(&hash_c);
+    let lhs = Bls12_381::pairing(blob.c.1, hash_c);
+    observer.pairing("e(c.1, H(c))", &lhs);
+    let rhs = Bls12_381::pairing(blob.rec_pk, blob.s);
+    observer.pairing("e(rec_pk, s)", &rhs);
+    let target = lhs - rhs;
+    observer.target(&target);
+
+    let hash_c = G2Prepared::from(hash_c);
+    space.messages().iter().enumerate().find_map(|(i, m)| {
+        let value = Bls12_381::pairing(m.0, hash_c.clone());
+        let matched = value == target;
+        observer.candidate(i, &value, matched);
+        matched.then_some(i)
+    })
+}
+
+/// First bit width tried by `recover_exponent_auto`.
+pub const AUTO_START_BITS: u32 = 16;
+/// Bits added at each step of `recover_exponent_auto`.
+pub const AUTO_STEP_BITS: u32 = 8;
+
+/// The exponent in `range` of the message `blob` encrypts, found by a
+/// discrete-log search of `blob.masked_plaintext_pairing()` to the base
+/// `e(g1, H(c))`.
+pub fn recover_exponent(blob: &Blob, config: &SchemeConfig, aad: &[u8], range: Range<u128>) -> Option<u128> {
+    let hash_c = config.hash_to_curve(&blob.c, aad);
+    let target = masked(blob, hash_c);
+    let base = Bls12_381::pairing(G1Affine::generator(), hash_c);
+    dlog::brute_force(&base, &target, range)
+}
+
+/// `recover_exponent` warm-started from partial knowledge of the exponent,
+/// e.g. `Congruence::low_bits(8, leaked)`: only exponents satisfying all of
+/// `known` are tried. Fails with `InvalidConfig` if `known` is contradictory.
+pub fn recover_exponent_with_hints(
+    blob: &Blob,
+    config: &SchemeConfig,
+    aad: &[u8],
+    range: Range<u128>,
+    known: &[Congruence],
+) -> Result<Option<u128>, Error> {
+    let class = dlog::combine(known).ok_or(Error::InvalidConfig)?;
+    let hash_c = config.hash_to_curve(&blob.c, aad);
+    let target = masked(blob, hash_c);
+    let base = Bls12_381::pairing(G1Affine::generator(), hash_c);
+    Ok(dlog::brute_force_congruent(&base, &target, range, class))
+}
+
+/// `recover_exponent` over exponents below `2^16`, then `2^24`, `2^32`, ...,
+/// each step scanning only the exponents the previous ones didn't, until the
+/// exponent is found or `budget` exponents have been tried. `on_step` is
+/// called with the bit width before each step.
+pub fn recover_exponent_auto(
+    blob: &Blob,
+    config: &SchemeConfig,
+    aad: &[u8],
+    budget: u128,
+    mut on_step: impl FnMut(u32),
+) -> Option<u128> {
+    let mut start = 0;
+    let mut bits = AUTO_START_BITS;
+    while start < budget && bits <= 128 {
+        let end = if bits == 128 { u128::MAX } else { 1u128 << bits }.min(budget);
+        on_step(bits);
+        if let Some(m) = recover_exponent(blob, config, aad, start..end) {
+            return Some(m);
+        }
+        start = end;
+        bits += AUTO_STEP_BITS;
+    }
+    None
+}
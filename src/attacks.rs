@@ -0,0 +1,4 @@
+//! Teaching modules on pairing pitfalls beyond the puzzle's own break.
+
+pub mod invalid_point;
+pub mod pohlig_hellman;
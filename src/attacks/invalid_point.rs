@@ -0,0 +1,101 @@
+//! Invalid-point inputs: points on the curve but outside the prime-order
+//! subgroup, or not on the curve at all.
+//!
+//! The scheme's arithmetic assumes every point is in the subgroup of order r.
+//! A receiver key with a small-order component makes `c.1 = m + sk * rec_pk`
+//! leak `sk` modulo that order, and pairings on such points are no longer
+//! bilinear in the expected way. `survey` shows which of the crate's entry
+//! points take such points without complaint; the `checked_*` wrappers
+//! reject them.
+
+use alloc::{vec, vec::Vec};
+use ark_bls12_381::{Fq, G1Affine, G2Affine};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::One;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{eip2537, point_audit::PointAudit, tamper, Auditor, Blob, ElGamal, Error, Receiver};
+
+/// A point on the G1 curve outside the prime-order subgroup: the first valid
+/// x-coordinate `1 + k` with `k = 0, 1, ...` that gives one.
+pub fn off_subgroup_g1() -> G1Affine {
+    let mut x = Fq::one();
+    loop {
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(x, false) {
+            if !p.is_in_correct_subgroup_assuming_on_curve() {
+                return p;
+            }
+        }
+        x += Fq::one();
+    }
+}
+
+/// A point on the G2 curve outside the prime-order subgroup.
+pub fn off_subgroup_g2() -> G2Affine {
+    tamper::off_subgroup_g2()
+}
+
+/// Whether one entry point accepted an off-subgroup input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiCheck {
+    pub api: &'static str,
+    pub accepts: bool,
+}
+
+fn encode<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut data = Vec::new();
+    value.serialize_uncompressed(&mut data).unwrap();
+    data
+}
+
+/// Feeds off-subgroup points to the crate's entry points and records which
+/// accept them. Constructors and the pairing check take any point; checked
+/// deserialization and the EIP-2537 decoders reject them.
+pub fn survey() -> Vec<ApiCheck> {
+    let (bad_g1, bad_g2) = (off_subgroup_g1(), off_subgroup_g2());
+    let g1 = G1Affine::generator();
+    let blob = Blob { sender_pk: g1, c: ElGamal(g1, g1), s: bad_g2, rec_pk: bad_g1 };
+    let data = encode(&blob);
+
+    vec![
+        // infallible, so they accept anything by construction
+        ApiCheck { api: "Receiver::new", accepts: true },
+        ApiCheck { api: "Auditor::check_auth", accepts: true },
+        ApiCheck {
+            api: "Blob::deserialize_uncompressed_unchecked",
+            accepts: Blob::deserialize_uncompressed_unchecked(data.as_slice()).is_ok(),
+        },
+        ApiCheck {
+            api: "Blob::deserialize_uncompressed",
+            accepts: Blob::deserialize_uncompressed(data.as_slice()).is_ok(),
+        },
+        ApiCheck { api: "eip2537::decode_g1", accepts: eip2537::decode_g1(&eip2537::encode_g1(&bad_g1)).is_ok() },
+        ApiCheck { api: "eip2537::decode_g2", accepts: eip2537::decode_g2(&eip2537::encode_g2(&bad_g2)).is_ok() },
+    ]
+}
+
+fn check<P: SWCurveConfig>(p: &Affine<P>) -> Result<(), Error> {
+    if !PointAudit::analyze(p).is_valid() {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(())
+}
+
+/// `Receiver::new`, rejecting keys outside the prime-order subgroup.
+pub fn checked_receiver(pk: G1Affine) -> Result<Receiver, Error> {
+    check(&pk)?;
+    Ok(Receiver::new(pk))
+}
+
+/// `Auditor::check_auth`, rejecting inputs outside the prime-order subgroups
+/// instead of pairing them.
+pub fn checked_check_auth(sender_pk: G1Affine, c: &ElGamal, s: G2Affine, aad: &[u8]) -> Result<bool, Error> {
+    check(&sender_pk)?;
+    check(&c.0)?;
+    check(&c.1)?;
+    check(&s)?;
+    Ok(Auditor::check_auth(sender_pk, c, s, aad))
+}
@@ -0,0 +1,157 @@
+//! Pohlig-Hellman: discrete logs in groups of smooth order.
+//!
+//! In a group of order `n = p_1^e_1 * ... * p_k^e_k` a discrete log splits
+//! into one per prime power, each solved digit by digit in base `p_i`, and
+//! recombined with the Chinese remainder theorem; the cost is driven by the
+//! largest `p_i`, not by `n`. The scheme's subgroup has prime order, which is
+//! what rules this out. The G1 curve group does not: its cofactor is
+//! `3 * 11^2 * 10177^2 * 859267^2 * 52437899^2`, so a receiver key with a
+//! component outside the subgroup (see `invalid_point`) leaks the sender's
+//! key modulo that component's order, as `leak_sender_sk` shows.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Projective};
+use ark_ec::Group;
+use ark_ff::{PrimeField, Zero};
+
+use crate::{
+    dlog::{self, Congruence},
+    Blob,
+};
+
+/// The cofactor of G1 as `(prime, exponent)` pairs.
+pub const G1_COFACTOR_FACTORS: [(u128, u32); 5] = [(3, 1), (11, 2), (10177, 2), (859267, 2), (52437899, 2)];
+
+fn limbs(x: u128) -> [u64; 2] {
+    [x as u64, (x >> 64) as u64]
+}
+
+fn product(factors: &[(u128, u32)]) -> Option<u128> {
+    factors.iter().try_fold(1u128, |n, &(p, e)| n.checked_mul(p.checked_pow(e)?))
+}
+
+/// The order of `p` as `(prime, exponent)` pairs, given that it divides the
+/// product of `factors`. `None` if it doesn't or the product overflows `u128`.
+pub fn order<G: Group>(p: &G, factors: &[(u128, u32)]) -> Option<Vec<(u128, u32)>> {
+    let mut n = product(factors)?;
+    if !p.mul_bigint(limbs(n)).is_zero() {
+        return None;
+    }
+    let mut order = Vec::new();
+    for &(prime, e) in factors {
+        let mut kept = e;
+        while kept > 0 && p.mul_bigint(limbs(n / prime)).is_zero() {
+            n /= prime;
+            kept -= 1;
+        }
+        if kept > 0 {
+            order.push((prime, kept));
+        }
+    }
+    Some(order)
+}
+
+/// `x` modulo the order of `base` with `x * base == target`, where the order
+/// of `base` divides the product of `factors` (prime, exponent). `None` if
+/// `target` is not a multiple of `base` or the product overflows `u128`.
+pub fn pohlig_hellman<G: Group>(base: &G, target: &G, factors: &[(u128, u32)]) -> Option<u128> {
+    let factors = order(base, factors)?;
+    let n = product(&factors)?;
+    let mut known = Vec::with_capacity(factors.len());
+    for &(p, e) in &factors {
+        let pe = p.pow(e);
+        // project onto the subgroup of order p^e, which h generates
+        let (h, t) = (base.mul_bigint(limbs(n / pe)), target.mul_bigint(limbs(n / pe)));
+        // x mod p^e digit by digit, each a discrete log to the base gamma of order p
+        let gamma = h.mul_bigint(limbs(p.pow(e - 1)));
+        let mut x = 0u128;
+        for k in 0..e {
+            let hk = (t - h.mul_bigint(limbs(x))).mul_bigint(limbs(p.pow(e - 1 - k)));
+            x += dlog::brute_force(&gamma, &hk, 0..p)? * p.pow(k);
+        }
+        known.push(Congruence::new(x, pe)?);
+    }
+    Some(dlog::combine(&known)?.residue)
+}
+
+/// The sender's key modulo the order of the small-order component of the
+/// receiver key, recovered from `c.1 = m + sk * rec_pk`: multiplying by the
+/// subgroup order r kills `m`, leaving `sk * (r * rec_pk)`. `None` if the
+/// receiver key is in the subgroup, where nothing leaks.
+pub fn leak_sender_sk(blob: &Blob) -> Option<Congruence> {
+    let r = Fr::MODULUS;
+    let small = G1Projective::from(blob.rec_pk).mul_bigint(r);
+    let target = G1Projective::from(blob.c.1).mul_bigint(r);
+    let factors = order(&small, &G1_COFACTOR_FACTORS)?;
+    if factors.is_empty() {
+        return None;
+    }
+    let residue = pohlig_hellman(&small, &target, &factors)?;
+    Congruence::new(residue, product(&factors)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attacks::invalid_point::off_subgroup_g1, bls, Message, Receiver, Sender};
+    use ark_bls12_381::G1Affine;
+    use ark_ec::CurveGroup;
+    use ark_ff::{BigInteger, UniformRand};
+
+    // G1's cofactor group is not cyclic in its squared primes, so the
+    // small-order components of points have order 3^a * 11 * 10177 * ...;
+    // scaling away the two largest primes keeps the searches short.
+    fn small_component_point() -> G1Projective {
+        G1Projective::from(off_subgroup_g1()).mul_bigint(limbs(859267u128.pow(2) * 52437899u128.pow(2)))
+    }
+
+    fn fr_mod(x: &Fr, m: u128) -> u128 {
+        x.into_bigint().to_bits_be().into_iter().fold(0, |acc, bit| (2 * acc + bit as u128) % m)
+    }
+
+    #[test]
+    fn order_of_components() {
+        let small = small_component_point().mul_bigint(Fr::MODULUS);
+        assert_eq!(order(&small, &G1_COFACTOR_FACTORS), Some(vec![(11, 1), (10177, 1)]));
+        assert_eq!(order(&G1Projective::zero(), &G1_COFACTOR_FACTORS), Some(vec![]));
+        assert_eq!(order(&G1Projective::generator(), &G1_COFACTOR_FACTORS), None);
+    }
+
+    #[test]
+    fn pohlig_hellman_recovers_known_exponents() {
+        let small = small_component_point().mul_bigint(Fr::MODULUS);
+        let n = 11 * 10177;
+        for x in [0, 1, 1234, 98765, n - 1] {
+            let target = small.mul_bigint(limbs(x));
+            assert_eq!(pohlig_hellman(&small, &target, &G1_COFACTOR_FACTORS), Some(x));
+        }
+        // on the 10177 component alone, given as 10177^2
+        let component = small.mul_bigint(limbs(11));
+        let target = component.mul_bigint(limbs(4321));
+        assert_eq!(pohlig_hellman(&component, &target, &[(10177, 2)]), Some(4321));
+        // a target outside the generated subgroup
+        assert_eq!(pohlig_hellman(&component, &small, &[(10177, 2)]), None);
+    }
+
+    #[test]
+    fn invalid_receiver_key_leaks_the_sender_key() {
+        let rng = &mut ark_std::test_rng();
+        let sender = Sender::new(Fr::rand(rng));
+        let rec_pk = small_component_point().into_affine();
+        let c = sender.send(Message::from_exponent(7), &Receiver::new(rec_pk));
+        let s = sender.authenticate(&c, &[]);
+        let leak = leak_sender_sk(&Blob { sender_pk: sender.pk, c, s, rec_pk }).unwrap();
+        assert_eq!(leak.modulus, 11 * 10177);
+        assert!(leak.contains(fr_mod(&sender.sk, leak.modulus)));
+    }
+
+    #[test]
+    fn honest_receiver_key_leaks_nothing() {
+        let rng = &mut ark_std::test_rng();
+        let sender = Sender::new(Fr::rand(rng));
+        let rec_pk: G1Affine = bls::public_key(&Fr::rand(rng));
+        let c = sender.send(Message::from_exponent(7), &Receiver::new(rec_pk));
+        let s = sender.authenticate(&c, &[]);
+        assert_eq!(leak_sender_sk(&Blob { sender_pk: sender.pk, c, s, rec_pk }), None);
+    }
+}
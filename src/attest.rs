@@ -0,0 +1,150 @@
+//! Auditor key pairs and signed audit reports.
+//!
+//! An auditor holding a key can `attest` to a blob: it runs the usual check
+//! and, if the blob passes, signs a report binding the blob's content hash to
+//! the context it was checked under. Third parties verify the report with
+//! `verify_attestation` without re-running the pairing check themselves.
+
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use core::fmt;
+use sha2::{Digest, Sha256};
+
+use crate::{bls, Auditor, Blob, Error, SchemeConfig};
+
+pub const ATTESTATION_DST: &[u8] = b"chaos-theory-attestation-v1";
+
+#[derive(Clone)]
+pub struct AuditorKey {
+    pub(crate) sk: Fr,
+    pub pk: G1Affine,
+}
+
+impl AuditorKey {
+    pub fn new(sk: Fr) -> Self {
+        AuditorKey { sk, pk: bls::public_key(&sk) }
+    }
+}
+
+impl fmt::Debug for AuditorKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditorKey").field("sk", &"<redacted>").field("pk", &self.pk).finish()
+    }
+}
+
+/// Statement that the blob with `blob_hash` passed the audit under the
+/// context with `aad_hash`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditReport {
+    pub blob_hash: [u8; 32],
+    pub aad_hash: [u8; 32],
+}
+
+impl AuditReport {
+    pub fn new(blob: &Blob, aad: &[u8]) -> Self {
+        AuditReport { blob_hash: blob.content_hash(), aad_hash: Sha256::digest(aad).into() }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        out[..32].copy_from_slice(&self.blob_hash);
+        out[32..].copy_from_slice(&self.aad_hash);
+        out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedAuditReport {
+    pub report: AuditReport,
+    pub auditor_pk: G1Affine,
+    pub signature: G2Affine,
+}
+
+impl Auditor {
+    pub fn with_key(config: SchemeConfig, sk: Fr) -> Self {
+        Auditor { key: Some(AuditorKey::new(sk)), ..Auditor::new(config) }
+    }
+
+    pub fn public_key(&self) -> Option<G1Affine> {
+        self.key.as_ref().map(|key| key.pk)
+    }
+
+    /// Verifies `blob` under `aad` and, if it passes, signs a report saying so.
+    pub fn attest(&self, blob: &Blob, aad: &[u8]) -> Result<SignedAuditReport, Error> {
+        let key = self.key.as_ref().ok_or(Error::MissingKey)?;
+        if !self.verify(blob.sender_pk, &blob.c, blob.s, aad) {
+            return Err(Error::InvalidSignature);
+        }
+        let report = AuditReport::new(blob, aad);
+        let signature = bls::sign(&key.sk, ATTESTATION_DST, &report.to_bytes());
+        Ok(SignedAuditReport { report, auditor_pk: key.pk, signature })
+    }
+}
+
+/// Checks that `attestation` was signed by `auditor_pk` and covers `blob`
+/// checked under `aad`.
+pub fn verify_attestation(auditor_pk: &G1Affine, blob: &Blob, aad: &[u8], attestation: &SignedAuditReport) -> bool {
+    attestation.auditor_pk == *auditor_pk
+        && attestation.report == AuditReport::new(blob, aad)
+        && bls::verify(auditor_pk, ATTESTATION_DST, &attestation.report.to_bytes(), &attestation.signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::UniformRand;
+
+    use crate::{Message, Receiver, Sender};
+
+    // A valid blob tagged under `aad`.
+    fn blob(seed: u64) -> Blob {
+        let rng = &mut ark_std::test_rng();
+        let sender = Sender::new(Fr::rand(rng) + Fr::from(seed));
+        let receiver = Receiver::new(bls::public_key(&Fr::rand(rng)));
+        let c = sender.send(Message(bls::public_key(&Fr::from(seed))), &receiver);
+        let s = sender.authenticate(&c, b"aad");
+        Blob { sender_pk: sender.pk, c, s, rec_pk: receiver.pk() }
+    }
+
+    fn auditor(seed: u64) -> Auditor {
+        Auditor::with_key(SchemeConfig::default(), Fr::from(seed))
+    }
+
+    #[test]
+    fn attestations_verify_for_their_blob_and_context() {
+        let (auditor, blob) = (auditor(1), blob(1));
+        let pk = auditor.public_key().unwrap();
+        let attestation = auditor.attest(&blob, b"aad").unwrap();
+        assert!(verify_attestation(&pk, &blob, b"aad", &attestation));
+
+        assert!(!verify_attestation(&pk, &self::blob(2), b"aad", &attestation));
+        assert!(!verify_attestation(&pk, &blob, b"other", &attestation));
+        assert!(!verify_attestation(&self::auditor(2).public_key().unwrap(), &blob, b"aad", &attestation));
+    }
+
+    #[test]
+    fn forged_attestations_fail() {
+        let (auditor, blob) = (auditor(1), blob(1));
+        let pk = auditor.public_key().unwrap();
+        let attestation = auditor.attest(&blob, b"aad").unwrap();
+
+        let mut forged = attestation;
+        forged.signature = (attestation.signature + G2Affine::generator()).into_affine();
+        assert!(!verify_attestation(&pk, &blob, b"aad", &forged));
+        // another auditor's signature over the same report, claimed under `pk`
+        let mut forged = self::auditor(2).attest(&blob, b"aad").unwrap();
+        assert!(!verify_attestation(&pk, &blob, b"aad", &forged));
+        forged.auditor_pk = pk;
+        assert!(!verify_attestation(&pk, &blob, b"aad", &forged));
+    }
+
+    #[test]
+    fn only_valid_blobs_are_attested() {
+        let mut blob = blob(1);
+        assert!(matches!(auditor(1).attest(&blob, b"other"), Err(Error::InvalidSignature)));
+        blob.s = (blob.s + G2Affine::generator()).into_affine();
+        assert!(matches!(auditor(1).attest(&blob, b"aad"), Err(Error::InvalidSignature)));
+        assert!(matches!(Auditor::default().attest(&self::blob(1), b"aad"), Err(Error::MissingKey)));
+        assert_eq!(Auditor::default().public_key(), None);
+    }
+}
@@ -0,0 +1,80 @@
+//! Batch APIs for encrypting and tagging many messages at once.
+//!
+//! With the `parallel` feature the per-message work is spread over rayon's
+//! thread pool; results are always returned in input order.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{G1Projective, G2Affine, G2Projective};
+use ark_ec::CurveGroup;
+use core::ops::Mul;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{ElGamal, Message, Receiver, Sender};
+
+impl Sender {
+    /// `send` for many messages to the same receiver. The shared mask
+    /// `r.pk * sk` is computed once, leaving one addition per message, and the
+    /// results are normalized to affine in a single batch inversion.
+    pub fn send_batch(&self, msgs: &[Message], r: &Receiver) -> Vec<ElGamal> {
+        let shared = r.pk().mul(&self.sk);
+
+        #[cfg(feature = "parallel")]
+        let c_2s: Vec<G1Projective> = msgs.par_iter().map(|m| shared + m.0).collect();
+        #[cfg(not(feature = "parallel"))]
+        let c_2s: Vec<G1Projective> = msgs.iter().map(|m| shared + m.0).collect();
+
+        G1Projective::normalize_batch(&c_2s)
+            .into_iter()
+            .map(|c_2| ElGamal(self.pk, c_2))
+            .collect()
+    }
+
+    /// `authenticate` for many ciphertexts under the same `aad`. Hash-to-curve
+    /// dominates the cost, so both it and the G2 multiplications run per item
+    /// on the thread pool.
+    pub fn authenticate_batch(&self, cs: &[ElGamal], aad: &[u8]) -> Vec<G2Affine> {
+        let tag = |c: &ElGamal| self.config().hash_to_curve(c, aad).mul(&self.sk);
+
+        #[cfg(feature = "parallel")]
+        let tags: Vec<G2Projective> = cs.par_iter().map(tag).collect();
+        #[cfg(not(feature = "parallel"))]
+        let tags: Vec<G2Projective> = cs.iter().map(tag).collect();
+
+        G2Projective::normalize_batch(&tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use ark_ff::UniformRand;
+
+    use crate::{bls, SchemeConfig};
+
+    #[test]
+    fn authenticate_batch_matches_authenticate() {
+        let rng = &mut ark_std::test_rng();
+        let receiver = Receiver::new(bls::public_key(&Fr::rand(rng)));
+        for config in [SchemeConfig::default(), SchemeConfig::standard()] {
+            let sender = Sender::with_config(Fr::rand(rng), config);
+            let cs: Vec<ElGamal> =
+                (0..5).map(|_| sender.send(Message(bls::public_key(&Fr::rand(rng))), &receiver)).collect();
+            let expected: Vec<G2Affine> = cs.iter().map(|c| sender.authenticate(c, b"aad")).collect();
+            assert_eq!(sender.authenticate_batch(&cs, b"aad"), expected);
+            assert!(sender.authenticate_batch(&[], b"aad").is_empty());
+        }
+    }
+
+    #[test]
+    fn send_batch_matches_send() {
+        let rng = &mut ark_std::test_rng();
+        let sender = Sender::new(Fr::rand(rng));
+        let receiver = Receiver::new(bls::public_key(&Fr::rand(rng)));
+        let msgs: Vec<Message> = (0..5).map(|_| Message(bls::public_key(&Fr::rand(rng)))).collect();
+        let expected: Vec<ElGamal> = msgs.iter().map(|&m| sender.send(m, &receiver)).collect();
+        assert!(sender.send_batch(&msgs, &receiver) == expected);
+        assert!(sender.send_batch(&[], &receiver).is_empty());
+    }
+}
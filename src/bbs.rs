@@ -0,0 +1,268 @@
+//! BBS+ signatures on vectors of messages, with selective disclosure.
+//!
+//! Keys follow the scheme's layout: the secret key `x` is in Fr and the public
+//! key `w = x * g1` in G1, so signatures live in G2. A signature on
+//! `m_1..m_L` is `(A, e, s)` with `A = B / (x + e)` and
+//! `B = g2 + s * h_0 + sum(m_i * h_i)`, checked by `e(w + e * g1, A) = e(g1, B)`.
+//! The generators `h_i` are hashed to G2 under `BBS_DST`.
+//!
+//! `BbsSignature::prove` produces the zero-knowledge proof of Camenisch,
+//! Drijvers and Lehmann (2016) that the holder has a signature on messages
+//! of which only the disclosed ones are revealed. The signature is
+//! randomized into `A' = r1 * A`, `Ā = x * A'` and `d = r1 * B - r2 * h_0`,
+//! and a Schnorr proof shows
+//!
+//! ```text
+//! Ā - d                  = -e * A' + r2 * h_0
+//! g2 + sum(disclosed m_i * h_i) = r3 * d - s' * h_0 - sum(hidden m_j * h_j)
+//! ```
+//!
+//! with `r3 = 1 / r1` and `s' = s - r2 * r3`; proofs of the same signature
+//! are unlinkable.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group, VariableBaseMSM};
+use ark_ff::{Field, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::Rng;
+
+use crate::{bls, fr_utils, Error};
+
+pub const BBS_DST: &[u8] = b"chaos-theory-bbs-v1";
+pub const BBS_CHALLENGE_DST: &[u8] = b"chaos-theory-bbs-challenge-v1";
+
+/// `h_i`; `h_0` blinds the signature, `h_1..` carry the messages.
+pub fn generator(i: usize) -> G2Affine {
+    bls::hash_to_g2(BBS_DST, &(i as u64).to_le_bytes())
+}
+
+/// A byte string as a message.
+pub fn message(bytes: &[u8]) -> Fr {
+    fr_utils::hash_to_scalar(bytes, BBS_DST).unwrap()
+}
+
+// g2 + s * h_0 + sum(m_i * h_i)
+fn commitment(s: Fr, messages: &[Fr]) -> G2Projective {
+    let bases: Vec<G2Affine> = (0..=messages.len()).map(generator).collect();
+    let scalars: Vec<Fr> = core::iter::once(s).chain(messages.iter().copied()).collect();
+    G2Projective::generator() + G2Projective::msm(&bases, &scalars).unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BbsSignature {
+    pub a: G2Affine,
+    pub e: Fr,
+    pub s: Fr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BbsProof {
+    pub a_prime: G2Affine,
+    pub a_bar: G2Affine,
+    pub d: G2Affine,
+    pub challenge: Fr,
+    pub z_e: Fr,
+    pub z_r2: Fr,
+    pub z_r3: Fr,
+    pub z_s: Fr,
+    /// Responses for the hidden messages, in increasing index order.
+    pub z_hidden: Vec<Fr>,
+}
+
+pub fn sign<R: Rng>(sk: &Fr, messages: &[Fr], rng: &mut R) -> BbsSignature {
+    let (e, s) = (Fr::rand(rng), Fr::rand(rng));
+    let a = commitment(s, messages) * (*sk + e).inverse().unwrap();
+    BbsSignature { a: a.into_affine(), e, s }
+}
+
+pub fn verify(pk: &G1Affine, messages: &[Fr], sig: &BbsSignature) -> bool {
+    let lhs = G1Projective::from(*pk) + G1Projective::generator() * sig.e;
+    !sig.a.is_zero()
+        && Bls12_381::pairing(lhs, sig.a) == Bls12_381::pairing(G1Affine::generator(), commitment(sig.s, messages))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn challenge(
+    pk: &G1Affine,
+    a_prime: &G2Affine,
+    a_bar: &G2Affine,
+    d: &G2Affine,
+    t1: &G2Affine,
+    t2: &G2Affine,
+    disclosed: &[(usize, Fr)],
+    nonce: &[u8],
+) -> Fr {
+    let mut transcript = Vec::new();
+    pk.serialize_compressed(&mut transcript).unwrap();
+    for p in [a_prime, a_bar, d, t1, t2] {
+        p.serialize_compressed(&mut transcript).unwrap();
+    }
+    for (i, m) in disclosed {
+        transcript.extend((*i as u64).to_le_bytes());
+        m.serialize_compressed(&mut transcript).unwrap();
+    }
+    transcript.extend(nonce);
+    fr_utils::hash_to_scalar(&transcript, BBS_CHALLENGE_DST).unwrap()
+}
+
+impl BbsSignature {
+    /// Proves possession of this signature on `messages`, revealing only
+    /// those at the indices in `disclosed` (strictly increasing). `nonce`
+    /// binds the proof to a session. Fails with `InvalidSignature` if the
+    /// signature doesn't verify and `InvalidConfig` on bad indices.
+    pub fn prove<R: Rng>(
+        &self,
+        pk: &G1Affine,
+        messages: &[Fr],
+        disclosed: &[usize],
+        nonce: &[u8],
+        rng: &mut R,
+    ) -> Result<BbsProof, Error> {
+        if !disclosed.windows(2).all(|w| w[0] < w[1]) || disclosed.last().is_some_and(|&i| i >= messages.len()) {
+            return Err(Error::InvalidConfig);
+        }
+        if !verify(pk, messages, self) {
+            return Err(Error::InvalidSignature);
+        }
+        let hidden: Vec<usize> = (0..messages.len()).filter(|i| !disclosed.contains(i)).collect();
+        let h0 = generator(0);
+
+        let (r1, r2) = (Fr::rand(rng), Fr::rand(rng));
+        let b = commitment(self.s, messages);
+        let a_prime = self.a * r1;
+        let a_bar = b * r1 - a_prime * self.e;
+        let d = b * r1 - h0 * r2;
+        let r3 = r1.inverse().unwrap();
+        let s_prime = self.s - r2 * r3;
+
+        let (k_e, k_r2, k_r3, k_s) = (Fr::rand(rng), Fr::rand(rng), Fr::rand(rng), Fr::rand(rng));
+        let k_hidden: Vec<Fr> = hidden.iter().map(|_| Fr::rand(rng)).collect();
+        let t1 = h0 * k_r2 - a_prime * k_e;
+        let hidden_bases: Vec<G2Affine> = hidden.iter().map(|&j| generator(j + 1)).collect();
+        let t2 = d * k_r3 - h0 * k_s - G2Projective::msm(&hidden_bases, &k_hidden).unwrap();
+
+        let [a_prime, a_bar, d, t1, t2] = <[G2Affine; 5]>::try_from(G2Projective::normalize_batch(&[
+            a_prime, a_bar, d, t1, t2,
+        ]))
+        .unwrap();
+        let revealed: Vec<(usize, Fr)> = disclosed.iter().map(|&i| (i, messages[i])).collect();
+        let c = challenge(pk, &a_prime, &a_bar, &d, &t1, &t2, &revealed, nonce);
+
+        Ok(BbsProof {
+            a_prime,
+            a_bar,
+            d,
+            challenge: c,
+            z_e: k_e + c * self.e,
+            z_r2: k_r2 + c * r2,
+            z_r3: k_r3 + c * r3,
+            z_s: k_s + c * s_prime,
+            z_hidden: hidden.iter().zip(k_hidden).map(|(&j, k)| k + c * messages[j]).collect(),
+        })
+    }
+}
+
+impl BbsProof {
+    /// Checks the proof for a signature by `pk` on `message_count` messages,
+    /// of which `disclosed` are revealed, under `nonce`.
+    pub fn verify(&self, pk: &G1Affine, message_count: usize, disclosed: &[(usize, Fr)], nonce: &[u8]) -> bool {
+        let increasing = disclosed.windows(2).all(|w| w[0].0 < w[1].0);
+        let hidden: Vec<usize> = (0..message_count).filter(|i| !disclosed.iter().any(|(j, _)| j == i)).collect();
+        if !increasing
+            || disclosed.last().is_some_and(|&(i, _)| i >= message_count)
+            || hidden.len() != self.z_hidden.len()
+            || self.a_prime.is_zero()
+        {
+            return false;
+        }
+        // Ā = x * A'
+        if Bls12_381::pairing(pk, self.a_prime) != Bls12_381::pairing(G1Affine::generator(), self.a_bar) {
+            return false;
+        }
+
+        let (h0, c) = (generator(0), self.challenge);
+        let t1 = h0 * self.z_r2 - self.a_prime * self.z_e - (G2Projective::from(self.a_bar) - self.d) * c;
+
+        let (bases, scalars): (Vec<G2Affine>, Vec<Fr>) =
+            disclosed.iter().map(|&(i, m)| (generator(i + 1), m)).unzip();
+        let public = G2Projective::generator() + G2Projective::msm(&bases, &scalars).unwrap();
+        let hidden_bases: Vec<G2Affine> = hidden.iter().map(|&j| generator(j + 1)).collect();
+        let t2 = self.d * self.z_r3
+            - h0 * self.z_s
+            - G2Projective::msm(&hidden_bases, &self.z_hidden).unwrap()
+            - public * c;
+
+        let [t1, t2] = <[G2Affine; 2]>::try_from(G2Projective::normalize_batch(&[t1, t2])).unwrap();
+        !c.is_zero() && c == challenge(pk, &self.a_prime, &self.a_bar, &self.d, &t1, &t2, disclosed, nonce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages() -> Vec<Fr> {
+        [&b"alice"[..], b"1990-01-01", b"FR", b"auditor"].iter().map(|m| message(m)).collect()
+    }
+
+    #[test]
+    fn signatures_verify_only_on_their_messages() {
+        let rng = &mut ark_std::test_rng();
+        let sk = Fr::rand(rng);
+        let pk = bls::public_key(&sk);
+        let ms = messages();
+        let sig = sign(&sk, &ms, rng);
+        assert!(verify(&pk, &ms, &sig));
+
+        let mut other = ms.clone();
+        other[1] = message(b"2000-01-01");
+        assert!(!verify(&pk, &other, &sig));
+        assert!(!verify(&bls::public_key(&Fr::rand(rng)), &ms, &sig));
+        assert!(!verify(&pk, &ms, &BbsSignature { e: sig.e + Fr::from(1u64), ..sig }));
+    }
+
+    #[test]
+    fn disclosure_proofs() {
+        let rng = &mut ark_std::test_rng();
+        let sk = Fr::rand(rng);
+        let pk = bls::public_key(&sk);
+        let ms = messages();
+        let sig = sign(&sk, &ms, rng);
+        let proof = sig.prove(&pk, &ms, &[0, 2], b"nonce", rng).unwrap();
+        let disclosed = [(0, ms[0]), (2, ms[2])];
+        assert!(proof.verify(&pk, ms.len(), &disclosed, b"nonce"));
+
+        // proofs of the same signature don't repeat
+        let again = sig.prove(&pk, &ms, &[0, 2], b"nonce", rng).unwrap();
+        assert!(again.verify(&pk, ms.len(), &disclosed, b"nonce") && again.a_prime != proof.a_prime);
+        // everything disclosed, or nothing
+        let all: Vec<(usize, Fr)> = ms.iter().copied().enumerate().collect();
+        assert!(sig.prove(&pk, &ms, &[0, 1, 2, 3], b"", rng).unwrap().verify(&pk, ms.len(), &all, b""));
+        assert!(sig.prove(&pk, &ms, &[], b"", rng).unwrap().verify(&pk, ms.len(), &[], b""));
+
+        assert!(!proof.verify(&pk, ms.len(), &[(0, ms[0]), (2, message(b"DE"))], b"nonce"));
+        assert!(!proof.verify(&pk, ms.len(), &[(0, ms[0]), (1, ms[2])], b"nonce"));
+        assert!(!proof.verify(&pk, ms.len(), &disclosed, b"other nonce"));
+        assert!(!proof.verify(&pk, ms.len() + 1, &disclosed, b"nonce"));
+        assert!(!proof.verify(&bls::public_key(&Fr::rand(rng)), ms.len(), &disclosed, b"nonce"));
+        let mut tampered = proof.clone();
+        tampered.z_hidden[0] += Fr::from(1u64);
+        assert!(!tampered.verify(&pk, ms.len(), &disclosed, b"nonce"));
+        let tampered = BbsProof { a_bar: (proof.a_bar + G2Affine::generator()).into_affine(), ..proof.clone() };
+        assert!(!tampered.verify(&pk, ms.len(), &disclosed, b"nonce"));
+    }
+
+    #[test]
+    fn prove_rejects_bad_inputs() {
+        let rng = &mut ark_std::test_rng();
+        let sk = Fr::rand(rng);
+        let pk = bls::public_key(&sk);
+        let ms = messages();
+        let sig = sign(&sk, &ms, rng);
+        assert!(matches!(sig.prove(&pk, &ms, &[2, 0], b"", rng), Err(Error::InvalidConfig)));
+        assert!(matches!(sig.prove(&pk, &ms, &[4], b"", rng), Err(Error::InvalidConfig)));
+        let forged = BbsSignature { s: sig.s + Fr::from(1u64), ..sig };
+        assert!(matches!(forged.prove(&pk, &ms, &[0], b"", rng), Err(Error::InvalidSignature)));
+    }
+}
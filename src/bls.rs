@@ -0,0 +1,28 @@
+//! Plain BLS signatures on byte strings, with the same key layout as the
+//! scheme (public keys in G1, signatures in G2). Used for statements other
+//! than ciphertexts, each under its own domain separation tag.
+
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{hashing::HashToCurve, pairing::Pairing, CurveGroup, Group};
+use core::ops::Mul;
+
+use crate::config::G2Hasher;
+
+pub fn hash_to_g2(dst: &[u8], msg: &[u8]) -> G2Affine {
+    G2Hasher::new(dst).unwrap().hash(msg).unwrap()
+}
+
+pub fn public_key(sk: &Fr) -> G1Affine {
+    G1Projective::generator().mul(sk).into_affine()
+}
+
+pub fn sign(sk: &Fr, dst: &[u8], msg: &[u8]) -> G2Affine {
+    hash_to_g2(dst, msg).mul(sk).into_affine()
+}
+
+pub fn verify(pk: &G1Affine, dst: &[u8], msg: &[u8], sig: &G2Affine) -> bool {
+    let lhs = { Bls12_381::pairing(G1Projective::generator(), sig) };
+    let rhs = { Bls12_381::pairing(pk, hash_to_g2(dst, msg)) };
+
+    lhs == rhs
+}
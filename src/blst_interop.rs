@@ -0,0 +1,104 @@
+//! Conversions between the arkworks types used here and `blst`'s `min_pk`
+//! variant (public keys in G1, signatures in G2), so tags produced by
+//! `Sender::authenticate` can be checked by production BLS stacks.
+//!
+//! Both libraries use the ZCash compressed point encoding, so conversions go
+//! through compressed bytes. Secret keys are big-endian in blst and
+//! little-endian in arkworks.
+
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use blst::{
+    min_pk::{PublicKey, SecretKey, Signature},
+    BLST_ERROR,
+};
+
+use crate::{ElGamal, Error, SchemeConfig};
+
+pub fn secret_key_to_blst(sk: &Fr) -> Result<SecretKey, Error> {
+    let bytes = sk.into_bigint().to_bytes_be();
+    SecretKey::from_bytes(&bytes).map_err(|_| Error::InvalidEncoding)
+}
+
+pub fn secret_key_from_blst(sk: &SecretKey) -> Fr {
+    Fr::from_be_bytes_mod_order(&sk.to_bytes())
+}
+
+pub fn public_key_to_blst(pk: &G1Affine) -> Result<PublicKey, Error> {
+    let mut bytes = Vec::new();
+    pk.serialize_compressed(&mut bytes).unwrap();
+    PublicKey::from_bytes(&bytes).map_err(|_| Error::InvalidEncoding)
+}
+
+pub fn public_key_from_blst(pk: &PublicKey) -> Result<G1Affine, Error> {
+    G1Affine::deserialize_compressed(&pk.compress()[..]).map_err(|_| Error::InvalidEncoding)
+}
+
+pub fn signature_to_blst(s: &G2Affine) -> Result<Signature, Error> {
+    let mut bytes = Vec::new();
+    s.serialize_compressed(&mut bytes).unwrap();
+    Signature::from_bytes(&bytes).map_err(|_| Error::InvalidEncoding)
+}
+
+pub fn signature_from_blst(s: &Signature) -> Result<G2Affine, Error> {
+    G2Affine::deserialize_compressed(&s.compress()[..]).map_err(|_| Error::InvalidEncoding)
+}
+
+/// `Auditor::check_auth` computed by blst: the signed message is the
+/// uncompressed ciphertext followed by `aad`, hashed to G2 under
+/// `config.dst()`.
+pub fn check_auth_blst(config: &SchemeConfig, sender_pk: &PublicKey, c: &ElGamal, s: &Signature, aad: &[u8]) -> bool {
+    let mut msg = Vec::new();
+    c.serialize_uncompressed(&mut msg).unwrap();
+    msg.extend_from_slice(aad);
+    s.verify(true, &msg, config.dst(), &[], sender_pk, true) == BLST_ERROR::BLST_SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bls, Message, Receiver, Sender};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::UniformRand;
+
+    #[test]
+    fn keys_and_signatures_round_trip() {
+        let rng = &mut ark_std::test_rng();
+        let sk = Fr::rand(rng);
+        assert_eq!(secret_key_from_blst(&secret_key_to_blst(&sk).unwrap()), sk);
+
+        let pk = bls::public_key(&sk);
+        let blst_pk = public_key_to_blst(&pk).unwrap();
+        assert_eq!(public_key_from_blst(&blst_pk).unwrap(), pk);
+        assert_eq!(secret_key_to_blst(&sk).unwrap().sk_to_pk().compress(), blst_pk.compress());
+
+        let s = bls::sign(&sk, b"dst", b"msg");
+        assert_eq!(signature_from_blst(&signature_to_blst(&s).unwrap()).unwrap(), s);
+    }
+
+    #[test]
+    fn blst_checks_tags() {
+        let rng = &mut ark_std::test_rng();
+        for config in [SchemeConfig::default(), SchemeConfig::standard()] {
+            let sender = Sender::with_config(Fr::rand(rng), config.clone());
+            let receiver = Receiver::new(bls::public_key(&Fr::rand(rng)));
+            let c = sender.send(Message::from_exponent(3), &receiver);
+            let s = signature_to_blst(&sender.authenticate(&c, b"aad")).unwrap();
+            let pk = public_key_to_blst(&sender.pk).unwrap();
+            assert!(check_auth_blst(&config, &pk, &c, &s, b"aad"));
+
+            assert!(!check_auth_blst(&config, &pk, &c, &s, b"other aad"));
+            let tampered = ElGamal(c.0, (c.1 + G1Affine::generator()).into_affine());
+            assert!(!check_auth_blst(&config, &pk, &tampered, &s, b"aad"));
+            let other_pk = public_key_to_blst(&bls::public_key(&Fr::rand(rng))).unwrap();
+            assert!(!check_auth_blst(&config, &other_pk, &c, &s, b"aad"));
+        }
+        // a tag is only valid under the DST it was made with
+        let sender = Sender::with_config(Fr::rand(rng), SchemeConfig::standard());
+        let c = sender.send(Message::from_exponent(3), &Receiver::new(bls::public_key(&Fr::rand(rng))));
+        let s = signature_to_blst(&sender.authenticate(&c, &[])).unwrap();
+        let pk = public_key_to_blst(&sender.pk).unwrap();
+        assert!(!check_auth_blst(&SchemeConfig::default(), &pk, &c, &s, &[]));
+    }
+}
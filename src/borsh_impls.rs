@@ -0,0 +1,83 @@
+//! Borsh encodings of the public types: the fixed-size compressed encoding of
+//! each point in field order, with no length prefixes.
+
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use borsh::{
+    io::{Error, ErrorKind, Read, Result, Write},
+    BorshDeserialize, BorshSerialize,
+};
+
+use crate::{Blob, ElGamal, Message, Receiver};
+
+const G1_COMPRESSED_LEN: usize = 48;
+const G2_COMPRESSED_LEN: usize = 96;
+
+fn write_point<T: CanonicalSerialize, W: Write>(p: &T, writer: &mut W) -> Result<()> {
+    let mut bytes = Vec::with_capacity(p.compressed_size());
+    p.serialize_compressed(&mut bytes).map_err(|_| Error::new(ErrorKind::InvalidData, "unserializable point"))?;
+    writer.write_all(&bytes)
+}
+
+fn read_point<T: CanonicalDeserialize, R: Read>(reader: &mut R, len: usize) -> Result<T> {
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes)?;
+    T::deserialize_compressed(bytes.as_slice()).map_err(|_| Error::new(ErrorKind::InvalidData, "invalid point"))
+}
+
+impl BorshSerialize for Message {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_point(&self.0, writer)
+    }
+}
+
+impl BorshDeserialize for Message {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Message(read_point::<G1Affine, _>(reader, G1_COMPRESSED_LEN)?))
+    }
+}
+
+impl BorshSerialize for ElGamal {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_point(&self.0, writer)?;
+        write_point(&self.1, writer)
+    }
+}
+
+impl BorshDeserialize for ElGamal {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(ElGamal(read_point(reader, G1_COMPRESSED_LEN)?, read_point(reader, G1_COMPRESSED_LEN)?))
+    }
+}
+
+impl BorshSerialize for Receiver {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_point(&self.pk(), writer)
+    }
+}
+
+impl BorshDeserialize for Receiver {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Receiver::new(read_point(reader, G1_COMPRESSED_LEN)?))
+    }
+}
+
+impl BorshSerialize for Blob {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_point(&self.sender_pk, writer)?;
+        self.c.serialize(writer)?;
+        write_point(&self.s, writer)?;
+        write_point(&self.rec_pk, writer)
+    }
+}
+
+impl BorshDeserialize for Blob {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Blob {
+            sender_pk: read_point(reader, G1_COMPRESSED_LEN)?,
+            c: ElGamal::deserialize_reader(reader)?,
+            s: read_point::<G2Affine, _>(reader, G2_COMPRESSED_LEN)?,
+            rec_pk: read_point(reader, G1_COMPRESSED_LEN)?,
+        })
+    }
+}
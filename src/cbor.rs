@@ -0,0 +1,441 @@
+//! Deterministic CBOR (RFC 8949 §4.2) encoding of blobs, audit reports and
+//! proofs for network transport.
+//!
+//! Wire layouts, with points and scalars in their compressed encoding and
+//! `[* x]` an array of any length:
+//!
+//! ```text
+//! Blob:              [ sender_pk: bstr(48), [ c.0: bstr(48), c.1: bstr(48) ], s: bstr(96), rec_pk: bstr(48) ]
+//! SignedAuditReport: [ [ blob_hash: bstr(32), aad_hash: bstr(32) ], auditor_pk: bstr(48), signature: bstr(96) ]
+//! snark::Proof:      [ a: bstr(48), b: bstr(96), c: bstr(48) ]
+//! LinkProof:         [ challenge: bstr(32), z_v: bstr(32), z_blinding: bstr(32), z_sk: bstr(32) ]
+//! MembershipProof:   [* [ c_i: bstr(32), z_i: bstr(32) ] ]
+//! RangeProof:        [* [ a: bstr(48), c: bstr(48), [ c_0: bstr(32), z_0: bstr(32) ], [ c_1, z_1 ] ] ]
+//! BbsProof:          [ a_prime, a_bar, d: bstr(96), challenge, z_e, z_r2, z_r3, z_s: bstr(32), [* z: bstr(32)] ]
+//! ```
+//!
+//! Heads always use the shortest form and no other layout is accepted, so
+//! every value has exactly one encoding.
+
+use alloc::vec::Vec;
+use ark_bls12_381::Fr;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{
+    attest::{AuditReport, SignedAuditReport},
+    bbs::BbsProof,
+    membership::MembershipProof,
+    pedersen::LinkProof,
+    range::{BitProof, RangeProof},
+    snark, Blob, ElGamal, Error,
+};
+
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_ARRAY: u8 = 4;
+
+fn write_head(out: &mut Vec<u8>, major: u8, len: u64) {
+    let major = major << 5;
+    match len {
+        0..=23 => out.push(major | len as u8),
+        24..=0xff => out.extend([major | 24, len as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend((len as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend((len as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend(len.to_be_bytes());
+        }
+    }
+}
+
+fn write_point<T: CanonicalSerialize>(out: &mut Vec<u8>, p: &T) {
+    write_head(out, MAJOR_BYTES, p.compressed_size() as u64);
+    p.serialize_compressed(&mut *out).unwrap();
+}
+
+pub fn encode_blob(blob: &Blob) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_head(&mut out, MAJOR_ARRAY, 4);
+    write_point(&mut out, &blob.sender_pk);
+    write_head(&mut out, MAJOR_ARRAY, 2);
+    write_point(&mut out, &blob.c.0);
+    write_point(&mut out, &blob.c.1);
+    write_point(&mut out, &blob.s);
+    write_point(&mut out, &blob.rec_pk);
+    out
+}
+
+fn write_hash(out: &mut Vec<u8>, hash: &[u8; 32]) {
+    write_head(out, MAJOR_BYTES, 32);
+    out.extend(hash);
+}
+
+fn write_pair(out: &mut Vec<u8>, (c, z): &(Fr, Fr)) {
+    write_head(out, MAJOR_ARRAY, 2);
+    write_point(out, c);
+    write_point(out, z);
+}
+
+pub fn encode_report(signed: &SignedAuditReport) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_head(&mut out, MAJOR_ARRAY, 3);
+    write_head(&mut out, MAJOR_ARRAY, 2);
+    write_hash(&mut out, &signed.report.blob_hash);
+    write_hash(&mut out, &signed.report.aad_hash);
+    write_point(&mut out, &signed.auditor_pk);
+    write_point(&mut out, &signed.signature);
+    out
+}
+
+pub fn encode_snark_proof(proof: &snark::Proof) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_head(&mut out, MAJOR_ARRAY, 3);
+    write_point(&mut out, &proof.a);
+    write_point(&mut out, &proof.b);
+    write_point(&mut out, &proof.c);
+    out
+}
+
+pub fn encode_link_proof(proof: &LinkProof) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_head(&mut out, MAJOR_ARRAY, 4);
+    for z in [&proof.challenge, &proof.z_v, &proof.z_blinding, &proof.z_sk] {
+        write_point(&mut out, z);
+    }
+    out
+}
+
+pub fn encode_membership_proof(proof: &MembershipProof) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_head(&mut out, MAJOR_ARRAY, proof.responses.len() as u64);
+    for response in &proof.responses {
+        write_pair(&mut out, response);
+    }
+    out
+}
+
+pub fn encode_range_proof(proof: &RangeProof) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_head(&mut out, MAJOR_ARRAY, proof.bits.len() as u64);
+    for bit in &proof.bits {
+        write_head(&mut out, MAJOR_ARRAY, 4);
+        write_point(&mut out, &bit.a);
+        write_point(&mut out, &bit.c);
+        write_pair(&mut out, &bit.responses[0]);
+        write_pair(&mut out, &bit.responses[1]);
+    }
+    out
+}
+
+pub fn encode_bbs_proof(proof: &BbsProof) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_head(&mut out, MAJOR_ARRAY, 9);
+    for p in [&proof.a_prime, &proof.a_bar, &proof.d] {
+        write_point(&mut out, p);
+    }
+    for z in [&proof.challenge, &proof.z_e, &proof.z_r2, &proof.z_r3, &proof.z_s] {
+        write_point(&mut out, z);
+    }
+    write_head(&mut out, MAJOR_ARRAY, proof.z_hidden.len() as u64);
+    for z in &proof.z_hidden {
+        write_point(&mut out, z);
+    }
+    out
+}
+
+fn take<'a>(data: &mut &'a [u8], n: usize) -> Result<&'a [u8], Error> {
+    if data.len() < n {
+        return Err(Error::InvalidEncoding);
+    }
+    let (head, rest) = data.split_at(n);
+    *data = rest;
+    Ok(head)
+}
+
+// reads a head of the given major type, rejecting non-shortest lengths
+fn read_head(data: &mut &[u8], major: u8) -> Result<u64, Error> {
+    let initial = take(data, 1)?[0];
+    if initial >> 5 != major {
+        return Err(Error::InvalidEncoding);
+    }
+    let (len, min) = match initial & 0x1f {
+        n @ 0..=23 => (n as u64, 0),
+        24 => (take(data, 1)?[0] as u64, 24),
+        25 => (u16::from_be_bytes(take(data, 2)?.try_into().unwrap()) as u64, 0x100),
+        26 => (u32::from_be_bytes(take(data, 4)?.try_into().unwrap()) as u64, 0x1_0000),
+        27 => (u64::from_be_bytes(take(data, 8)?.try_into().unwrap()), 0x1_0000_0000),
+        _ => return Err(Error::InvalidEncoding),
+    };
+    if len < min {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(len)
+}
+
+fn read_point<T: CanonicalSerialize + CanonicalDeserialize>(data: &mut &[u8]) -> Result<T, Error> {
+    let len = read_head(data, MAJOR_BYTES)?;
+    let bytes = take(data, usize::try_from(len).map_err(|_| Error::InvalidEncoding)?)?;
+    let point = T::deserialize_compressed(bytes).map_err(|_| Error::InvalidEncoding)?;
+    if point.compressed_size() != bytes.len() {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(point)
+}
+
+// reads an array head, which must announce `len` items
+fn read_array(data: &mut &[u8], len: u64) -> Result<(), Error> {
+    if read_head(data, MAJOR_ARRAY)? != len {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(())
+}
+
+// reads an array of any length with `read_item`; the length isn't trusted
+// for allocation, each item has to be there
+fn read_vec<T>(data: &mut &[u8], read_item: fn(&mut &[u8]) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+    let len = read_head(data, MAJOR_ARRAY)?;
+    (0..len).map(|_| read_item(data)).collect()
+}
+
+fn read_hash(data: &mut &[u8]) -> Result<[u8; 32], Error> {
+    if read_head(data, MAJOR_BYTES)? != 32 {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(take(data, 32)?.try_into().unwrap())
+}
+
+fn read_pair(data: &mut &[u8]) -> Result<(Fr, Fr), Error> {
+    read_array(data, 2)?;
+    Ok((read_point(data)?, read_point(data)?))
+}
+
+// decodes all of `data` with `read`, rejecting trailing bytes
+fn decode_all<T>(mut data: &[u8], read: impl FnOnce(&mut &[u8]) -> Result<T, Error>) -> Result<T, Error> {
+    let value = read(&mut data)?;
+    if !data.is_empty() {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(value)
+}
+
+pub fn decode_blob(data: &[u8]) -> Result<Blob, Error> {
+    decode_all(data, |data| {
+        read_array(data, 4)?;
+        let sender_pk = read_point(data)?;
+        read_array(data, 2)?;
+        let c = ElGamal(read_point(data)?, read_point(data)?);
+        Ok(Blob { sender_pk, c, s: read_point(data)?, rec_pk: read_point(data)? })
+    })
+}
+
+pub fn decode_report(data: &[u8]) -> Result<SignedAuditReport, Error> {
+    decode_all(data, |data| {
+        read_array(data, 3)?;
+        read_array(data, 2)?;
+        let report = AuditReport { blob_hash: read_hash(data)?, aad_hash: read_hash(data)? };
+        Ok(SignedAuditReport { report, auditor_pk: read_point(data)?, signature: read_point(data)? })
+    })
+}
+
+pub fn decode_snark_proof(data: &[u8]) -> Result<snark::Proof, Error> {
+    decode_all(data, |data| {
+        read_array(data, 3)?;
+        Ok(snark::Proof { a: read_point(data)?, b: read_point(data)?, c: read_point(data)? })
+    })
+}
+
+pub fn decode_link_proof(data: &[u8]) -> Result<LinkProof, Error> {
+    decode_all(data, |data| {
+        read_array(data, 4)?;
+        Ok(LinkProof {
+            challenge: read_point(data)?,
+            z_v: read_point(data)?,
+            z_blinding: read_point(data)?,
+            z_sk: read_point(data)?,
+        })
+    })
+}
+
+pub fn decode_membership_proof(data: &[u8]) -> Result<MembershipProof, Error> {
+    decode_all(data, |data| Ok(MembershipProof { responses: read_vec(data, read_pair)? }))
+}
+
+fn read_bit(data: &mut &[u8]) -> Result<BitProof, Error> {
+    read_array(data, 4)?;
+    Ok(BitProof { a: read_point(data)?, c: read_point(data)?, responses: [read_pair(data)?, read_pair(data)?] })
+}
+
+pub fn decode_range_proof(data: &[u8]) -> Result<RangeProof, Error> {
+    decode_all(data, |data| Ok(RangeProof { bits: read_vec(data, read_bit)? }))
+}
+
+pub fn decode_bbs_proof(data: &[u8]) -> Result<BbsProof, Error> {
+    decode_all(data, |data| {
+        read_array(data, 9)?;
+        Ok(BbsProof {
+            a_prime: read_point(data)?,
+            a_bar: read_point(data)?,
+            d: read_point(data)?,
+            challenge: read_point(data)?,
+            z_e: read_point(data)?,
+            z_r2: read_point(data)?,
+            z_r3: read_point(data)?,
+            z_s: read_point(data)?,
+            z_hidden: read_vec(data, read_point)?,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{G1Affine, G2Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::UniformRand;
+    use ark_std::rand::Rng;
+
+    use crate::{bls, Auditor, Message, Receiver, SchemeConfig, Sender};
+
+    fn blob<R: Rng>(rng: &mut R) -> Blob {
+        let sender = Sender::new(Fr::rand(rng));
+        let receiver = Receiver::new(bls::public_key(&Fr::rand(rng)));
+        let c = sender.send(Message(bls::public_key(&Fr::rand(rng))), &receiver);
+        let s = sender.authenticate(&c, b"aad");
+        Blob { sender_pk: sender.pk, c, s, rec_pk: receiver.pk() }
+    }
+
+    fn pair<R: Rng>(rng: &mut R) -> (Fr, Fr) {
+        (Fr::rand(rng), Fr::rand(rng))
+    }
+
+    fn g1<R: Rng>(rng: &mut R) -> G1Affine {
+        (G1Affine::generator() * Fr::rand(rng)).into_affine()
+    }
+
+    fn g2<R: Rng>(rng: &mut R) -> G2Affine {
+        (G2Affine::generator() * Fr::rand(rng)).into_affine()
+    }
+
+    // Every encoding of a value of each layout.
+    fn encodings() -> Vec<Vec<u8>> {
+        let rng = &mut ark_std::test_rng();
+        let blob = blob(rng);
+        let report = Auditor::with_key(SchemeConfig::default(), Fr::rand(rng)).attest(&blob, b"aad").unwrap();
+        vec![
+            encode_blob(&blob),
+            encode_report(&report),
+            encode_snark_proof(&snark::Proof { a: g1(rng), b: g2(rng), c: g1(rng) }),
+            encode_link_proof(&LinkProof {
+                challenge: Fr::rand(rng),
+                z_v: Fr::rand(rng),
+                z_blinding: Fr::rand(rng),
+                z_sk: Fr::rand(rng),
+            }),
+            encode_membership_proof(&MembershipProof { responses: (0..3).map(|_| pair(rng)).collect() }),
+            encode_range_proof(&RangeProof {
+                bits: (0..2).map(|_| BitProof { a: g1(rng), c: g1(rng), responses: [pair(rng), pair(rng)] }).collect(),
+            }),
+            encode_bbs_proof(&BbsProof {
+                a_prime: g2(rng),
+                a_bar: g2(rng),
+                d: g2(rng),
+                challenge: Fr::rand(rng),
+                z_e: Fr::rand(rng),
+                z_r2: Fr::rand(rng),
+                z_r3: Fr::rand(rng),
+                z_s: Fr::rand(rng),
+                z_hidden: (0..2).map(|_| Fr::rand(rng)).collect(),
+            }),
+        ]
+    }
+
+    // Decodes `data` with the decoder of layout `i` and re-encodes it.
+    fn reencode(i: usize, data: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(match i {
+            0 => encode_blob(&decode_blob(data)?),
+            1 => encode_report(&decode_report(data)?),
+            2 => encode_snark_proof(&decode_snark_proof(data)?),
+            3 => encode_link_proof(&decode_link_proof(data)?),
+            4 => encode_membership_proof(&decode_membership_proof(data)?),
+            5 => encode_range_proof(&decode_range_proof(data)?),
+            _ => encode_bbs_proof(&decode_bbs_proof(data)?),
+        })
+    }
+
+    #[test]
+    fn values_round_trip() {
+        let rng = &mut ark_std::test_rng();
+        let blob = blob(rng);
+        assert!(decode_blob(&encode_blob(&blob)).unwrap() == blob);
+        let report = Auditor::with_key(SchemeConfig::default(), Fr::rand(rng)).attest(&blob, b"aad").unwrap();
+        assert_eq!(decode_report(&encode_report(&report)).unwrap(), report);
+        let empty = MembershipProof { responses: Vec::new() };
+        assert_eq!(encode_membership_proof(&empty), [0x80]);
+        assert_eq!(decode_membership_proof(&[0x80]).unwrap(), empty);
+
+        for (i, data) in encodings().iter().enumerate() {
+            assert_eq!(&reencode(i, data).unwrap(), data, "layout {i}");
+        }
+    }
+
+    #[test]
+    fn blob_layout_is_fixed() {
+        let data = encode_blob(&blob(&mut ark_std::test_rng()));
+        // array(4), bstr(48) sender_pk, array(2), bstr(48) c.0 ...
+        assert_eq!(data[..3], [0x84, 0x58, 48]);
+        assert_eq!(data[51..54], [0x82, 0x58, 48]);
+        assert_eq!(data.len(), 1 + 4 * (2 + 48) + 1 + (2 + 96));
+    }
+
+    #[test]
+    fn trailing_bytes_are_rejected() {
+        for (i, mut data) in encodings().into_iter().enumerate() {
+            data.push(0);
+            assert!(matches!(reencode(i, &data), Err(Error::InvalidEncoding)), "layout {i}");
+            data.truncate(data.len() - 2);
+            assert!(matches!(reencode(i, &data), Err(Error::InvalidEncoding)), "layout {i}");
+        }
+    }
+
+    #[test]
+    fn non_shortest_heads_are_rejected() {
+        for (i, data) in encodings().into_iter().enumerate() {
+            // the outer array length in a one-byte argument instead of the initial byte
+            let long: Vec<u8> = [0x98, data[0] & 0x1f].into_iter().chain(data[1..].iter().copied()).collect();
+            assert!(matches!(reencode(i, &long), Err(Error::InvalidEncoding)), "layout {i}");
+        }
+        // a 48-byte string length in two bytes
+        let data = encode_blob(&blob(&mut ark_std::test_rng()));
+        let long: Vec<u8> = [0x84, 0x59, 0, 48].into_iter().chain(data[3..].iter().copied()).collect();
+        assert!(matches!(decode_blob(&long), Err(Error::InvalidEncoding)));
+    }
+
+    #[test]
+    fn other_layouts_are_rejected() {
+        let data = encode_link_proof(&LinkProof {
+            challenge: Fr::from(1u64),
+            z_v: Fr::from(2u64),
+            z_blinding: Fr::from(3u64),
+            z_sk: Fr::from(4u64),
+        });
+        // a scalar above the group order
+        let mut big = data.clone();
+        big[3..35].fill(0xff);
+        assert!(matches!(decode_link_proof(&big), Err(Error::InvalidEncoding)));
+        // a map instead of an array, and an array one item short
+        let mut map = data.clone();
+        map[0] = 0xa4;
+        assert!(matches!(decode_link_proof(&map), Err(Error::InvalidEncoding)));
+        let mut short = data[..data.len() - 34].to_vec();
+        short[0] = 0x83;
+        assert!(matches!(decode_link_proof(&short), Err(Error::InvalidEncoding)));
+        // an array claiming more items than the input holds
+        let huge = [0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+        assert!(matches!(decode_membership_proof(&huge), Err(Error::InvalidEncoding)));
+    }
+}
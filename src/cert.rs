@@ -0,0 +1,296 @@
+//! Certificates binding identities to keys.
+//!
+//! An auditor holding a key issues a `Certificate` saying that `pk` belongs
+//! to `identity` between `not_before` and `not_after`. Participants of the
+//! demo network collect certificates in a `CertificateStore` and address each
+//! other by name instead of by raw point: `Sender::send_to_identity` resolves
+//! the receiver's key through the store and binds the name into the tag, and
+//! `Auditor::check_identity` checks both.
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    bls,
+    registry::{push_point, push_str},
+    Auditor, Blob, Error, Message, Receiver, Sender,
+};
+
+pub const CERTIFICATE_DST: &[u8] = b"chaos-theory-certificate-v1";
+pub const IDENTITY_DST: &[u8] = b"chaos-theory-identity-v1";
+
+/// The context a ciphertext addressed to `identity` is tagged under: a hash
+/// of the identity followed by the application context.
+pub fn identity_aad(identity: &str, aad: &[u8]) -> Vec<u8> {
+    let mut out = Sha256::new().chain_update(IDENTITY_DST).chain_update(identity).finalize().to_vec();
+    out.extend_from_slice(aad);
+    out
+}
+
+/// Times are opaque `u64`s (e.g. unix seconds), as in the key registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    pub identity: String,
+    pub pk: G1Affine,
+    pub not_before: u64,
+    pub not_after: u64,
+    pub signature: G2Affine,
+}
+
+impl Certificate {
+    /// The signed part: identity, key and validity window.
+    pub fn tbs_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        push_str(&mut out, &self.identity);
+        push_point(&mut out, &self.pk);
+        out.extend(self.not_before.to_le_bytes());
+        out.extend(self.not_after.to_le_bytes());
+        out
+    }
+
+    /// Checks the issuer's signature and that the certificate is valid at `now`.
+    pub fn verify(&self, issuer_pk: &G1Affine, now: u64) -> Result<(), Error> {
+        if !bls::verify(issuer_pk, CERTIFICATE_DST, &self.tbs_bytes(), &self.signature) {
+            return Err(Error::InvalidSignature);
+        }
+        if now < self.not_before || now >= self.not_after {
+            return Err(Error::ExpiredKey);
+        }
+        Ok(())
+    }
+
+    /// The signed part followed by the compressed signature.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.tbs_bytes();
+        self.signature.serialize_compressed(&mut out).unwrap();
+        out
+    }
+
+    /// Parses `to_bytes` output. Fails with `InvalidEncoding` on malformed
+    /// input, including trailing bytes after the signature.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = |_| Error::InvalidEncoding;
+        let mut rest = bytes;
+        let len = u64::deserialize_compressed(&mut rest).map_err(invalid)? as usize;
+        if rest.len() < len {
+            return Err(Error::InvalidEncoding);
+        }
+        let (identity, mut rest) = rest.split_at(len);
+        let identity = String::from_utf8(identity.to_vec()).map_err(|_| Error::InvalidEncoding)?;
+        let pk = G1Affine::deserialize_compressed(&mut rest).map_err(invalid)?;
+        let not_before = u64::deserialize_compressed(&mut rest).map_err(invalid)?;
+        let not_after = u64::deserialize_compressed(&mut rest).map_err(invalid)?;
+        let signature = G2Affine::deserialize_compressed(&mut rest).map_err(invalid)?;
+        if !rest.is_empty() {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(Certificate { identity, pk, not_before, not_after, signature })
+    }
+}
+
+impl Auditor {
+    /// Certifies that `pk` belongs to `identity` in `[not_before, not_after)`.
+    pub fn issue_certificate(
+        &self,
+        identity: &str,
+        pk: G1Affine,
+        not_before: u64,
+        not_after: u64,
+    ) -> Result<Certificate, Error> {
+        let key = self.key.as_ref().ok_or(Error::MissingKey)?;
+        let mut cert =
+            Certificate { identity: identity.into(), pk, not_before, not_after, signature: G2Affine::default() };
+        cert.signature = bls::sign(&key.sk, CERTIFICATE_DST, &cert.tbs_bytes());
+        Ok(cert)
+    }
+}
+
+/// Certificates from one issuer, indexed by identity.
+#[derive(Debug, Clone)]
+pub struct CertificateStore {
+    issuer_pk: G1Affine,
+    certs: BTreeMap<String, Vec<Certificate>>,
+}
+
+impl CertificateStore {
+    pub fn new(issuer_pk: G1Affine) -> Self {
+        CertificateStore { issuer_pk, certs: BTreeMap::new() }
+    }
+
+    pub fn issuer_pk(&self) -> G1Affine {
+        self.issuer_pk
+    }
+
+    /// Adds `cert` if it carries a valid signature of the issuer. Expired
+    /// certificates are kept, `lookup` skips them.
+    pub fn add(&mut self, cert: Certificate) -> Result<(), Error> {
+        if !bls::verify(&self.issuer_pk, CERTIFICATE_DST, &cert.tbs_bytes(), &cert.signature) {
+            return Err(Error::InvalidSignature);
+        }
+        self.certs.entry(cert.identity.clone()).or_default().push(cert);
+        Ok(())
+    }
+
+    /// The most recently added certificate of `identity` valid at `now`.
+    pub fn lookup(&self, identity: &str, now: u64) -> Option<&Certificate> {
+        self.certs
+            .get(identity)?
+            .iter()
+            .rev()
+            .find(|cert| now >= cert.not_before && now < cert.not_after)
+    }
+}
+
+impl Sender {
+    /// Encrypts `m` to the key certified for `identity` at `now` and tags it
+    /// under `identity_aad(identity, aad)`.
+    pub fn send_to_identity(
+        &self,
+        m: Message,
+        identity: &str,
+        store: &CertificateStore,
+        now: u64,
+        aad: &[u8],
+    ) -> Result<Blob, Error> {
+        let cert = store.lookup(identity, now).ok_or(Error::UnknownIdentity)?;
+        let c = self.send(m, &Receiver::new(cert.pk));
+        let s = self.authenticate(&c, &identity_aad(identity, aad));
+        Ok(Blob { sender_pk: self.pk, c, s, rec_pk: cert.pk })
+    }
+}
+
+impl Auditor {
+    /// Checks that `blob` is addressed to the key certified for `identity` at
+    /// `now` and that its tag covers the identity.
+    pub fn check_identity(
+        &self,
+        store: &CertificateStore,
+        identity: &str,
+        now: u64,
+        blob: &Blob,
+        aad: &[u8],
+    ) -> Result<(), Error> {
+        let cert = store.lookup(identity, now).ok_or(Error::UnknownIdentity)?;
+        if cert.pk != blob.rec_pk {
+            return Err(Error::IdentityMismatch);
+        }
+        if !self.verify(blob.sender_pk, &blob.c, blob.s, &identity_aad(identity, aad)) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use ark_ff::UniformRand;
+
+    use crate::SchemeConfig;
+
+    fn issuer() -> Auditor {
+        Auditor::with_key(SchemeConfig::default(), Fr::rand(&mut ark_std::test_rng()))
+    }
+
+    fn other_issuer() -> Auditor {
+        Auditor::with_key(SchemeConfig::default(), Fr::from(7u64))
+    }
+
+    #[test]
+    fn certificates_round_trip() {
+        let auditor = issuer();
+        let cert = auditor.issue_certificate("alice", bls::public_key(&Fr::from(1u64)), 10, 20).unwrap();
+        let bytes = cert.to_bytes();
+        assert_eq!(Certificate::from_bytes(&bytes).unwrap(), cert);
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(Certificate::from_bytes(&trailing), Err(Error::InvalidEncoding)));
+        assert!(matches!(Certificate::from_bytes(&bytes[..bytes.len() - 1]), Err(Error::InvalidEncoding)));
+        assert!(matches!(Certificate::from_bytes(&[]), Err(Error::InvalidEncoding)));
+        // an identity length past the end of the input
+        let mut long = bytes;
+        long[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(Certificate::from_bytes(&long), Err(Error::InvalidEncoding)));
+    }
+
+    #[test]
+    fn certificates_verify_in_their_window_only() {
+        let auditor = issuer();
+        let cert = auditor.issue_certificate("alice", bls::public_key(&Fr::from(1u64)), 10, 20).unwrap();
+        let issuer_pk = auditor.public_key().unwrap();
+        cert.verify(&issuer_pk, 10).unwrap();
+        cert.verify(&issuer_pk, 19).unwrap();
+        assert!(matches!(cert.verify(&issuer_pk, 9), Err(Error::ExpiredKey)));
+        assert!(matches!(cert.verify(&issuer_pk, 20), Err(Error::ExpiredKey)));
+
+        assert!(matches!(cert.verify(&other_issuer().public_key().unwrap(), 15), Err(Error::InvalidSignature)));
+        let mut moved = cert.clone();
+        moved.not_after = 30;
+        assert!(matches!(moved.verify(&issuer_pk, 15), Err(Error::InvalidSignature)));
+        assert!(matches!(Auditor::default().issue_certificate("alice", cert.pk, 10, 20), Err(Error::MissingKey)));
+    }
+
+    #[test]
+    fn store_rejects_forged_certificates() {
+        let auditor = issuer();
+        let mut store = CertificateStore::new(auditor.public_key().unwrap());
+        let pk = bls::public_key(&Fr::from(1u64));
+        let forged = other_issuer().issue_certificate("alice", pk, 0, 100).unwrap();
+        assert!(matches!(store.add(forged), Err(Error::InvalidSignature)));
+        let mut renamed = auditor.issue_certificate("alice", pk, 0, 100).unwrap();
+        renamed.identity = "mallory".into();
+        assert!(matches!(store.add(renamed), Err(Error::InvalidSignature)));
+        assert_eq!(store.lookup("alice", 50), None);
+        assert_eq!(store.lookup("mallory", 50), None);
+
+        let cert = auditor.issue_certificate("alice", pk, 0, 100).unwrap();
+        store.add(cert.clone()).unwrap();
+        assert_eq!(store.lookup("alice", 50), Some(&cert));
+        assert_eq!(store.lookup("alice", 100), None);
+    }
+
+    // A store certifying `alice` and `bob` over [0, 100), a sender and a blob
+    // for `alice` tagged under `aad`.
+    fn network() -> (CertificateStore, Sender, Blob) {
+        let rng = &mut ark_std::test_rng();
+        let auditor = issuer();
+        let mut store = CertificateStore::new(auditor.public_key().unwrap());
+        for name in ["alice", "bob"] {
+            store.add(auditor.issue_certificate(name, bls::public_key(&Fr::rand(rng)), 0, 100).unwrap()).unwrap();
+        }
+        let sender = Sender::new(Fr::rand(rng));
+        let blob = sender.send_to_identity(Message(bls::public_key(&Fr::rand(rng))), "alice", &store, 50, b"aad");
+        (store, sender, blob.unwrap())
+    }
+
+    #[test]
+    fn blobs_check_against_their_identity() {
+        let (store, sender, blob) = network();
+        let auditor = Auditor::default();
+        assert!(blob.rec_pk == store.lookup("alice", 50).unwrap().pk);
+        auditor.check_identity(&store, "alice", 50, &blob, b"aad").unwrap();
+        assert!(matches!(auditor.check_identity(&store, "bob", 50, &blob, b"aad"), Err(Error::IdentityMismatch)));
+        let other_aad = auditor.check_identity(&store, "alice", 50, &blob, b"other");
+        assert!(matches!(other_aad, Err(Error::InvalidSignature)));
+        assert!(matches!(auditor.check_identity(&store, "carol", 50, &blob, b"aad"), Err(Error::UnknownIdentity)));
+        assert!(matches!(auditor.check_identity(&store, "alice", 100, &blob, b"aad"), Err(Error::UnknownIdentity)));
+
+        let m = Message(bls::public_key(&Fr::from(1u64)));
+        assert!(matches!(sender.send_to_identity(m, "carol", &store, 50, b"aad"), Err(Error::UnknownIdentity)));
+    }
+
+    #[test]
+    fn tags_without_the_identity_fail() {
+        let (store, sender, mut blob) = network();
+        blob.s = sender.authenticate(&blob.c, b"aad");
+        let plain = Auditor::default().check_identity(&store, "alice", 50, &blob, b"aad");
+        assert!(matches!(plain, Err(Error::InvalidSignature)));
+        // the identity tag is the plain tag under `identity_aad`
+        blob.s = sender.authenticate(&blob.c, &identity_aad("alice", b"aad"));
+        Auditor::default().check_identity(&store, "alice", 50, &blob, b"aad").unwrap();
+    }
+}
@@ -0,0 +1,58 @@
+//! Compressed encodings of G2 and GT elements for archives.
+//!
+//! G2 points use the standard compressed encoding (96 bytes instead of 192).
+//! GT elements are compressed with the torus T2: a pairing output
+//! `x = c0 + c1 * w` has norm one over Fq6, so it is determined by
+//! `g = (1 + c0) / c1` through `x = (g + w) / (g - w)`. That halves the 576
+//! bytes of an Fq12 element to the 288 of an Fq6 one; the identity, the only
+//! element of GT with `c1 = 0`, is encoded as `g = 0`.
+//!
+//! Decompressing costs an Fq12 inversion, plus an exponentiation by the group
+//! order for the subgroup check; `benches/compression.rs` measures both
+//! against the uncompressed encodings.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Bls12_381, Fq12, Fq6, Fr, G2Affine};
+use ark_ec::pairing::PairingOutput;
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+
+use crate::Error;
+
+pub const COMPRESSED_G2_LEN: usize = 96;
+pub const COMPRESSED_GT_LEN: usize = 288;
+
+pub fn compress_g2(p: &G2Affine) -> Vec<u8> {
+    let mut out = Vec::with_capacity(COMPRESSED_G2_LEN);
+    p.serialize_compressed(&mut out).unwrap();
+    out
+}
+
+/// Fails with `InvalidEncoding` unless `bytes` is a compressed point of the
+/// prime-order subgroup.
+pub fn decompress_g2(bytes: &[u8]) -> Result<G2Affine, Error> {
+    G2Affine::deserialize_compressed(bytes).map_err(|_| Error::InvalidEncoding)
+}
+
+pub fn compress_gt(x: &PairingOutput<Bls12_381>) -> Vec<u8> {
+    let x = x.0;
+    let g = if x.c1.is_zero() { Fq6::zero() } else { (Fq6::one() + x.c0) * x.c1.inverse().unwrap() };
+    let mut out = Vec::with_capacity(COMPRESSED_GT_LEN);
+    g.serialize_compressed(&mut out).unwrap();
+    out
+}
+
+/// Fails with `InvalidEncoding` if `bytes` is not an encoded Fq6 element or,
+/// with `validate`, if the decompressed value is not in GT.
+pub fn decompress_gt(bytes: &[u8], validate: Validate) -> Result<PairingOutput<Bls12_381>, Error> {
+    let g = Fq6::deserialize_with_mode(bytes, Compress::Yes, Validate::Yes).map_err(|_| Error::InvalidEncoding)?;
+    if g.is_zero() {
+        return Ok(PairingOutput(Fq12::one()));
+    }
+    // g - w is never zero: w^2 = v is not a square in Fq6
+    let x = Fq12::new(g, Fq6::one()) * Fq12::new(g, -Fq6::one()).inverse().unwrap();
+    if validate == Validate::Yes && !x.pow(Fr::MODULUS).is_one() {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(PairingOutput(x))
+}
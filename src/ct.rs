@@ -0,0 +1,135 @@
+//! Constant-time message-space lookup and decryption.
+//!
+//! `CtEq` compares field elements limb by limb on their (always reduced)
+//! Montgomery representation, and points and pairing outputs coordinate by
+//! coordinate, without branching on the values. `lookup` scans the whole
+//! candidate list and selects the matching index with `subtle`, so the time
+//! taken doesn't depend on where (or whether) the plaintext sits in the
+//! message space; `decrypt` is the receiver's path through it.
+//!
+//! This covers comparisons and selection only: the arkworks scalar
+//! multiplication and affine normalization `decrypt` relies on are not
+//! constant-time.
+
+use ark_bls12_381::{Fr, G1Projective};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::{CubicExtConfig, CubicExtField, Fp, FpConfig, QuadExtConfig, QuadExtField};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use crate::{ElGamal, Message};
+
+/// Equality that doesn't branch on the values compared.
+pub trait CtEq {
+    fn ct_eq(&self, other: &Self) -> Choice;
+}
+
+impl<P: FpConfig<N>, const N: usize> CtEq for Fp<P, N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0 .0[..].ct_eq(&other.0 .0[..])
+    }
+}
+
+impl<P: QuadExtConfig> CtEq for QuadExtField<P>
+where
+    P::BaseField: CtEq,
+{
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1)
+    }
+}
+
+impl<P: CubicExtConfig> CtEq for CubicExtField<P>
+where
+    P::BaseField: CtEq,
+{
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2)
+    }
+}
+
+impl<P: SWCurveConfig> CtEq for Affine<P>
+where
+    P::BaseField: CtEq,
+{
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.x.ct_eq(&other.x)
+            & self.y.ct_eq(&other.y)
+            & Choice::from(self.infinity as u8).ct_eq(&Choice::from(other.infinity as u8))
+    }
+}
+
+impl<P: Pairing> CtEq for PairingOutput<P>
+where
+    P::TargetField: CtEq,
+{
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl CtEq for Message {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+/// Index of `target` in `candidates`, found with a fixed-iteration scan.
+///
+/// Works for messages as well as for paired messages (`PairingOutput`) in the
+/// attack. If `target` appears more than once the last index is returned.
+pub fn lookup<T: CtEq>(candidates: &[T], target: &T) -> CtOption<u64> {
+    let mut index = 0u64;
+    let mut found = Choice::from(0);
+    for (i, candidate) in candidates.iter().enumerate() {
+        let eq = candidate.ct_eq(target);
+        index.conditional_assign(&(i as u64), eq);
+        found |= eq;
+    }
+    CtOption::new(index, found)
+}
+
+pub fn lookup_message(space: &[Message], m: &Message) -> CtOption<u64> {
+    lookup(space, m)
+}
+
+/// Decrypts `c` with the receiver's secret key `sk`, `c.1 - sk * c.0`, and
+/// looks the plaintext up in `space`.
+pub fn decrypt(sk: &Fr, c: &ElGamal, space: &[Message]) -> CtOption<u64> {
+    let m = Message((G1Projective::from(c.1) - c.0 * sk).into_affine());
+    lookup_message(space, &m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Receiver, Sender};
+    use ark_bls12_381::G2Affine;
+    use ark_ec::AffineRepr;
+    use ark_ff::UniformRand;
+
+    #[test]
+    fn ct_eq_agrees_with_eq() {
+        let g = G2Affine::generator();
+        assert!(bool::from(g.ct_eq(&g)));
+        assert!(!bool::from(g.ct_eq(&(g + g).into_affine())));
+        assert!(!bool::from(g.ct_eq(&G2Affine::identity())));
+        let gt = ark_bls12_381::Bls12_381::pairing(crate::Message::from_exponent(3).0, g);
+        assert!(bool::from(gt.ct_eq(&gt)));
+        assert!(!bool::from(gt.ct_eq(&(gt + gt))));
+    }
+
+    #[test]
+    fn decrypt_finds_the_plaintext() {
+        let rng = &mut ark_std::test_rng();
+        let space: alloc::vec::Vec<Message> = (1..=10).map(Message::from_exponent).collect();
+        let rec_sk = Fr::rand(rng);
+        let receiver = Receiver::new(crate::bls::public_key(&rec_sk));
+        let c = Sender::new(Fr::rand(rng)).send(space[6], &receiver);
+        assert_eq!(Option::<u64>::from(decrypt(&rec_sk, &c, &space)), Some(6));
+        assert!(bool::from(decrypt(&Fr::rand(rng), &c, &space).is_none()));
+    }
+}
@@ -0,0 +1,116 @@
+//! Explains why a serialized blob fails to parse.
+//!
+//! Challenge files get truncated by downloads or re-encoded by other tools;
+//! instead of a bare deserialization error, `diagnose_blob` names the field
+//! that failed, where it starts and what is wrong with it.
+
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
+use core::fmt;
+
+use crate::{point_audit::PointAudit, Blob};
+
+const G1_LEN: usize = 96;
+const G2_LEN: usize = 192;
+
+/// Length of an uncompressed blob, the format of `blob.bin`.
+pub const BLOB_LEN: usize = 4 * G1_LEN + G2_LEN;
+/// Length of a compressed blob.
+pub const COMPRESSED_BLOB_LEN: usize = BLOB_LEN / 2;
+
+// fields in serialization order, with their uncompressed lengths
+const FIELDS: [(&str, usize); 5] =
+    [("sender_pk", G1_LEN), ("c.0", G1_LEN), ("c.1", G1_LEN), ("s", G2_LEN), ("rec_pk", G1_LEN)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    /// The data ends inside the field.
+    Truncated,
+    /// A coordinate is not below the field modulus, or the flag bits are invalid.
+    BadEncoding,
+    NotOnCurve,
+    NotInSubgroup,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub field: &'static str,
+    /// Byte offset of the field in the data.
+    pub offset: usize,
+    pub expected_len: usize,
+    /// Bytes of the field present in the data.
+    pub actual_len: usize,
+    pub problem: Problem,
+    pub data_len: usize,
+    /// The data has the length of a compressed blob and parses as one.
+    pub looks_compressed: bool,
+}
+
+fn check_point(bytes: &[u8], g2: bool) -> Option<Problem> {
+    let audit = if g2 {
+        G2Affine::deserialize_uncompressed_unchecked(bytes).map(|p| PointAudit::analyze(&p))
+    } else {
+        G1Affine::deserialize_uncompressed_unchecked(bytes).map(|p| PointAudit::analyze(&p))
+    };
+    match audit {
+        Err(_) => Some(Problem::BadEncoding),
+        Ok(audit) if !audit.on_curve => Some(Problem::NotOnCurve),
+        Ok(audit) if !audit.in_subgroup => Some(Problem::NotInSubgroup),
+        Ok(_) => None,
+    }
+}
+
+/// The first problem found in `data` read as an uncompressed blob, or `None`
+/// if it parses.
+pub fn diagnose_blob(data: &[u8]) -> Option<Diagnostic> {
+    let looks_compressed = data.len() == COMPRESSED_BLOB_LEN
+        && Blob::deserialize_with_mode(data, Compress::Yes, Validate::Yes).is_ok();
+
+    let mut offset = 0;
+    for (field, expected_len) in FIELDS {
+        let actual_len = data.len().saturating_sub(offset).min(expected_len);
+        let problem = if actual_len < expected_len {
+            Some(Problem::Truncated)
+        } else {
+            check_point(&data[offset..offset + expected_len], expected_len == G2_LEN)
+        };
+        if let Some(problem) = problem {
+            return Some(Diagnostic {
+                field,
+                offset,
+                expected_len,
+                actual_len,
+                problem,
+                data_len: data.len(),
+                looks_compressed,
+            });
+        }
+        offset += expected_len;
+    }
+    None
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Diagnostic { field, offset, expected_len, actual_len, .. } = self;
+        match self.problem {
+            Problem::Truncated => write!(
+                f,
+                "data ends inside field `{field}` at byte offset {offset}: expected {expected_len} bytes, found \
+                 {actual_len} ({} of {BLOB_LEN} bytes, truncated)",
+                self.data_len
+            )?,
+            Problem::BadEncoding => {
+                write!(f, "field `{field}` at byte offset {offset} is not a valid uncompressed point encoding")?
+            }
+            Problem::NotOnCurve => write!(f, "field `{field}` at byte offset {offset} is not on the curve")?,
+            Problem::NotInSubgroup => {
+                write!(f, "field `{field}` at byte offset {offset} is not in the prime-order subgroup")?
+            }
+        }
+        if self.looks_compressed {
+            write!(f, "; the data is a compressed blob, re-encode it uncompressed")?;
+        }
+        Ok(())
+    }
+}
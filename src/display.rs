@@ -0,0 +1,169 @@
+//! Human-readable `Display` and `Debug` output.
+//!
+//! Points print as hex of their compressed encoding: truncated for `Display`,
+//! in full for `Debug`. `Blob::verbose` prints affine coordinates instead.
+//! Secret keys are never printed.
+
+use alloc::string::String;
+use ark_bls12_381::{Bls12_381, Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::pairing::PairingOutput;
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use core::fmt;
+
+use crate::{hex, Blob, ElGamal, Message, Receiver, Sender};
+
+const SHORT_HEX_LEN: usize = 16;
+
+pub fn full_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = alloc::vec::Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    hex::encode(&bytes)
+}
+
+pub fn short_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut s = full_hex(value);
+    s.truncate(SHORT_HEX_LEN);
+    s.push_str("..");
+    s
+}
+
+/// A pairing output, displayed like the points.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gt(pub PairingOutput<Bls12_381>);
+
+impl fmt::Display for Gt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Gt({})", short_hex(&self.0))
+    }
+}
+
+impl fmt::Debug for Gt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Gt({})", full_hex(&self.0))
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Message({})", short_hex(&self.0))
+    }
+}
+
+impl fmt::Debug for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Message({})", full_hex(&self.0))
+    }
+}
+
+impl fmt::Display for ElGamal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ElGamal({}, {})", short_hex(&self.0), short_hex(&self.1))
+    }
+}
+
+impl fmt::Debug for ElGamal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ElGamal({}, {})", full_hex(&self.0), full_hex(&self.1))
+    }
+}
+
+impl fmt::Display for Sender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sender(pk: {})", short_hex(&self.pk))
+    }
+}
+
+impl fmt::Debug for Sender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sender {{ sk: <redacted>, pk: {} }}", full_hex(&self.pk))
+    }
+}
+
+impl fmt::Display for Receiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Receiver(pk: {})", short_hex(&self.pk()))
+    }
+}
+
+impl fmt::Debug for Receiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Receiver {{ pk: {} }}", full_hex(&self.pk()))
+    }
+}
+
+impl fmt::Display for Blob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Blob(sender_pk: {}, c: {}, s: {}, rec_pk: {})",
+            short_hex(&self.sender_pk),
+            self.c,
+            short_hex(&self.s),
+            short_hex(&self.rec_pk)
+        )
+    }
+}
+
+impl fmt::Debug for Blob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Blob")
+            .field("sender_pk", &full_hex(&self.sender_pk))
+            .field("c", &self.c)
+            .field("s", &full_hex(&self.s))
+            .field("rec_pk", &full_hex(&self.rec_pk))
+            .finish()
+    }
+}
+
+fn fq_hex(x: &Fq) -> String {
+    hex::encode(&x.into_bigint().to_bytes_be())
+}
+
+fn fq2_hex(x: &Fq2) -> String {
+    alloc::format!("({}, {})", fq_hex(&x.c0), fq_hex(&x.c1))
+}
+
+pub(crate) struct G1Coords<'a>(pub &'a G1Affine);
+
+impl fmt::Display for G1Coords<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.infinity {
+            write!(f, "infinity")
+        } else {
+            write!(f, "x = {}, y = {}", fq_hex(&self.0.x), fq_hex(&self.0.y))
+        }
+    }
+}
+
+pub(crate) struct G2Coords<'a>(pub &'a G2Affine);
+
+impl fmt::Display for G2Coords<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.infinity {
+            write!(f, "infinity")
+        } else {
+            write!(f, "x = {}, y = {}", fq2_hex(&self.0.x), fq2_hex(&self.0.y))
+        }
+    }
+}
+
+/// Multi-line view of a blob with the affine coordinates of every point.
+pub struct Verbose<'a>(&'a Blob);
+
+impl Blob {
+    pub fn verbose(&self) -> Verbose<'_> {
+        Verbose(self)
+    }
+}
+
+impl fmt::Display for Verbose<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let blob = self.0;
+        writeln!(f, "sender_pk: {}", G1Coords(&blob.sender_pk))?;
+        writeln!(f, "c.0:       {}", G1Coords(&blob.c.0))?;
+        writeln!(f, "c.1:       {}", G1Coords(&blob.c.1))?;
+        writeln!(f, "s:         {}", G2Coords(&blob.s))?;
+        writeln!(f, "rec_pk:    {}", G1Coords(&blob.rec_pk))
+    }
+}
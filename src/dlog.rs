@@ -0,0 +1,324 @@
+//! Bounded discrete-log search in any prime-order group, e.g. recovering `m`
+//! from `e(g1, H(c))^m` in the pairing attack.
+//!
+//! Exponents are `u128` (`i128` for signed searches); interval arithmetic
+//! saturates instead of overflowing near the ends of the range. Partial
+//! knowledge of the exponent, as `Congruence`s combined by `combine`,
+//! restricts the search to one residue class.
+
+use ark_ec::Group;
+use core::ops::Range;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Number of consecutive exponents scanned by one unit of work.
+pub const BATCH_SIZE: u128 = 1 << 16;
+
+fn limbs(x: u128) -> [u64; 2] {
+    [x as u64, (x >> 64) as u64]
+}
+
+// x*base for x in range, by repeated addition from a single scalar multiplication
+fn scan<G: Group>(base: &G, target: &G, range: Range<u128>) -> Option<u128> {
+    let mut current = base.mul_bigint(limbs(range.start));
+    for x in range {
+        if current == *target {
+            return Some(x);
+        }
+        current += base;
+    }
+    None
+}
+
+// x*base == target or x*base == -target, as +x or -x
+fn scan_signed<G: Group>(base: &G, target: &G, range: Range<u128>) -> Option<i128> {
+    let neg_target = -*target;
+    let mut current = base.mul_bigint(limbs(range.start));
+    for x in range {
+        if current == *target {
+            return Some(x as i128);
+        }
+        if current == neg_target {
+            return Some(-(x as i128));
+        }
+        current += base;
+    }
+    None
+}
+
+#[cfg(feature = "parallel")]
+fn find_batch<T: Send, F: Fn(u128) -> Option<T> + Send + Sync>(batches: u128, search: F) -> Option<T> {
+    (0..batches).into_par_iter().find_map_first(search)
+}
+
+#[cfg(not(feature = "parallel"))]
+fn find_batch<T, F: Fn(u128) -> Option<T>>(batches: u128, search: F) -> Option<T> {
+    (0..batches).find_map(search)
+}
+
+/// Smallest `x` in `range` with `x * base == target`.
+///
+/// Uses one addition per candidate and no memory, which beats BSGS for small
+/// ranges. The range is split into batches of `BATCH_SIZE` exponents that are
+/// searched in parallel with the `parallel` feature.
+pub fn brute_force<G: Group>(base: &G, target: &G, range: Range<u128>) -> Option<u128> {
+    let batches = range.end.saturating_sub(range.start).div_ceil(BATCH_SIZE);
+    find_batch(batches, |i| {
+        let start = range.start + i * BATCH_SIZE;
+        let end = start.saturating_add(BATCH_SIZE).min(range.end);
+        scan(base, target, start..end)
+    })
+}
+
+/// `x` in `[-bound, bound]` with `x * base == target`, smallest `|x|` first.
+///
+/// Homomorphically combined ciphertexts can carry negative exponents, i.e.
+/// `q - |x|` for the group order `q`; both signs are checked at every step
+/// so this costs the same as `brute_force` over `0..=bound`. `bound` is
+/// capped at `i128::MAX`.
+pub fn brute_force_signed<G: Group>(base: &G, target: &G, bound: u128) -> Option<i128> {
+    let end = bound.min(i128::MAX as u128) + 1;
+    find_batch(end.div_ceil(BATCH_SIZE), |i| {
+        let start = i * BATCH_SIZE;
+        scan_signed(base, target, start..start.saturating_add(BATCH_SIZE).min(end))
+    })
+}
+
+/// Partial knowledge of an exponent: `x ≡ residue (mod modulus)`, e.g. its
+/// parity or its low bits leaked by a side channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Congruence {
+    pub residue: u128,
+    pub modulus: u128,
+}
+
+impl Congruence {
+    /// `None` if `modulus` is zero.
+    pub fn new(residue: u128, modulus: u128) -> Option<Self> {
+        (modulus != 0).then(|| Congruence { residue: residue % modulus, modulus })
+    }
+
+    /// The low `bits` bits of `x` are those of `value`. `None` for `bits >= 128`.
+    pub fn low_bits(bits: u32, value: u128) -> Option<Self> {
+        Self::new(value, 1u128.checked_shl(bits)?)
+    }
+
+    pub fn parity(odd: bool) -> Self {
+        Congruence { residue: odd as u128, modulus: 2 }
+    }
+
+    pub fn contains(&self, x: u128) -> bool {
+        x % self.modulus == self.residue
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// a * b mod m for a, b < m, by double-and-add so nothing overflows
+fn mul_mod(a: u128, mut b: u128, m: u128) -> u128 {
+    let add = |x: u128, y: u128| if x >= m - y { x - (m - y) } else { x + y };
+    let (mut acc, mut a) = (0, a);
+    while b != 0 {
+        if b & 1 == 1 {
+            acc = add(acc, a);
+        }
+        a = add(a, a);
+        b >>= 1;
+    }
+    acc
+}
+
+// a^-1 mod m, by the extended Euclidean algorithm with coefficients kept mod m
+fn inv_mod(a: u128, m: u128) -> Option<u128> {
+    if m == 1 {
+        return Some(0);
+    }
+    let (mut old_r, mut r) = (a % m, m);
+    let (mut old_s, mut s) = (1u128, 0u128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        let qs = mul_mod(q % m, s, m);
+        (old_s, s) = (s, if old_s >= qs { old_s - qs } else { m - (qs - old_s) });
+    }
+    (old_r == 1).then_some(old_s)
+}
+
+/// The congruence satisfied by exactly the exponents satisfying all of
+/// `known`, by the Chinese remainder theorem (moduli need not be coprime).
+/// `None` if they contradict each other or the combined modulus overflows
+/// `u128`.
+pub fn combine(known: &[Congruence]) -> Option<Congruence> {
+    known.iter().try_fold(Congruence { residue: 0, modulus: 1 }, |a, b| {
+        let g = gcd(a.modulus, b.modulus);
+        if a.residue % g != b.residue % g {
+            return None;
+        }
+        let (m1, m2) = (a.modulus / g, b.modulus / g);
+        // a.residue + a.modulus * k ≡ b.residue (mod b.modulus), solved for k mod m2
+        let r1 = a.residue % b.modulus;
+        let diff = (if b.residue >= r1 { b.residue - r1 } else { b.modulus - (r1 - b.residue) }) / g;
+        let k = mul_mod(diff % m2, inv_mod(m1 % m2, m2)?, m2);
+        let modulus = a.modulus.checked_mul(m2)?;
+        Some(Congruence { residue: a.residue + a.modulus * k, modulus })
+    })
+}
+
+/// Smallest `x` in `range` with `x * base == target` and `x` in `class`.
+///
+/// Writing `x = r + k * M` turns the search into `k * (M * base) ==
+/// target - r * base`, so only one exponent in `M` is tried: knowing the low
+/// 8 bits makes the search 256 times faster.
+pub fn brute_force_congruent<G: Group>(base: &G, target: &G, range: Range<u128>, class: Congruence) -> Option<u128> {
+    let Congruence { residue: r, modulus: m } = class;
+    if range.end <= r {
+        return None;
+    }
+    let first = if range.start <= r { 0 } else { (range.start - r).div_ceil(m) };
+    let last = (range.end - r).div_ceil(m);
+    let step = base.mul_bigint(limbs(m));
+    let shifted = *target - base.mul_bigint(limbs(r));
+    brute_force(&step, &shifted, first..last).map(|k| r + k * m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Fr, G1Projective};
+    use ark_ff::Zero;
+
+    fn g() -> G1Projective {
+        G1Projective::generator()
+    }
+
+    fn times(x: u128) -> G1Projective {
+        g() * Fr::from(x)
+    }
+
+    #[test]
+    fn brute_force_finds_exponents() {
+        let x = 2 * BATCH_SIZE + 17;
+        assert_eq!(brute_force(&g(), &times(x), 0..3 * BATCH_SIZE), Some(x));
+        assert_eq!(brute_force(&g(), &times(999), 100..1000), Some(999));
+        assert_eq!(brute_force(&g(), &times(600), 500..700), Some(600));
+        assert_eq!(brute_force(&g(), &times(0), 0..10), Some(0));
+    }
+
+    #[test]
+    fn brute_force_misses() {
+        assert_eq!(brute_force(&g(), &times(1000), 0..1000), None);
+        assert_eq!(brute_force(&g(), &times(400), 500..700), None);
+        assert_eq!(brute_force(&g(), &times(5), 5..5), None);
+        assert_eq!(brute_force(&g(), &times(5), 10..5), None);
+    }
+
+    // Every exponent matches the identity: with or without `parallel`, the
+    // first batch's answer wins.
+    #[test]
+    fn brute_force_returns_the_smallest_exponent() {
+        let zero = G1Projective::zero();
+        assert_eq!(brute_force(&zero, &zero, 7..2 * BATCH_SIZE + 5), Some(7));
+        assert_eq!(brute_force(&zero, &zero, BATCH_SIZE + 1..3 * BATCH_SIZE), Some(BATCH_SIZE + 1));
+    }
+
+    #[test]
+    fn brute_force_signed_finds_both_signs() {
+        assert_eq!(brute_force_signed(&g(), &times(42), 100), Some(42));
+        assert_eq!(brute_force_signed(&g(), &-times(42), 100), Some(-42));
+        assert_eq!(brute_force_signed(&g(), &G1Projective::zero(), 100), Some(0));
+        assert_eq!(brute_force_signed(&g(), &times(100), 100), Some(100));
+        assert_eq!(brute_force_signed(&g(), &-times(100), 100), Some(-100));
+        assert_eq!(brute_force_signed(&g(), &times(101), 100), None);
+        assert_eq!(brute_force_signed(&g(), &-times(101), 100), None);
+    }
+
+    #[test]
+    fn brute_force_signed_crosses_batches() {
+        let bound = BATCH_SIZE + 10;
+        assert_eq!(brute_force_signed(&g(), &-times(BATCH_SIZE + 3), bound), Some(-(BATCH_SIZE as i128 + 3)));
+        assert_eq!(brute_force_signed(&g(), &times(BATCH_SIZE), bound), Some(BATCH_SIZE as i128));
+        assert_eq!(brute_force_signed(&g(), &times(bound), bound), Some(bound as i128));
+        assert_eq!(brute_force_signed(&g(), &times(bound + 1), bound), None);
+    }
+
+    #[test]
+    fn exponents_above_2_64() {
+        let x = (1u128 << 64) + 5;
+        assert_eq!(brute_force(&g(), &times(x), 1 << 64..(1 << 64) + 100), Some(x));
+        assert_eq!(brute_force(&g(), &times(5), 1 << 64..(1 << 64) + 100), None);
+        let top = u128::MAX - 3;
+        assert_eq!(brute_force(&g(), &times(top), u128::MAX - 10..u128::MAX), Some(top));
+        assert_eq!(g().mul_bigint(limbs(x)), times(x));
+    }
+
+    #[test]
+    fn messages_from_large_exponents() {
+        use crate::Message;
+        use ark_ec::CurveGroup;
+
+        for x in [u64::MAX as u128, 1 << 64, (1 << 64) + 5, u128::MAX] {
+            assert!(Message::from_exponent(x).0 == g().mul_bigint(limbs(x)).into_affine());
+        }
+        // 2^64 is not the wrapped 0 or the low limb alone
+        assert!(Message::from_exponent(1 << 64) != Message::from_exponent(0));
+        assert_eq!(times(1 << 64), times(1 << 32) * Fr::from(1u64 << 32));
+    }
+
+    fn class(residue: u128, modulus: u128) -> Congruence {
+        Congruence::new(residue, modulus).unwrap()
+    }
+
+    #[test]
+    fn combine_coprime_and_shared_moduli() {
+        assert_eq!(combine(&[class(2, 3), class(3, 5)]), Some(class(8, 15)));
+        assert_eq!(combine(&[class(2, 4), class(4, 6)]), Some(class(10, 12)));
+        assert_eq!(combine(&[class(3, 8), class(11, 16), class(1, 3)]), Some(class(43, 48)));
+        assert_eq!(combine(&[class(5, 6), class(5, 6)]), Some(class(5, 6)));
+        assert_eq!(combine(&[]), Some(class(0, 1)));
+        // moduli close to 2^64 and 2^127, where a * b mod m needs `mul_mod`
+        let large = [((1 << 64) + 13, (1 << 63) - 25, (1 << 100) + 777), ((1 << 127) - 1, 2, (1 << 126) + 12345)];
+        for (m1, m2, x) in large {
+            assert_eq!(combine(&[class(x, m1), class(x, m2)]), Some(class(x, m1 * m2)));
+        }
+    }
+
+    #[test]
+    fn combine_rejects_contradictions_and_overflow() {
+        assert_eq!(combine(&[class(1, 4), class(2, 6)]), None);
+        assert_eq!(combine(&[class(0, 2), class(1, 2)]), None);
+        assert_eq!(combine(&[class(0, 1 << 100), class(1, (1 << 100) - 1)]), None);
+        assert_eq!(Congruence::new(1, 0), None);
+    }
+
+    #[test]
+    fn low_bits_and_parity() {
+        assert_eq!(Congruence::low_bits(128, 5), None);
+        assert_eq!(Congruence::low_bits(8, 0x1ff), Some(class(0xff, 256)));
+        assert_eq!(Congruence::low_bits(127, u128::MAX), Some(class(u128::MAX >> 1, 1 << 127)));
+        assert_eq!(Congruence::low_bits(0, 7), Some(class(0, 1)));
+        assert!(Congruence::parity(true).contains(7) && !Congruence::parity(true).contains(8));
+    }
+
+    #[test]
+    fn brute_force_congruent_agrees_with_brute_force() {
+        // start above the residue, end at or below it, and a match below the range
+        for (x, class, range) in [
+            (181, class(5, 16), 100..300),
+            (50, class(50, 64), 0..51),
+            (50, class(50, 64), 0..50),
+            (20, class(4, 16), 30..100),
+            (3 * BATCH_SIZE + 7, class(7, 1 << 10), BATCH_SIZE..4 * BATCH_SIZE),
+        ] {
+            let expected = brute_force(&g(), &times(x), range.clone());
+            assert_eq!(brute_force_congruent(&g(), &times(x), range, class), expected);
+        }
+        assert_eq!(brute_force_congruent(&g(), &times(50), 0..50, class(50, 64)), None);
+        // a class that excludes the exponent finds nothing
+        assert_eq!(brute_force_congruent(&g(), &times(181), 100..300, class(6, 16)), None);
+    }
+}
@@ -0,0 +1,116 @@
+//! Point encodings of the EIP-2537 BLS12-381 precompiles.
+//!
+//! A base field element takes 64 bytes: 16 zero bytes followed by its 48-byte
+//! big-endian value. A G1 point is `x || y` (128 bytes), a G2 point is
+//! `x.c0 || x.c1 || y.c0 || y.c1` (256 bytes), and the point at infinity is
+//! all zeros.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::{ElGamal, Error};
+
+pub const FP_LEN: usize = 64;
+pub const G1_LEN: usize = 2 * FP_LEN;
+pub const G2_LEN: usize = 4 * FP_LEN;
+/// Length of one `(G1, G2)` pair in the pairing-check precompile input.
+pub const PAIR_LEN: usize = G1_LEN + G2_LEN;
+
+const FP_PADDING: usize = FP_LEN - 48;
+
+fn encode_fp(f: &Fq, out: &mut Vec<u8>) {
+    out.extend_from_slice(&[0u8; FP_PADDING]);
+    out.extend_from_slice(&f.into_bigint().to_bytes_be());
+}
+
+fn decode_fp(bytes: &[u8]) -> Result<Fq, Error> {
+    let (padding, value) = bytes.split_at(FP_PADDING);
+    if padding.iter().any(|&b| b != 0) {
+        return Err(Error::InvalidEncoding);
+    }
+    let f = Fq::from_be_bytes_mod_order(value);
+    // reject values >= p, which from_be_bytes_mod_order would silently reduce
+    if f.into_bigint().to_bytes_be() != value {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(f)
+}
+
+pub fn encode_g1(p: &G1Affine) -> Vec<u8> {
+    let mut out = Vec::with_capacity(G1_LEN);
+    if p.infinity {
+        out.resize(G1_LEN, 0);
+    } else {
+        encode_fp(&p.x, &mut out);
+        encode_fp(&p.y, &mut out);
+    }
+    out
+}
+
+pub fn encode_g2(p: &G2Affine) -> Vec<u8> {
+    let mut out = Vec::with_capacity(G2_LEN);
+    if p.infinity {
+        out.resize(G2_LEN, 0);
+    } else {
+        for f in [&p.x.c0, &p.x.c1, &p.y.c0, &p.y.c1] {
+            encode_fp(f, &mut out);
+        }
+    }
+    out
+}
+
+/// Decodes a G1 point, rejecting points off the curve or outside the
+/// prime-order subgroup, as the precompiles do.
+pub fn decode_g1(bytes: &[u8]) -> Result<G1Affine, Error> {
+    if bytes.len() != G1_LEN {
+        return Err(Error::InvalidEncoding);
+    }
+    if bytes.iter().all(|&b| b == 0) {
+        return Ok(G1Affine::zero());
+    }
+    let x = decode_fp(&bytes[..FP_LEN])?;
+    let y = decode_fp(&bytes[FP_LEN..])?;
+    let p = G1Affine::new_unchecked(x, y);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(p)
+}
+
+/// Decodes a G2 point, rejecting points off the curve or outside the
+/// prime-order subgroup, as the precompiles do.
+pub fn decode_g2(bytes: &[u8]) -> Result<G2Affine, Error> {
+    if bytes.len() != G2_LEN {
+        return Err(Error::InvalidEncoding);
+    }
+    if bytes.iter().all(|&b| b == 0) {
+        return Ok(G2Affine::zero());
+    }
+    let mut fs = bytes.chunks_exact(FP_LEN).map(decode_fp);
+    let mut next = || fs.next().unwrap();
+    let x = Fq2::new(next()?, next()?);
+    let y = Fq2::new(next()?, next()?);
+    let p = G2Affine::new_unchecked(x, y);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(p)
+}
+
+/// Input for the pairing-check precompile that succeeds iff
+/// `Auditor::check_auth(sender_pk, c, s, aad)` does.
+///
+/// The check `e(g1, s) == e(pk, H(c))` is rewritten as
+/// `e(-g1, s) * e(pk, H(c)) == 1`. `H(c)` is computed here, off-chain.
+pub fn check_auth_input(sender_pk: &G1Affine, c: &ElGamal, s: &G2Affine, aad: &[u8]) -> Vec<u8> {
+    let hash_c = c.hash_to_curve(aad);
+
+    let mut out = Vec::with_capacity(2 * PAIR_LEN);
+    out.extend(encode_g1(&-G1Affine::generator()));
+    out.extend(encode_g2(s));
+    out.extend(encode_g1(sender_pk));
+    out.extend(encode_g2(&hash_c));
+    out
+}
@@ -0,0 +1,107 @@
+//! Rich output in evcxr, the Rust Jupyter kernel.
+//!
+//! evcxr calls an inherent `evcxr_display` method on the value of a cell if
+//! there is one, and renders what it prints between the content markers.
+//! Points show as hex of their compressed encoding with the full value on
+//! hover; audit results are coloured by outcome.
+
+use ark_serialize::CanonicalSerialize;
+use std::fmt::Write;
+
+use crate::{
+    attest::SignedAuditReport,
+    display::{full_hex, short_hex, Gt},
+    hex,
+    point_audit::PointAudit,
+    tamper::Verdict,
+    Blob, ElGamal, Message,
+};
+
+const OK: &str = "#2e7d32";
+const FAIL: &str = "#c62828";
+
+fn point<T: CanonicalSerialize>(value: &T) -> String {
+    format!("<code title=\"{}\">{}</code>", full_hex(value), short_hex(value))
+}
+
+fn flag(ok: bool) -> String {
+    let (color, text) = if ok { (OK, "yes") } else { (FAIL, "no") };
+    format!("<span style=\"color:{color}\">{text}</span>")
+}
+
+// prints an HTML table with a caption and one row per field
+fn table(caption: &str, rows: &[(&str, String)]) {
+    let mut html = format!("<table><caption>{caption}</caption>");
+    for (name, value) in rows {
+        write!(html, "<tr><th style=\"text-align:left\">{name}</th><td>{value}</td></tr>").unwrap();
+    }
+    html.push_str("</table>");
+    println!("EVCXR_BEGIN_CONTENT text/html\n{html}\nEVCXR_END_CONTENT");
+}
+
+impl Blob {
+    pub fn evcxr_display(&self) {
+        table(
+            "Blob",
+            &[
+                ("sender_pk", point(&self.sender_pk)),
+                ("c.0", point(&self.c.0)),
+                ("c.1", point(&self.c.1)),
+                ("s", point(&self.s)),
+                ("rec_pk", point(&self.rec_pk)),
+            ],
+        );
+    }
+}
+
+impl ElGamal {
+    pub fn evcxr_display(&self) {
+        table("ElGamal", &[("c.0", point(&self.0)), ("c.1", point(&self.1))]);
+    }
+}
+
+impl Message {
+    pub fn evcxr_display(&self) {
+        table("Message", &[("m", point(&self.0))]);
+    }
+}
+
+impl Gt {
+    pub fn evcxr_display(&self) {
+        table("Gt", &[("value", point(&self.0))]);
+    }
+}
+
+impl PointAudit {
+    pub fn evcxr_display(&self) {
+        table(
+            "PointAudit",
+            &[
+                ("not identity", flag(!self.is_identity)),
+                ("on curve", flag(self.on_curve)),
+                ("in subgroup", flag(self.in_subgroup)),
+            ],
+        );
+    }
+}
+
+impl Verdict {
+    pub fn evcxr_display(&self) {
+        let color = if *self == Verdict::Valid { OK } else { FAIL };
+        println!("EVCXR_BEGIN_CONTENT text/html\n<b style=\"color:{color}\">{self:?}</b>\nEVCXR_END_CONTENT");
+    }
+}
+
+impl SignedAuditReport {
+    pub fn evcxr_display(&self) {
+        table(
+            "SignedAuditReport",
+            &[
+                ("blob_hash", format!("<code>{}</code>", hex::encode(&self.report.blob_hash))),
+                ("aad_hash", format!("<code>{}</code>", hex::encode(&self.report.aad_hash))),
+                ("auditor_pk", point(&self.auditor_pk)),
+                ("signature", point(&self.signature)),
+            ],
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! C ABI over the scheme, for embedding it into C/C++ test harnesses.
+//!
+//! `Sender`, `Receiver` and `ElGamal` cross the boundary as opaque handles that
+//! must be released with the matching `*_free` function. Points and scalars
+//! cross as byte buffers in arkworks' canonical uncompressed encoding, the same
+//! one used by `blob.bin`. The header in `include/chaos_theory.h` is generated
+//! from this file with `cbindgen`.
+
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::{ptr, slice};
+
+use crate::{Auditor, Blob, ElGamal, Message, Receiver, Sender};
+
+/// Length of an uncompressed G1 point (public keys, messages).
+pub const CHAOS_G1_LEN: usize = 96;
+/// Length of an uncompressed G2 point (authentication tags).
+pub const CHAOS_G2_LEN: usize = 192;
+/// Length of a serialized `ElGamal` ciphertext.
+pub const CHAOS_ELGAMAL_LEN: usize = 2 * CHAOS_G1_LEN;
+/// Length of a serialized scalar (secret keys).
+pub const CHAOS_SCALAR_LEN: usize = 32;
+
+unsafe fn read<T: CanonicalDeserialize>(data: *const u8, len: usize) -> Option<T> {
+    if data.is_null() {
+        return None;
+    }
+    let bytes = slice::from_raw_parts(data, len);
+    T::deserialize_uncompressed(bytes).ok()
+}
+
+// Application context; a null pointer is accepted for an empty `aad`.
+unsafe fn aad<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (data.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(data, len)),
+    }
+}
+
+unsafe fn write<T: CanonicalSerialize>(value: &T, out: *mut u8, out_len: usize) -> isize {
+    let size = value.uncompressed_size();
+    if out.is_null() || out_len < size {
+        return -1;
+    }
+    let buf = slice::from_raw_parts_mut(out, out_len);
+    match value.serialize_uncompressed(&mut buf[..size]) {
+        Ok(()) => size as isize,
+        Err(_) => -1,
+    }
+}
+
+fn into_handle<T>(value: Option<T>) -> *mut T {
+    value.map_or(ptr::null_mut(), |v| Box::into_raw(Box::new(v)))
+}
+
+unsafe fn free_handle<T>(handle: *mut T) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Creates a sender from a serialized secret key. Returns null on malformed input.
+///
+/// # Safety
+/// `sk` must point to `sk_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_sender_new(sk: *const u8, sk_len: usize) -> *mut Sender {
+    into_handle(read::<Fr>(sk, sk_len).map(Sender::new))
+}
+
+/// Writes the sender's public key into `out`. Returns the number of bytes
+/// written, or -1 if `out` is too small.
+///
+/// # Safety
+/// `sender` must be a live handle and `out` must point to `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_sender_pk(sender: *const Sender, out: *mut u8, out_len: usize) -> isize {
+    match sender.as_ref() {
+        Some(sender) => write(&sender.pk, out, out_len),
+        None => -1,
+    }
+}
+
+/// # Safety
+/// `sender` must be null or a handle returned by `chaos_sender_new`.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_sender_free(sender: *mut Sender) {
+    free_handle(sender)
+}
+
+/// Creates a receiver from a serialized public key. Returns null on malformed input.
+///
+/// # Safety
+/// `pk` must point to `pk_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_receiver_new(pk: *const u8, pk_len: usize) -> *mut Receiver {
+    into_handle(read::<G1Affine>(pk, pk_len).map(Receiver::new))
+}
+
+/// # Safety
+/// `receiver` must be null or a handle returned by `chaos_receiver_new`.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_receiver_free(receiver: *mut Receiver) {
+    free_handle(receiver)
+}
+
+/// Encrypts a serialized message point to `receiver`. Returns null on malformed input.
+///
+/// # Safety
+/// `sender` and `receiver` must be live handles and `msg` must point to
+/// `msg_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_sender_send(
+    sender: *const Sender,
+    msg: *const u8,
+    msg_len: usize,
+    receiver: *const Receiver,
+) -> *mut ElGamal {
+    let (Some(sender), Some(receiver)) = (sender.as_ref(), receiver.as_ref()) else {
+        return ptr::null_mut();
+    };
+    into_handle(read::<Message>(msg, msg_len).map(|m| sender.send(m, receiver)))
+}
+
+/// Writes the authentication tag of `c` under the context `aad` into `out`.
+/// Returns the number of bytes written, or -1 if `out` is too small.
+///
+/// # Safety
+/// `sender` and `c` must be live handles, `aad_data` must point to `aad_len`
+/// readable bytes (or be null if `aad_len` is 0) and `out` must point to
+/// `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_sender_authenticate(
+    sender: *const Sender,
+    c: *const ElGamal,
+    aad_data: *const u8,
+    aad_len: usize,
+    out: *mut u8,
+    out_len: usize,
+) -> isize {
+    match (sender.as_ref(), c.as_ref(), aad(aad_data, aad_len)) {
+        (Some(sender), Some(c), Some(aad)) => write(&sender.authenticate(c, aad), out, out_len),
+        _ => -1,
+    }
+}
+
+/// Parses a serialized ciphertext. Returns null on malformed input.
+///
+/// # Safety
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_elgamal_from_bytes(data: *const u8, len: usize) -> *mut ElGamal {
+    into_handle(read::<ElGamal>(data, len))
+}
+
+/// Writes the serialized ciphertext into `out`. Returns the number of bytes
+/// written, or -1 if `out` is too small.
+///
+/// # Safety
+/// `c` must be a live handle and `out` must point to `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_elgamal_to_bytes(c: *const ElGamal, out: *mut u8, out_len: usize) -> isize {
+    match c.as_ref() {
+        Some(c) => write(c, out, out_len),
+        None => -1,
+    }
+}
+
+/// # Safety
+/// `c` must be null or a handle returned by this module.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_elgamal_free(c: *mut ElGamal) {
+    free_handle(c)
+}
+
+/// Runs the auditor check on a serialized sender key and tag under the
+/// context `aad`. Returns 1 if the tag is valid, 0 if it is not, and -1 on
+/// malformed input.
+///
+/// # Safety
+/// `c` must be a live handle, `sender_pk` must point to `pk_len` readable
+/// bytes, `s` to `s_len` readable bytes and `aad_data` to `aad_len` readable
+/// bytes (or be null if `aad_len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn chaos_auditor_check_auth(
+    sender_pk: *const u8,
+    pk_len: usize,
+    c: *const ElGamal,
+    s: *const u8,
+    s_len: usize,
+    aad_data: *const u8,
+    aad_len: usize,
+) -> i32 {
+    let (Some(pk), Some(c), Some(s), Some(aad)) = (
+        read::<G1Affine>(sender_pk, pk_len),
+        c.as_ref(),
+        read::<G2Affine>(s, s_len),
+        aad(aad_data, aad_len),
+    ) else {
+        return -1;
+    };
+    Auditor::check_auth(pk, c, s, aad) as i32
+}
+
+/// Runs the auditor check on a whole serialized blob, with an empty `aad`.
+/// Returns 1 if the tag is valid, 0 if it is not, and -1 on malformed input.
+///
+/// # Safety
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chaos_blob_check(data: *const u8, len: usize) -> i32 {
+    match read::<Blob>(data, len) {
+        Some(blob) => Auditor::check_auth(blob.sender_pk, &blob.c, blob.s, &[]) as i32,
+        None => -1,
+    }
+}
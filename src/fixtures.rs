@@ -0,0 +1,62 @@
+//! Named deterministic key pairs and blobs for examples, doc tests and
+//! downstream integration tests.
+//!
+//! A fixture's secret key is SHA-256 of `FIXTURE_DST` and its name, reduced
+//! mod r, so fixtures are stable across releases and independent of any RNG.
+
+use ark_bls12_381::{Fr, G1Affine};
+use ark_ff::PrimeField;
+use sha2::{Digest, Sha256};
+
+use crate::{bls, message_space::MessageSpace, Blob, Receiver, Sender};
+
+pub const FIXTURE_DST: &[u8] = b"chaos-theory-fixture-v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixture {
+    pub name: &'static str,
+    pub sk: Fr,
+    pub pk: G1Affine,
+}
+
+impl Fixture {
+    pub fn named(name: &'static str) -> Self {
+        let digest = Sha256::new().chain_update(FIXTURE_DST).chain_update(name).finalize();
+        let sk = Fr::from_be_bytes_mod_order(&digest);
+        Fixture { name, sk, pk: bls::public_key(&sk) }
+    }
+
+    pub fn sender(&self) -> Sender {
+        Sender::new(self.sk)
+    }
+
+    pub fn receiver(&self) -> Receiver {
+        Receiver::new(self.pk)
+    }
+}
+
+pub fn alice() -> Fixture {
+    Fixture::named("alice")
+}
+
+pub fn bob() -> Fixture {
+    Fixture::named("bob")
+}
+
+pub fn carol() -> Fixture {
+    Fixture::named("carol")
+}
+
+/// Message `index` of `MessageSpace::puzzle()` from `from` to `to`, tagged
+/// with an empty `aad`.
+pub fn blob(from: &Fixture, to: &Fixture, index: usize) -> Blob {
+    let sender = from.sender();
+    let c = sender.send(MessageSpace::puzzle().messages()[index], &to.receiver());
+    let s = sender.authenticate(&c, &[]);
+    Blob { sender_pk: from.pk, c, s, rec_pk: to.pk }
+}
+
+/// Alice to Bob, encrypting the same plaintext as the original `blob.bin`.
+pub fn puzzle_blob() -> Blob {
+    blob(&alice(), &bob(), 3)
+}
@@ -0,0 +1,96 @@
+//! Scalar conversions and sampling.
+//!
+//! `FrExt` adds checked conversions to every prime field, so
+//! `Fr::from_u128_checked` and `Fr::from_hex` read like constructors. Hex is
+//! big-endian, as scalars are written in specs and test vectors, unlike the
+//! little-endian arkworks encoding `keys` uses.
+
+use alloc::{format, string::String};
+use ark_bls12_381::Fr;
+use ark_ff::{BigInt, BigInteger, PrimeField};
+use ark_std::rand::Rng;
+
+use crate::{h2c, hex, Error};
+
+pub trait FrExt: PrimeField {
+    /// `x` as a field element, or `None` if it is not below the modulus.
+    fn from_u128_checked(x: u128) -> Option<Self> {
+        let y = Self::from(x);
+        (y.to_u128() == Some(x)).then_some(y)
+    }
+
+    /// The element as an integer, or `None` if it doesn't fit in 128 bits.
+    fn to_u128(&self) -> Option<u128> {
+        let bytes = self.into_bigint().to_bytes_le();
+        if bytes.iter().skip(16).any(|&b| b != 0) {
+            return None;
+        }
+        let n = bytes.len().min(16);
+        let mut low = [0u8; 16];
+        low[..n].copy_from_slice(&bytes[..n]);
+        Some(u128::from_le_bytes(low))
+    }
+
+    /// Reads big-endian hex, with or without `0x`. Fails with
+    /// `InvalidEncoding` on malformed hex or a value not below the modulus.
+    fn from_hex(s: &str) -> Result<Self, Error> {
+        let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s))?;
+        let x = Self::from_be_bytes_mod_order(&bytes);
+        // from_be_bytes_mod_order silently reduces values >= the modulus
+        if trim_zeros(&x.into_bigint().to_bytes_be()) != trim_zeros(&bytes) {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(x)
+    }
+
+    /// Big-endian hex with `0x`, padded to the modulus' byte length.
+    fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(&self.into_bigint().to_bytes_be()))
+    }
+}
+
+impl<F: PrimeField> FrExt for F {}
+
+fn trim_zeros(bytes: &[u8]) -> &[u8] {
+    &bytes[bytes.iter().take_while(|&&b| b == 0).count()..]
+}
+
+/// `msg` hashed to a scalar under `dst`, as RFC 9380's hash-to-field. Fails
+/// with `InvalidConfig` for an empty or over-long `dst`.
+pub fn hash_to_scalar(msg: &[u8], dst: &[u8]) -> Result<Fr, Error> {
+    Ok(h2c::hash_to_field(msg, dst, 1)?[0])
+}
+
+/// A uniform scalar below `bound`, by rejection sampling: candidates are
+/// drawn with as many bits as `bound` has, so each is accepted with
+/// probability above 1/2. Fails with `InvalidConfig` if `bound` is zero.
+pub fn random_below<R: Rng>(rng: &mut R, bound: &Fr) -> Result<Fr, Error> {
+    let bound = bound.into_bigint();
+    let bits = bound.num_bits() as usize;
+    if bits == 0 {
+        return Err(Error::InvalidConfig);
+    }
+    loop {
+        let mut limbs: [u64; 4] = rng.gen();
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            match bits.saturating_sub(64 * i) {
+                0 => *limb = 0,
+                n if n < 64 => *limb &= (1 << n) - 1,
+                _ => {}
+            }
+        }
+        let candidate = BigInt(limbs);
+        if candidate < bound {
+            return Ok(Fr::from_bigint(candidate).unwrap());
+        }
+    }
+}
+
+/// A uniform scalar in `[start, end)`, comparing scalars as integers below
+/// the modulus. Fails with `InvalidConfig` if the range is empty.
+pub fn random_in_range<R: Rng>(rng: &mut R, start: Fr, end: Fr) -> Result<Fr, Error> {
+    if start >= end {
+        return Err(Error::InvalidConfig);
+    }
+    Ok(start + random_below(rng, &(end - start))?)
+}
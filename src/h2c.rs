@@ -0,0 +1,129 @@
+//! The hash-to-field steps of RFC 9380 that `SchemeConfig::hasher` runs
+//! internally, for code that needs them on their own (e.g. to derive
+//! challenges or scalars in proofs).
+
+use alloc::vec::Vec;
+use ark_ff::{
+    field_hashers::{DefaultFieldHasher, HashToField},
+    Field,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{config::MAX_DST_LEN, Error};
+
+const B_IN_BYTES: usize = 32;
+const S_IN_BYTES: usize = 64;
+
+/// `expand_message_xmd` with SHA-256 (RFC 9380, section 5.3.1). Fails with
+/// `InvalidConfig` for an empty or over-long `dst` or if more than 255 hash
+/// blocks would be needed.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Result<Vec<u8>, Error> {
+    let ell = len_in_bytes.div_ceil(B_IN_BYTES);
+    if dst.is_empty() || dst.len() > MAX_DST_LEN || ell > 255 {
+        return Err(Error::InvalidConfig);
+    }
+    let dst_prime = |h: Sha256| h.chain_update(dst).chain_update([dst.len() as u8]);
+
+    let b_0 = dst_prime(
+        Sha256::new()
+            .chain_update([0u8; S_IN_BYTES])
+            .chain_update(msg)
+            .chain_update((len_in_bytes as u16).to_be_bytes())
+            .chain_update([0u8]),
+    )
+    .finalize();
+
+    let mut out = Vec::with_capacity(ell * B_IN_BYTES);
+    let mut b_i = dst_prime(Sha256::new().chain_update(b_0).chain_update([1u8])).finalize();
+    out.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let mixed: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        b_i = dst_prime(Sha256::new().chain_update(mixed).chain_update([i as u8])).finalize();
+        out.extend_from_slice(&b_i);
+    }
+    out.truncate(len_in_bytes);
+    Ok(out)
+}
+
+/// `count` elements of `F` hashed from `msg` under `dst`, with the same
+/// SHA-256 expander and 128-bit security parameter as the scheme's hasher.
+pub fn hash_to_field<F: Field>(msg: &[u8], dst: &[u8], count: usize) -> Result<Vec<F>, Error> {
+    if dst.is_empty() || dst.len() > MAX_DST_LEN {
+        return Err(Error::InvalidConfig);
+    }
+    let hasher = <DefaultFieldHasher<Sha256, 128> as HashToField<F>>::new(dst);
+    Ok(hasher.hash_to_field(msg, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::hex;
+
+    // RFC 9380, appendix K.1: expand_message_xmd(SHA-256).
+    const K1_DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+
+    fn k1_messages() -> [Vec<u8>; 5] {
+        [
+            b"".to_vec(),
+            b"abc".to_vec(),
+            b"abcdef0123456789".to_vec(),
+            [b"q128_".as_slice(), &[b'q'; 128]].concat(),
+            [b"a512_".as_slice(), &[b'a'; 512]].concat(),
+        ]
+    }
+
+    fn check_k1(len_in_bytes: usize, expected: [&str; 5]) {
+        for (msg, expected) in k1_messages().iter().zip(expected) {
+            let uniform_bytes = expand_message_xmd(msg, K1_DST, len_in_bytes).unwrap();
+            assert_eq!(hex::encode(&uniform_bytes), expected, "msg = {}", String::from_utf8_lossy(msg));
+        }
+    }
+
+    #[test]
+    fn rfc9380_k1_len_0x20() {
+        check_k1(
+            0x20,
+            [
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+                "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1",
+                "b23a1d2b4d97b2ef7785562a7e8bac7eed54ed6e97e29aa51bfe3f12ddad1ff9",
+                "4623227bcc01293b8c130bf771da8c298dede7383243dc0993d2d94823958c4c",
+            ],
+        );
+    }
+
+    #[test]
+    fn rfc9380_k1_len_0x80() {
+        check_k1(
+            0x80,
+            [
+                "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbee0d121587713a3e0dd4d5e69e93eb7cd\
+                 4f5df4cd103e188cf60cb02edc3edf18eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dc\
+                 c541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced",
+                "abba86a6129e366fc877aab32fc4ffc70120d8996c88aee2fe4b32d6c7b6437a647e6c3163d40b76a73cf6a5674ef1d8\
+                 90f95b664ee0afa5359a5c4e07985635bbecbac65d747d3d2da7ec2b8221b17b0ca9dc8a1ac1c07ea6a1e60583e2cb00\
+                 058e77b7b72a298425cd1b941ad4ec65e8afc50303a22c0f99b0509b4c895f40",
+                "ef904a29bffc4cf9ee82832451c946ac3c8f8058ae97d8d629831a74c6572bd9ebd0df635cd1f208e2038e760c499498\
+                 4ce73f0d55ea9f22af83ba4734569d4bc95e18350f740c07eef653cbb9f87910d833751825f0ebefa1abe5420bb52be1\
+                 4cf489b37fe1a72f7de2d10be453b2c9d9eb20c7e3f6edc5a60629178d9478df",
+                "80be107d0884f0d881bb460322f0443d38bd222db8bd0b0a5312a6fedb49c1bbd88fd75d8b9a09486c60123dfa1d73c1\
+                 cc3169761b17476d3c6b7cbbd727acd0e2c942f4dd96ae3da5de368d26b32286e32de7e5a8cb2949f866a0b80c58116b\
+                 29fa7fabb3ea7d520ee603e0c25bcaf0b9a5e92ec6a1fe4e0391d1cdbce8c68a",
+                "546aff5444b5b79aa6148bd81728704c32decb73a3ba76e9e75885cad9def1d06d6792f8a7d12794e90efed817d96920\
+                 d728896a4510864370c207f99bd4a608ea121700ef01ed879745ee3e4ceef777eda6d9e5e38b90c86ea6fb0b36504ba4\
+                 a45d22e86f6db5dd43d98a294bebb9125d5b794e9d2a81181066eb954966a487",
+            ],
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_parameters() {
+        assert!(matches!(expand_message_xmd(b"abc", b"", 32), Err(Error::InvalidConfig)));
+        assert!(matches!(expand_message_xmd(b"abc", &[b'x'; MAX_DST_LEN + 1], 32), Err(Error::InvalidConfig)));
+        assert!(matches!(expand_message_xmd(b"abc", K1_DST, 255 * 32 + 1), Err(Error::InvalidConfig)));
+        assert_eq!(expand_message_xmd(b"abc", K1_DST, 255 * 32).unwrap().len(), 255 * 32);
+    }
+}
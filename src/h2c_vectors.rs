@@ -0,0 +1,272 @@
+//! Hash-to-curve test vectors in the layout of RFC 9380, appendix J.
+//!
+//! `generate` hashes the RFC's messages under a DST and `render` prints them
+//! as the appendix does (`msg`, `P.x`, `P.y`, G2 coordinates as `c0 + I * c1`),
+//! so the output for the RFC's `QUUX-V01-CS02-with-...` DSTs can be diffed
+//! against the RFC text, and `parse` reads that text back for `check`.
+
+use alloc::{format, string::String, vec, vec::Vec};
+use ark_bls12_381::{Fq, G1Affine, G2Affine};
+use ark_ec::hashing::HashToCurve;
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::{
+    config::{G1Hasher, G2Hasher},
+    hex, Error,
+};
+
+/// DST of the RFC's `BLS12381G1_XMD:SHA-256_SSWU_RO_` vectors.
+pub const RFC_G1_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+/// DST of the RFC's `BLS12381G2_XMD:SHA-256_SSWU_RO_` vectors.
+pub const RFC_G2_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suite {
+    G1,
+    /// The suite the scheme's tags are hashed with.
+    G2,
+}
+
+impl Suite {
+    pub fn id(self) -> &'static str {
+        match self {
+            Suite::G1 => "BLS12381G1_XMD:SHA-256_SSWU_RO_",
+            Suite::G2 => "BLS12381G2_XMD:SHA-256_SSWU_RO_",
+        }
+    }
+
+    pub fn rfc_dst(self) -> &'static [u8] {
+        match self {
+            Suite::G1 => RFC_G1_DST,
+            Suite::G2 => RFC_G2_DST,
+        }
+    }
+}
+
+/// The first two vectors of RFC 9380, J.9.1, as printed there.
+pub const RFC_G1_EXCERPT: &str = "\
+suite   = BLS12381G1_XMD:SHA-256_SSWU_RO_
+dst     = QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_
+
+msg     =
+P.x     = 0x052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1
+P.y     = 0x08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265
+
+msg     = abc
+P.x     = 0x03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903
+P.y     = 0x0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d
+";
+
+/// The first two vectors of RFC 9380, J.10.1, as printed there.
+pub const RFC_G2_EXCERPT: &str = "\
+suite   = BLS12381G2_XMD:SHA-256_SSWU_RO_
+dst     = QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_
+
+msg     =
+P.x     = 0x0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a
+    + I * 0x05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d
+P.y     = 0x0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92
+    + I * 0x12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6
+
+msg     = abc
+P.x     = 0x02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6
+    + I * 0x139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8
+P.y     = 0x1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48
+    + I * 0x00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16
+";
+
+impl Suite {
+    /// The RFC's own expected points for its first two messages.
+    pub fn rfc_excerpt(self) -> &'static str {
+        match self {
+            Suite::G1 => RFC_G1_EXCERPT,
+            Suite::G2 => RFC_G2_EXCERPT,
+        }
+    }
+}
+
+/// A hashed message. Coordinates have one component in G1 and two
+/// (`c0`, `c1`) in G2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vector {
+    pub msg: Vec<u8>,
+    pub x: Vec<Fq>,
+    pub y: Vec<Fq>,
+}
+
+/// The messages of the RFC's vectors, in order.
+pub fn rfc_messages() -> Vec<Vec<u8>> {
+    let repeat = |prefix: &str, c: u8, n: usize| [prefix.as_bytes(), &vec![c; n]].concat();
+    vec![
+        b"".to_vec(),
+        b"abc".to_vec(),
+        b"abcdef0123456789".to_vec(),
+        repeat("q128_", b'q', 128),
+        repeat("a512_", b'a', 512),
+    ]
+}
+
+/// `msg` hashed to the curve of `suite` under `dst`. Fails with
+/// `InvalidConfig` for an empty or over-long `dst`.
+pub fn hash(suite: Suite, dst: &[u8], msg: &[u8]) -> Result<Vector, Error> {
+    let invalid = |_| Error::InvalidConfig;
+    Ok(match suite {
+        Suite::G1 => {
+            let p: G1Affine = G1Hasher::new(dst).map_err(invalid)?.hash(msg).map_err(invalid)?;
+            Vector { msg: msg.to_vec(), x: vec![p.x], y: vec![p.y] }
+        }
+        Suite::G2 => {
+            let p: G2Affine = G2Hasher::new(dst).map_err(invalid)?.hash(msg).map_err(invalid)?;
+            Vector { msg: msg.to_vec(), x: vec![p.x.c0, p.x.c1], y: vec![p.y.c0, p.y.c1] }
+        }
+    })
+}
+
+/// The RFC's messages hashed under `dst`.
+pub fn generate(suite: Suite, dst: &[u8]) -> Result<Vec<Vector>, Error> {
+    rfc_messages().iter().map(|msg| hash(suite, dst, msg)).collect()
+}
+
+/// Indices of the vectors in `vectors` that don't hash to their point.
+pub fn check(suite: Suite, dst: &[u8], vectors: &[Vector]) -> Result<Vec<usize>, Error> {
+    let mut mismatches = Vec::new();
+    for (i, v) in vectors.iter().enumerate() {
+        if hash(suite, dst, &v.msg)? != *v {
+            mismatches.push(i);
+        }
+    }
+    Ok(mismatches)
+}
+
+fn fq_hex(x: &Fq) -> String {
+    format!("0x{}", hex::encode(&x.into_bigint().to_bytes_be()))
+}
+
+fn coordinate(c: &[Fq]) -> String {
+    let parts: Vec<String> = c.iter().map(fq_hex).collect();
+    parts.join("\n    + I * ")
+}
+
+/// `vectors` in the layout of the RFC, headed by the suite and DST.
+pub fn render(suite: Suite, dst: &[u8], vectors: &[Vector]) -> String {
+    let mut out = format!("suite   = {}\nDST     = {}\n", suite.id(), String::from_utf8_lossy(dst));
+    for v in vectors {
+        out += &format!(
+            "\nmsg     = {}\nP.x     = {}\nP.y     = {}\n",
+            String::from_utf8_lossy(&v.msg),
+            coordinate(&v.x),
+            coordinate(&v.y)
+        );
+    }
+    out
+}
+
+fn parse_coordinate(s: &str) -> Result<Vec<Fq>, Error> {
+    s.split("+ I *")
+        .map(|part| {
+            let part = part.trim();
+            let digits = part.strip_prefix("0x").unwrap_or(part);
+            let bytes = hex::decode(digits)?;
+            if bytes.len() > 48 {
+                return Err(Error::InvalidEncoding);
+            }
+            Ok(Fq::from_be_bytes_mod_order(&bytes))
+        })
+        .collect()
+}
+
+/// Reads vectors in the layout of `render` or of the RFC text: `key = value`
+/// lines, values continued on indented lines. Only `DST` (`dst` in the
+/// RFC), `msg`, `P.x` and `P.y` are read, so the RFC's `u[0]`, `Q0.x`, ...
+/// lines may stay in.
+/// Returns the DST, if given, and the vectors.
+pub fn parse(s: &str) -> Result<(Option<Vec<u8>>, Vec<Vector>), Error> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for line in s.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            let (_, value) = fields.last_mut().ok_or(Error::InvalidEncoding)?;
+            let part = line.trim();
+            if part.starts_with('+') {
+                value.push(' ');
+            }
+            value.push_str(part);
+        } else {
+            let (key, value) = line.split_once('=').ok_or(Error::InvalidEncoding)?;
+            fields.push((key.trim().into(), value.trim().into()));
+        }
+    }
+
+    let mut dst = None;
+    let mut vectors = Vec::new();
+    let mut msg = None;
+    let mut x = None;
+    for (key, value) in fields {
+        match key.as_str() {
+            "DST" | "dst" => dst = Some(value.into_bytes()),
+            "msg" => msg = Some(value.into_bytes()),
+            "P.x" => x = Some(parse_coordinate(&value)?),
+            "P.y" => {
+                let (msg, x) = (msg.take(), x.take());
+                vectors.push(Vector {
+                    msg: msg.ok_or(Error::InvalidEncoding)?,
+                    x: x.ok_or(Error::InvalidEncoding)?,
+                    y: parse_coordinate(&value)?,
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok((dst, vectors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{config::STANDARD_G2_DST, SchemeConfig, DST};
+
+    // The puzzle's legacy `[1, 3, 3, 7]` DST must never stand in for a
+    // standard one: the default config uses it, `SchemeConfig::standard`
+    // uses the IETF ciphersuite's, and it hashes the RFC's messages to other
+    // points than every standard DST does.
+    #[test]
+    fn legacy_dst_is_separated_from_standard_ones() {
+        let standard = [STANDARD_G2_DST, RFC_G1_DST, RFC_G2_DST];
+        assert_eq!(SchemeConfig::default().dst(), DST);
+        assert_eq!(SchemeConfig::standard().dst(), STANDARD_G2_DST);
+        assert!(!standard.contains(&DST));
+        for msg in rfc_messages() {
+            let legacy = hash(Suite::G2, DST, &msg).unwrap();
+            for dst in standard {
+                assert_ne!(hash(Suite::G2, dst, &msg).unwrap(), legacy);
+            }
+        }
+    }
+
+    #[test]
+    fn generated_vectors_match_the_rfc() {
+        for suite in [Suite::G1, Suite::G2] {
+            let (dst, expected) = parse(suite.rfc_excerpt()).unwrap();
+            assert_eq!(dst.as_deref(), Some(suite.rfc_dst()));
+            assert_eq!(expected.iter().map(|v| v.msg.as_slice()).collect::<Vec<_>>(), [&b""[..], b"abc"]);
+            assert_eq!(generate(suite, suite.rfc_dst()).unwrap()[..2], expected[..]);
+            assert!(check(suite, suite.rfc_dst(), &expected).unwrap().is_empty());
+            assert_eq!(check(suite, DST, &expected).unwrap(), [0, 1]);
+        }
+    }
+
+    #[test]
+    fn rendered_vectors_parse_and_check() {
+        for suite in [Suite::G1, Suite::G2] {
+            let vectors = generate(suite, suite.rfc_dst()).unwrap();
+            let (dst, parsed) = parse(&render(suite, suite.rfc_dst(), &vectors)).unwrap();
+            assert_eq!(dst.as_deref(), Some(suite.rfc_dst()));
+            assert_eq!(parsed, vectors);
+            assert!(check(suite, suite.rfc_dst(), &parsed).unwrap().is_empty());
+            assert_eq!(check(suite, DST, &parsed).unwrap(), (0..vectors.len()).collect::<Vec<_>>());
+        }
+    }
+}
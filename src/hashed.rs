@@ -0,0 +1,65 @@
+//! Ciphertexts together with their hash to G2.
+//!
+//! Hashing to G2 is the most expensive step of tagging and verifying after the
+//! pairings. A `HashedCiphertext` computes `H(c)` once, under a given
+//! configuration and context, and can then be tagged, verified and attacked
+//! without hashing again.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Bls12_381, G1Affine, G1Projective, G2Affine};
+use ark_ec::{pairing::Pairing, CurveGroup, Group};
+use core::ops::Mul;
+
+use crate::{Auditor, ElGamal, SchemeConfig, Sender};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashedCiphertext {
+    c: ElGamal,
+    dst: Vec<u8>,
+    hash: G2Affine,
+}
+
+impl HashedCiphertext {
+    pub fn new(c: ElGamal, config: &SchemeConfig, aad: &[u8]) -> Self {
+        let hash = config.hash_to_curve(&c, aad);
+        HashedCiphertext { c, dst: config.dst().to_vec(), hash }
+    }
+
+    pub fn ciphertext(&self) -> &ElGamal {
+        &self.c
+    }
+
+    /// `H(c)`.
+    pub fn hash(&self) -> G2Affine {
+        self.hash
+    }
+
+    /// The DST the hash was computed under.
+    pub fn dst(&self) -> &[u8] {
+        &self.dst
+    }
+}
+
+impl Sender {
+    /// `authenticate` with the hash already computed. `hc` must have been
+    /// hashed under this sender's configuration.
+    pub fn authenticate_hashed(&self, hc: &HashedCiphertext) -> G2Affine {
+        debug_assert_eq!(hc.dst(), self.config().dst());
+        hc.hash.mul(&self.sk).into_affine()
+    }
+}
+
+impl Auditor {
+    /// `verify` with the hash already computed. `hc` must have been hashed
+    /// under this auditor's configuration.
+    pub fn verify_hashed(&self, sender_pk: G1Affine, hc: &HashedCiphertext, s: G2Affine) -> bool {
+        debug_assert_eq!(hc.dst(), self.config().dst());
+        if !self.strict_inputs_ok(&sender_pk, &s) {
+            return false;
+        }
+        let lhs = { Bls12_381::pairing(G1Projective::generator(), s) };
+        let rhs = { Bls12_381::pairing(sender_pk, hc.hash) };
+
+        lhs == rhs
+    }
+}
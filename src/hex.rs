@@ -0,0 +1,42 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::Error;
+
+pub fn encode(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 * bytes.len());
+    for b in bytes {
+        write!(s, "{b:02x}").unwrap();
+    }
+    s
+}
+
+pub fn decode(s: &str) -> Result<Vec<u8>, Error> {
+    // from_str_radix alone would take "+a" as 0x0a
+    if s.len() % 2 != 0 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(Error::InvalidEncoding);
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| Error::InvalidEncoding))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_both_cases() {
+        assert_eq!(decode("00ff10Ab").unwrap(), [0x00, 0xff, 0x10, 0xab]);
+        assert_eq!(decode("").unwrap(), Vec::<u8>::new());
+        assert_eq!(encode(&decode("DEADbeef").unwrap()), "deadbeef");
+    }
+
+    #[test]
+    fn rejects_non_hex_digits() {
+        for s in ["+a", "-1", "a+", " a", "0x", "g0", "0", "abc", "\u{e9}"] {
+            assert!(matches!(decode(s), Err(Error::InvalidEncoding)), "{s:?}");
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Finding the sender of a tag among registered keys.
+//!
+//! Blobs that arrive without sender metadata can still be attributed: the tag
+//! `s = sk * H(c)` verifies under exactly one key. `H(c)` is hashed and
+//! prepared for the Miller loop once and `e(g1, s)` computed once, so each
+//! candidate costs a single pairing instead of a full `verify`.
+
+use ark_bls12_381::{Bls12_381, G1Affine, G1Projective, G2Affine};
+use ark_ec::{pairing::Pairing, Group};
+
+use crate::{pairing_utils::G2Prepared, Auditor, ElGamal};
+
+impl Auditor {
+    /// Index in `candidates` of the key `s` verifies under, for `c` tagged
+    /// under `aad`.
+    pub fn identify_signer(&self, candidates: &[G1Affine], c: &ElGamal, s: G2Affine, aad: &[u8]) -> Option<usize> {
+        let hash_c = G2Prepared::from(self.config().hash_to_curve(c, aad));
+        let target = Bls12_381::pairing(G1Projective::generator(), s);
+        candidates.iter().position(|pk| {
+            self.strict_inputs_ok(pk, &s) && Bls12_381::pairing(*pk, hash_c.clone()) == target
+        })
+    }
+}
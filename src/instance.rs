@@ -0,0 +1,79 @@
+//! Seeded puzzle instances.
+//!
+//! An instance is fully determined by its seed: the sender and receiver keys
+//! and the index of the encrypted message are drawn, in that order, from
+//! ChaCha20 keyed with the seed (little-endian, zero-padded to 32 bytes).
+//! Unlike `StdRng`, ChaCha20's output is fixed by its specification, so graders
+//! can recreate the blob of any instance byte for byte and check a submitted
+//! `blob.bin`/answer pair without keeping the files around.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::UniformRand;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::{RngCore, SeedableRng};
+use core::ops::Mul;
+use rand_chacha::ChaCha20Rng;
+
+use crate::{message_space::MessageSpace, Blob, Receiver, Sender};
+
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub seed: u64,
+    pub sender_sk: Fr,
+    pub receiver_sk: Fr,
+    /// Index of the encrypted message in `MessageSpace::puzzle()`.
+    pub index: usize,
+    pub blob: Blob,
+}
+
+impl Instance {
+    pub fn from_seed(seed: u64) -> Self {
+        let mut key = [0u8; 32];
+        key[..8].copy_from_slice(&seed.to_le_bytes());
+        let mut rng = ChaCha20Rng::from_seed(key);
+        let sender_sk = Fr::rand(&mut rng);
+        let receiver_sk = Fr::rand(&mut rng);
+        let space = MessageSpace::puzzle();
+        // not `gen_range`, whose algorithm `rand` may change; the modulo bias
+        // is below 2^-60
+        let index = (rng.next_u64() % space.len() as u64) as usize;
+
+        let sender = Sender::new(sender_sk);
+        let receiver = Receiver::new(G1Affine::generator().mul(receiver_sk).into_affine());
+        let c = sender.send(space.messages()[index], &receiver);
+        let s = sender.authenticate(&c, &[]);
+        let blob = Blob { sender_pk: sender.pk, c, s, rec_pk: receiver.pk() };
+
+        Instance { seed, sender_sk, receiver_sk, index, blob }
+    }
+
+    /// The instance's `blob.bin`.
+    pub fn blob_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        self.blob.serialize_uncompressed(&mut data).unwrap();
+        data
+    }
+
+    /// The plaintext exponent, i.e. the expected answer.
+    pub fn answer(&self) -> u128 {
+        MessageSpace::puzzle().exponents()[self.index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack;
+
+    #[test]
+    fn seeds_determine_instances() {
+        assert_eq!(Instance::from_seed(7).blob_bytes(), Instance::from_seed(7).blob_bytes());
+        assert_ne!(Instance::from_seed(7).blob_bytes(), Instance::from_seed(8).blob_bytes());
+        let instance = Instance::from_seed(7);
+        let space = MessageSpace::puzzle();
+        let index = attack::recover_index(&instance.blob, &Default::default(), &[], &space);
+        assert_eq!(index, Some(instance.index));
+    }
+}
@@ -0,0 +1,144 @@
+//! Key import and export in the crate's canonical bytes, hex and a JSON
+//! format modelled on JWK.
+//!
+//! Canonical bytes are the arkworks compressed encodings: 32 little-endian
+//! bytes for a secret key, 48 for a public key. Hex is those bytes in lower
+//! case. The JSON format is a flat object of strings, with the bytes in hex:
+//!
+//! ```json
+//! {"kty":"BLS","crv":"BLS12-381-G1","x":"<public key>","d":"<secret key>"}
+//! ```
+//!
+//! `d` is only present for secret keys; on import it must match `x`.
+//! `decode_sk` and `decode_pk` recognise the format from the data.
+
+use alloc::{format, string::String, vec::Vec};
+use ark_bls12_381::{Fr, G1Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{bls, hex, Error};
+
+pub const KTY: &str = "BLS";
+/// Public keys are in G1.
+pub const CRV: &str = "BLS12-381-G1";
+
+pub const SK_LEN: usize = 32;
+pub const PK_LEN: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    Raw,
+    Hex,
+    Json,
+}
+
+fn to_bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+fn json(x: &G1Affine, d: Option<&Fr>) -> String {
+    let mut s = format!("{{\"kty\":\"{KTY}\",\"crv\":\"{CRV}\",\"x\":\"{}\"", hex::encode(&to_bytes(x)));
+    if let Some(d) = d {
+        s.push_str(&format!(",\"d\":\"{}\"", hex::encode(&to_bytes(d))));
+    }
+    s.push('}');
+    s
+}
+
+// Parses a flat JSON object whose values are strings without escapes.
+pub(crate) fn parse_json(s: &str) -> Result<Vec<(&str, &str)>, Error> {
+    let inner = s.trim().strip_prefix('{').and_then(|s| s.strip_suffix('}')).ok_or(Error::InvalidEncoding)?;
+    let string = |s: &str| -> Result<&str, Error> {
+        let s = s.trim().strip_prefix('"').and_then(|s| s.strip_suffix('"')).ok_or(Error::InvalidEncoding)?;
+        if s.contains(['"', '\\']) {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(s)
+    };
+    inner
+        .split(',')
+        .map(|member| {
+            let (key, value) = member.split_once(':').ok_or(Error::InvalidEncoding)?;
+            Ok((string(key)?, string(value)?))
+        })
+        .collect()
+}
+
+// The `x` and `d` members of a JSON key, after checking `kty` and `crv`.
+fn json_members(s: &str) -> Result<(G1Affine, Option<Fr>), Error> {
+    let members = parse_json(s)?;
+    let get = |name: &str| members.iter().find(|(k, _)| *k == name).map(|&(_, v)| v);
+    if get("kty") != Some(KTY) || get("crv") != Some(CRV) {
+        return Err(Error::InvalidEncoding);
+    }
+    let x = pk_from_bytes(&hex::decode(get("x").ok_or(Error::InvalidEncoding)?)?)?;
+    let d = get("d").map(|d| sk_from_bytes(&hex::decode(d)?)).transpose()?;
+    Ok((x, d))
+}
+
+// Raw keys are told apart by their length, as their bytes can be anything.
+fn detect(data: &[u8], raw_len: usize) -> KeyFormat {
+    if data.len() == raw_len {
+        KeyFormat::Raw
+    } else if data.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{') {
+        KeyFormat::Json
+    } else {
+        KeyFormat::Hex
+    }
+}
+
+pub fn sk_from_bytes(bytes: &[u8]) -> Result<Fr, Error> {
+    Fr::deserialize_compressed(bytes).map_err(|_| Error::InvalidEncoding)
+}
+
+/// Fails with `InvalidEncoding` unless `bytes` is a compressed point of the
+/// prime-order subgroup.
+pub fn pk_from_bytes(bytes: &[u8]) -> Result<G1Affine, Error> {
+    G1Affine::deserialize_compressed(bytes).map_err(|_| Error::InvalidEncoding)
+}
+
+pub fn encode_sk(sk: &Fr, format: KeyFormat) -> Vec<u8> {
+    match format {
+        KeyFormat::Raw => to_bytes(sk),
+        KeyFormat::Hex => hex::encode(&to_bytes(sk)).into_bytes(),
+        KeyFormat::Json => json(&bls::public_key(sk), Some(sk)).into_bytes(),
+    }
+}
+
+pub fn encode_pk(pk: &G1Affine, format: KeyFormat) -> Vec<u8> {
+    match format {
+        KeyFormat::Raw => to_bytes(pk),
+        KeyFormat::Hex => hex::encode(&to_bytes(pk)).into_bytes(),
+        KeyFormat::Json => json(pk, None).into_bytes(),
+    }
+}
+
+/// Fails with `InvalidEncoding` if `data` is in none of the formats, or with
+/// `IdentityMismatch` if a JSON key's `x` is not the public key of its `d`.
+pub fn decode_sk(data: &[u8]) -> Result<Fr, Error> {
+    let text = || core::str::from_utf8(data).map_err(|_| Error::InvalidEncoding);
+    match detect(data, SK_LEN) {
+        KeyFormat::Raw => sk_from_bytes(data),
+        KeyFormat::Hex => sk_from_bytes(&hex::decode(text()?.trim())?),
+        KeyFormat::Json => {
+            let (x, d) = json_members(text()?)?;
+            let sk = d.ok_or(Error::MissingKey)?;
+            if bls::public_key(&sk) != x {
+                return Err(Error::IdentityMismatch);
+            }
+            Ok(sk)
+        }
+    }
+}
+
+/// Accepts JSON secret keys too, returning their public key.
+pub fn decode_pk(data: &[u8]) -> Result<G1Affine, Error> {
+    let text = || core::str::from_utf8(data).map_err(|_| Error::InvalidEncoding);
+    match detect(data, PK_LEN) {
+        KeyFormat::Raw => pk_from_bytes(data),
+        KeyFormat::Hex => pk_from_bytes(&hex::decode(text()?.trim())?),
+        KeyFormat::Json => Ok(json_members(text()?)?.0),
+    }
+}
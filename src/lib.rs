@@ -0,0 +1,273 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{CurveGroup, Group};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::{Digest, Sha256};
+use core::{
+    hash::{Hash, Hasher},
+    ops::Mul,
+};
+
+pub mod accumulator;
+pub mod anonymous;
+pub mod answer;
+pub mod attack;
+pub mod attacks;
+pub mod attest;
+pub mod batch;
+pub mod bbs;
+pub mod bls;
+pub mod cbor;
+pub mod cert;
+pub mod compress;
+#[cfg(feature = "blst-interop")]
+pub mod blst_interop;
+#[cfg(feature = "borsh")]
+mod borsh_impls;
+pub mod config;
+pub mod ct;
+pub mod diagnostics;
+pub mod display;
+pub mod dlog;
+pub mod eip2537;
+#[cfg(feature = "evcxr")]
+mod evcxr;
+#[cfg(feature = "std")]
+pub mod ffi;
+pub mod fixtures;
+pub mod fr_utils;
+pub mod h2c;
+pub mod h2c_vectors;
+pub mod hashed;
+pub mod hex;
+pub mod identify;
+pub mod instance;
+pub mod keys;
+pub mod membership;
+pub mod message_space;
+pub mod metrics;
+#[cfg(feature = "protobuf")]
+pub mod proto;
+pub mod pairing_utils;
+pub mod pedersen;
+pub mod point_audit;
+pub mod quorum;
+pub mod range;
+pub mod reader;
+pub mod recovery;
+pub mod registry;
+pub mod report;
+pub mod security;
+pub mod session;
+#[cfg(feature = "cli")]
+pub mod settings;
+pub mod side_channel;
+pub mod snark;
+#[cfg(feature = "std")]
+pub mod simulate;
+pub mod solidity;
+#[cfg(feature = "std")]
+pub mod srs;
+pub mod stealth;
+#[cfg(feature = "storage")]
+pub mod storage;
+pub mod stream;
+pub mod strict;
+pub mod submission;
+pub mod symbolic;
+pub mod tally;
+pub mod tamper;
+pub mod testkit;
+pub mod transcript;
+pub mod transparency;
+pub mod variants;
+#[cfg(feature = "std")]
+pub mod tuning;
+#[cfg(feature = "proptest")]
+pub mod strategies;
+
+pub use config::SchemeConfig;
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidMsg,
+    InvalidEncoding,
+    InvalidConfig,
+    InvalidSignature,
+    InvalidUpdate,
+    UnknownSender,
+    RevokedKey,
+    ExpiredKey,
+    InvalidShares,
+    Storage,
+    InvalidProof,
+    MissingKey,
+    UnknownIdentity,
+    IdentityMismatch,
+}
+
+/// Domain separation tag used when hashing ciphertexts to G2.
+pub const DST: &[u8] = &[1, 3, 3, 7];
+
+// Hashes the canonical compressed encoding, so that equal values hash equally
+// regardless of how they were constructed.
+fn hash_canonical<T: CanonicalSerialize, H: Hasher>(value: &T, state: &mut H) {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes.hash(state);
+}
+
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ElGamal(pub G1Affine, pub G1Affine);
+
+impl Hash for ElGamal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_canonical(self, state)
+    }
+}
+
+impl ElGamal {
+    pub fn hash_to_curve(&self, aad: &[u8]) -> G2Affine {
+        SchemeConfig::default().hash_to_curve(self, aad)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Message(pub G1Affine);
+
+impl Message {
+    /// Encodes `m` as `g1^m`.
+    pub fn from_exponent(m: u128) -> Self {
+        Message(G1Projective::generator().mul(Fr::from(m)).into_affine())
+    }
+}
+
+impl Hash for Message {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_canonical(self, state)
+    }
+}
+
+#[derive(Clone)]
+pub struct Sender {
+    pub sk: Fr,
+    pub pk: G1Affine,
+    config: SchemeConfig,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Receiver {
+    pk: G1Affine,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Auditor {
+    config: SchemeConfig,
+    key: Option<attest::AuditorKey>,
+    strict: strict::StrictVerification,
+}
+
+impl Sender {
+    pub fn new(sk: Fr) -> Self {
+        Self::with_config(sk, SchemeConfig::default())
+    }
+
+    pub fn with_config(sk: Fr, config: SchemeConfig) -> Self {
+        let pk = G1Projective::generator().mul(&sk).into_affine();
+        Sender { sk, pk, config }
+    }
+
+    pub fn config(&self) -> &SchemeConfig {
+        &self.config
+    }
+
+    pub fn send(&self, m: Message, r: &Receiver) -> ElGamal {
+        let c_2: G1Affine = (r.pk.mul(&self.sk) + m.0).into_affine();
+        ElGamal(self.pk, c_2)
+    }
+
+    /// Tags `c` together with the application context `aad` (protocol id,
+    /// session id, ...). The auditor must be given the same `aad`.
+    pub fn authenticate(&self, c: &ElGamal, aad: &[u8]) -> G2Affine {
+        self.authenticate_hashed(&hashed::HashedCiphertext::new(c.clone(), &self.config, aad))
+    }
+}
+
+impl Receiver {
+    pub fn new(pk: G1Affine) -> Self {
+        Receiver { pk }
+    }
+
+    pub fn pk(&self) -> G1Affine {
+        self.pk
+    }
+}
+
+impl Auditor {
+    pub fn new(config: SchemeConfig) -> Self {
+        Auditor { config, key: None, strict: strict::StrictVerification::Off }
+    }
+
+    pub fn config(&self) -> &SchemeConfig {
+        &self.config
+    }
+
+    /// `check_auth` under this auditor's configuration.
+    pub fn verify(&self, sender_pk: G1Affine, c: &ElGamal, s: G2Affine, aad: &[u8]) -> bool {
+        self.verify_hashed(sender_pk, &hashed::HashedCiphertext::new(c.clone(), &self.config, aad), s)
+    }
+
+    pub fn check_auth(sender_pk: G1Affine, c: &ElGamal, s: G2Affine, aad: &[u8]) -> bool {
+        Auditor::default().verify(sender_pk, c, s, aad)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Blob {
+    pub sender_pk: G1Affine,
+    pub c: ElGamal,
+    pub s: G2Affine,
+    pub rec_pk: G1Affine,
+}
+
+impl Blob {
+    /// SHA-256 of the uncompressed encoding, identifying the blob in
+    /// databases, logs and attestations.
+    pub fn content_hash(&self) -> [u8; 32] {
+        let mut data = Vec::new();
+        self.serialize_uncompressed(&mut data).unwrap();
+        Sha256::digest(data).into()
+    }
+}
+
+impl Hash for Blob {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_canonical(self, state)
+    }
+}
+
+pub fn generate_message_space() -> ([u64; 10],[Message; 10]) {
+    let g1 = G1Projective::generator();
+    let msgs = [
+        390183091831u64,
+        4987238947234982,
+        84327489279482,
+        8492374892742,
+        5894274824234,
+        4982748927426,
+        48248927348927427,
+        489274982749828,
+        99084321987189371,
+        8427489729843712893,
+    ];
+    let g_m = msgs.iter()
+        .map(|&msg_i| Message(g1.mul(Fr::from(msg_i)).into_affine()))
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    return (msgs,g_m); // modified to return the u64 msgs just for display purposes.
+}
@@ -1,142 +1,1037 @@
-use ark_bls12_381::{g2::Config, Bls12_381, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
 use ark_ec::{
-    hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve},
-    pairing::Pairing,
-    CurveGroup, Group,
+    pairing::{Pairing, PairingOutput},
+    AffineRepr,
+};
+use ark_ff::UniformRand;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+#[cfg(feature = "watch")]
+use std::sync::mpsc;
+#[cfg(feature = "storage")]
+use zkhack_puzzle_solution::storage;
+use std::{
+    collections::HashSet,
+    env,
+    fs::{self, File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process, thread,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
-use ark_ff::field_hashers::DefaultFieldHasher;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use sha2::Sha256;
-use std::{fs::File, io::Read, ops::Mul};
 
 use prompt::{puzzle, welcome};
+use zkhack_puzzle_solution::{
+    answer, attack, bls, cert, diagnostics,
+    display::{full_hex, short_hex},
+    dlog, generate_message_space,
+    h2c_vectors::{self, Suite},
+    hashed::HashedCiphertext,
+    hex,
+    instance::Instance,
+    keys,
+    message_space::MessageSpace,
+    metrics::AuditMetrics,
+    point_audit::PointAudit,
+    reader::{BlobReader, Field},
+    recovery,
+    report::{AttackReport, AttackResult},
+    settings::{Format, Settings, SETTINGS_FILE},
+    simulate, solidity, submission, symbolic, tally,
+    tamper::{self, Verdict},
+    transcript::Transcript,
+    transparency, tuning,
+    variants::{SchemeVariant, VARIANTS},
+    Auditor, Blob, ElGamal, Message, Receiver, SchemeConfig, Sender, DST,
+};
+
+pub fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let mut settings = Settings::load(&mut args).expect("invalid settings");
+    #[cfg(feature = "parallel")]
+    settings.init_threads();
+
+    let command = args.first().map(String::as_str);
+    if settings.variant != SchemeVariant::Legacy && !variant_independent(command) {
+        eprintln!("{} only supports the legacy variant, not {}", command.unwrap_or("solving"), settings.variant);
+        process::exit(1);
+    }
 
-#[derive(Debug)]
-pub enum Error {
-    InvalidMsg,
+    match command {
+        Some("export-verifier") => export_verifier(&args[1..]),
+        Some("calibrate") => calibrate(&args[1..], &mut settings),
+        Some("estimate") => estimate(&args[1..], &settings),
+        Some("attack") => attack(&args[1..], &settings),
+        Some("variants") => variants(&settings),
+        Some("h2c-vectors") => h2c_vectors(&args[1..], &settings),
+        Some("simulate") => simulate(&args[1..]),
+        Some("tally") => tally(&args[1..]),
+        Some("replay") => replay(&args[1..], &settings),
+        Some("inspect") => inspect(&args[1..], &settings),
+        Some("diff") => diff(&args[1..]),
+        Some("split-key") => split_key(&args[1..]),
+        Some("recover") => recover(&args[1..]),
+        Some("convert-key") => convert_key(&args[1..]),
+        Some("mutate") => mutate(&args[1..], &settings),
+        Some("verify-head") => verify_head(&args[1..]),
+        Some("sign-head") => sign_head(&args[1..], &settings),
+        Some("audit") => audit(&args[1..], &settings),
+        Some("commit-answer") => commit_answer(&args[1..], &settings),
+        Some("reveal-answer") => reveal_answer(&args[1..], &settings),
+        Some("verify-answer") => verify_answer(&args[1..], &settings),
+        Some("submit") => submit(&args[1..], &settings),
+        Some("issue-cert") => issue_cert(&args[1..]),
+        Some("verify-cert") => verify_cert(&args[1..]),
+        Some("puzzle") if args.get(1).is_some_and(|a| a == "regen") => regen(&args[2..], &settings),
+        _ => solve(&args, &settings),
+    }
 }
 
-fn hasher() -> MapToCurveBasedHasher<G2Projective, DefaultFieldHasher<Sha256, 128>, WBMap<Config>> {
-    let wb_to_curve_hasher =
-        MapToCurveBasedHasher::<G2Projective, DefaultFieldHasher<Sha256, 128>, WBMap<Config>>::new(
-            &[1, 3, 3, 7],
-        )
-        .unwrap();
-    wb_to_curve_hasher
+// usage: export-verifier [out.sol]
+fn export_verifier(args: &[String]) {
+    let contract = solidity::verifier_contract("ChaosTheoryAuditor");
+    match args.first() {
+        Some(path) => fs::write(path, contract).unwrap(),
+        None => print!("{contract}"),
+    }
+}
+
+fn yes_no(b: bool) -> &'static str {
+    if b { "yes" } else { "NO" }
+}
+
+fn print_row<T: CanonicalSerialize>(name: &str, point: &T, audit: PointAudit) {
+    println!("{:<10} {:<20} {:<9} {}", name, short_hex(point), yes_no(audit.on_curve), yes_no(audit.in_subgroup));
+}
+
+fn g1_row(name: &str, p: &G1Affine) {
+    print_row(name, p, PointAudit::analyze(p));
+}
+
+fn g2_row(name: &str, p: &G2Affine) {
+    print_row(name, p, PointAudit::analyze(p));
+}
+
+// usage: inspect [blob.bin] [--verbose]
+fn inspect(args: &[String], settings: &Settings) {
+    let verbose = args.iter().any(|a| a == "--verbose");
+    let path = args.iter().find(|a| !a.starts_with("--")).map_or(settings.blob.as_path(), Path::new);
+    let auditor = settings.auditor();
+
+    let data = fs::read(path).unwrap();
+    // skip validation so that malformed points show up in the table instead of aborting
+    let blob = Blob::deserialize_uncompressed_unchecked(data.as_slice()).unwrap_or_else(|e| unparsed(path, &data, e));
+
+    println!("{:<10} {:<20} {:<9} {}", "field", "encoding", "on curve", "in subgroup");
+    g1_row("sender_pk", &blob.sender_pk);
+    g1_row("rec_pk", &blob.rec_pk);
+    g1_row("c.0", &blob.c.0);
+    g1_row("c.1", &blob.c.1);
+    g2_row("s", &blob.s);
+    g2_row("H(c)", &auditor.config().hash_to_curve(&blob.c, &[]));
+
+    if verbose {
+        println!();
+        println!("{}", blob.verbose());
+    }
+
+    let valid = auditor.verify(blob.sender_pk, &blob.c, blob.s, &[]);
+    println!();
+    match settings.format {
+        Format::Text => println!("auditor verdict: {}", if valid { "valid" } else { "INVALID" }),
+        Format::Json => println!("{{\"valid\":{valid}}}"),
+    }
+}
+
+// usage: split-key <sk.bin> <threshold> <n>
+// writes <sk.bin>.share-<i> for i in 1..=n
+fn split_key(args: &[String]) {
+    let [path, threshold, n] = args else {
+        panic!("usage: split-key <sk.bin> <threshold> <n>");
+    };
+    let sk = read_sk(path);
+    let shares = recovery::split(&sk, threshold.parse().unwrap(), n.parse().unwrap(), &mut rand::thread_rng()).unwrap();
+    for share in shares {
+        fs::write(format!("{path}.share-{}", share.index), share.to_bytes()).unwrap();
+    }
+}
+
+// usage: convert-key <in> <out> [--to raw|hex|json] [--public]
+// reads a secret key, or a public key with --public, in any format
+fn convert_key(args: &[String]) {
+    let usage = "usage: convert-key <in> <out> [--to raw|hex|json] [--public]";
+    let [input, out, ..] = args else {
+        panic!("{usage}");
+    };
+    let format = match args.iter().position(|a| a == "--to").map(|i| args.get(i + 1).expect(usage).as_str()) {
+        None | Some("json") => keys::KeyFormat::Json,
+        Some("hex") => keys::KeyFormat::Hex,
+        Some("raw") => keys::KeyFormat::Raw,
+        Some(_) => panic!("{usage}"),
+    };
+    let data = if args.iter().any(|a| a == "--public") {
+        keys::encode_pk(&read_g1(input), format)
+    } else {
+        keys::encode_sk(&read_sk(input), format)
+    };
+    fs::write(out, data).unwrap();
+}
+
+// usage: recover <share>... --out <sk.bin>
+fn recover(args: &[String]) {
+    let i = args.iter().position(|a| a == "--out").expect("usage: recover <share>... --out <sk.bin>");
+    let (paths, out) = (&args[..i], &args[i + 1]);
+    let shares = paths
+        .iter()
+        .map(|path| recovery::Share::from_bytes(&fs::read(path).unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    let sk = recovery::recover(&shares).unwrap();
+
+    let mut data = Vec::new();
+    sk.serialize_compressed(&mut data).unwrap();
+    fs::write(out, data).unwrap();
+    println!("recovered key from {} shares, public key {}", shares.len(), short_hex(&bls::public_key(&sk)));
+}
+
+// usage: mutate [blob.bin] [--out <dir>]
+// writes one corrupted blob per mutation and prints the expected verdicts
+fn mutate(args: &[String], settings: &Settings) {
+    let i = args.iter().position(|a| a == "--out").unwrap_or(args.len());
+    let out = args.get(i + 1).map_or(settings.out_dir.clone(), PathBuf::from);
+    let path = args[..i].first().map_or(settings.blob.as_path(), Path::new);
+
+    let blob = read_blob(path);
+    fs::create_dir_all(&out).unwrap();
+    for mutation in tamper::ALL_MUTATIONS {
+        let data = mutation.apply(&blob);
+        let file = out.join(format!("{}.bin", mutation.name()));
+        fs::write(&file, &data).unwrap();
+        println!("{} {:?}", file.display(), mutation.expected_verdict());
+    }
+}
+
+// usage: verify-head <log_pk.bin> <head.bin> [<newer_head.bin> <proof.bin>]
+// checks gossiped signed tree heads and, given two, that the newer extends the older
+fn verify_head(args: &[String]) {
+    let usage = "usage: verify-head <log_pk.bin> <head.bin> [<newer_head.bin> <proof.bin>]";
+    let read_head = |path: &String| transparency::SignedTreeHead::from_bytes(&fs::read(path).unwrap()).unwrap();
+
+    let log_pk = G1Affine::deserialize_compressed(fs::read(args.first().expect(usage)).unwrap().as_slice()).unwrap();
+    let head = read_head(args.get(1).expect(usage));
+    println!("head of size {}: signature {}", head.head.size, if head.verify(&log_pk) { "valid" } else { "INVALID" });
+
+    if let [_, _, newer, proof] = args {
+        let newer = read_head(newer);
+        println!("head of size {}: signature {}", newer.head.size, if newer.verify(&log_pk) { "valid" } else { "INVALID" });
+        let proof = fs::read(proof).unwrap();
+        let proof: Vec<transparency::Hash> = proof.chunks_exact(32).map(|c| c.try_into().unwrap()).collect();
+        let consistent = transparency::verify_consistency(&head.head, &newer.head, &proof);
+        println!("consistency: {}", if consistent { "valid" } else { "INVALID" });
+    }
+}
+
+// Minimal JSON string escaping for file names in audit events.
+fn json_str(s: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Audits one file, records it in `metrics` and returns its JSON event, or None
+// if the same content was already audited (editors and copies fire several
+// events per file).
+fn audit_file(
+    auditor: &Auditor,
+    path: &Path,
+    seen: &mut HashSet<[u8; 32]>,
+    cache: Option<&VerdictCache>,
+    metrics: &mut AuditMetrics,
+) -> Option<String> {
+    let data = fs::read(path).ok()?;
+    let hash: [u8; 32] = Sha256::digest(&data).into();
+    if !seen.insert(hash) {
+        return None;
+    }
+    let mut cached = false;
+    let verdict = match auditor.config().deserialize_blob(&data) {
+        Ok(blob) => {
+            let valid = match cache.and_then(|cache| cache.get(&blob)) {
+                Some(valid) => {
+                    cached = true;
+                    valid
+                }
+                None => {
+                    let valid = auditor.verify(blob.sender_pk, &blob.c, blob.s, &[]);
+                    if let Some(cache) = cache {
+                        cache.put(&blob, valid);
+                    }
+                    valid
+                }
+            };
+            if valid { Verdict::Valid } else { Verdict::Invalid }
+        }
+        Err(_) => Verdict::Malformed,
+    };
+    metrics.record(verdict, cached);
+    let verdict = match verdict {
+        Verdict::Valid => "valid",
+        Verdict::Invalid => "invalid",
+        Verdict::Malformed => "malformed",
+    };
+    let time = now();
+    Some(format!(
+        "{{\"event\":\"audit\",\"time\":{time},\"file\":{},\"sha256\":\"{}\",\"verdict\":\"{verdict}\",\"cached\":{cached}}}",
+        json_str(&path.display().to_string()),
+        hex::encode(&hash),
+    ))
 }
 
-#[derive(CanonicalSerialize, CanonicalDeserialize)]
-pub struct ElGamal(G1Affine, G1Affine);
+// Verdicts of blobs audited by earlier runs, by blob content, in the database
+// of the `storage` feature. The database is tied to the auditor's DST and
+// strictness, so it is never reused under another configuration.
+#[cfg(feature = "storage")]
+struct VerdictCache(storage::AuditDb);
+
+#[cfg(feature = "storage")]
+impl VerdictCache {
+    fn open(path: &str, auditor: &Auditor) -> Result<Self, String> {
+        let db = storage::AuditDb::open(path).map_err(|_| format!("cannot open {path}"))?;
+        let strict = auditor.strict_verification() == zkhack_puzzle_solution::strict::StrictVerification::On;
+        db.bind_auditor(auditor.config().dst(), strict)
+            .map_err(|_| format!("{path} holds verdicts of another DST or strictness"))?;
+        Ok(VerdictCache(db))
+    }
 
-impl ElGamal {
-    pub fn hash_to_curve(&self) -> G2Affine {
-        let mut data = Vec::new();
-        self.serialize_uncompressed(&mut data).unwrap();
+    fn get(&self, blob: &Blob) -> Option<bool> {
+        self.0.verdict(blob).unwrap()
+    }
 
-        hasher().hash(&data).unwrap()
+    fn put(&self, blob: &Blob, valid: bool) {
+        let id = self.0.insert_blob(blob, now()).unwrap();
+        self.0.record_verdict(id, valid, now()).unwrap();
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Message(G1Affine);
+#[cfg(not(feature = "storage"))]
+struct VerdictCache;
+
+#[cfg(not(feature = "storage"))]
+impl VerdictCache {
+    fn open(_path: &str, _auditor: &Auditor) -> Result<Self, String> {
+        Err("audit --cache requires the `storage` feature".into())
+    }
 
-struct Sender {
-    pub sk: Fr,
-    pub pk: G1Affine,
+    fn get(&self, _blob: &Blob) -> Option<bool> {
+        None
+    }
+
+    fn put(&self, _blob: &Blob, _valid: bool) {}
+}
+
+fn is_blob_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "bin")
 }
 
-pub struct Receiver {
-    pk: G1Affine,
+// Where audit events go: stdout, the log file if one is set, and the
+// transparency log over that file, whose head `sign_head` signs and writes
+// when a path and key were given.
+struct EventLog {
+    file: Option<File>,
+    tree: transparency::TransparencyLog,
+    head: Option<(String, Fr)>,
 }
 
-pub struct Auditor {}
+impl EventLog {
+    fn open(settings: &Settings, head: Option<(String, Fr)>) -> Self {
+        let Some(path) = &settings.log else {
+            if head.is_some() {
+                eprintln!("signing tree heads needs the `log` setting");
+                process::exit(2);
+            }
+            return EventLog { file: None, tree: transparency::TransparencyLog::new(), head };
+        };
+        let tree = transparency::TransparencyLog::from_lines(&fs::read_to_string(path).unwrap_or_default());
+        let file = OpenOptions::new().create(true).append(true).open(path).unwrap();
+        EventLog { file: Some(file), tree, head }
+    }
 
-impl Sender {
-    pub fn send(&self, m: Message, r: &Receiver) -> ElGamal {
-        let c_2: G1Affine = (r.pk.mul(&self.sk) + m.0).into_affine();
-        ElGamal(self.pk, c_2)
+    fn emit(&mut self, event: String) {
+        println!("{event}");
+        if let Some(file) = self.file.as_mut() {
+            writeln!(file, "{event}").unwrap();
+            self.tree.append(event.as_bytes());
+        }
     }
 
-    pub fn authenticate(&self, c: &ElGamal) -> G2Affine {
-        let hash_c = c.hash_to_curve();
-        hash_c.mul(&self.sk).into_affine()
+    fn sign_head(&self) {
+        if let Some((path, sk)) = &self.head {
+            fs::write(path, self.tree.sign_head(sk, now()).to_bytes()).unwrap();
+        }
+    }
+}
+
+// usage: audit [--watch] [--cache <audit.db>] [--metrics <audit.prom>]
+//              [--sign-head <head.bin> --key <auditor.sk>] <dir>
+// audits every *.bin file in <dir> and prints one JSON event per line, also
+// appended to the log file if one is set, then a summary event; with --watch
+// keeps running and audits files as they are dropped into <dir>; with --cache
+// reuses verdicts of blobs audited by earlier runs under the same DST and
+// strictness; with --metrics writes the counters in the Prometheus text
+// format, and with --sign-head the signed head of the transparency log over
+// the log file, after the batch and after every watched file
+fn audit(args: &[String], settings: &Settings) {
+    let usage = "usage: audit [--watch] [--cache <audit.db>] [--metrics <audit.prom>] \
+                 [--sign-head <head.bin> --key <auditor.sk>] <dir>";
+    let watch = args.iter().any(|a| a == "--watch");
+    let flag_at = |name: &str| args.iter().position(|a| a == name);
+    let auditor = settings.auditor();
+    let cache = flag_at("--cache").map(|i| {
+        VerdictCache::open(args.get(i + 1).expect(usage), &auditor).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            process::exit(2)
+        })
+    });
+    let metrics_path = flag_at("--metrics").map(|i| args.get(i + 1).expect(usage));
+    let head = flag_at("--sign-head").map(|i| {
+        let key = flag_at("--key").and_then(|j| args.get(j + 1)).expect(usage);
+        (args.get(i + 1).expect(usage).clone(), read_sk(key))
+    });
+    let is_value =
+        |i: usize| i > 0 && ["--cache", "--metrics", "--sign-head", "--key"].contains(&args[i - 1].as_str());
+    let dir = (0..args.len())
+        .find(|&i| !args[i].starts_with("--") && !is_value(i))
+        .map(|i| Path::new(&args[i]))
+        .expect(usage);
+
+    let mut log = EventLog::open(settings, head);
+    let mut seen = HashSet::new();
+
+    let start = Instant::now();
+    let mut metrics = AuditMetrics::new();
+    let write_metrics = |metrics: &AuditMetrics| {
+        if let Some(path) = metrics_path {
+            fs::write(path, metrics.to_prometheus(start.elapsed().as_secs_f64())).unwrap();
+        }
+    };
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir).unwrap().map(|e| e.unwrap().path()).filter(|p| is_blob_file(p)).collect();
+    paths.sort();
+    for path in paths {
+        if let Some(event) = audit_file(&auditor, &path, &mut seen, cache.as_ref(), &mut metrics) {
+            log.emit(event);
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    log.emit(format!(
+        "{{\"event\":\"summary\",\"time\":{},\"audited\":{},\"valid\":{},\"invalid\":{},\"malformed\":{},\"cached\":{},\
+         \"pairings\":{},\"verifications_per_sec\":{:.1}}}",
+        now(),
+        metrics.audited(),
+        metrics.valid,
+        metrics.invalid,
+        metrics.malformed,
+        metrics.cached,
+        metrics.pairings(),
+        metrics.verifications_per_sec(elapsed),
+    ));
+    write_metrics(&metrics);
+    log.sign_head();
+
+    if watch {
+        watch_dir(dir, |path| {
+            if let Some(event) = audit_file(&auditor, path, &mut seen, cache.as_ref(), &mut metrics) {
+                log.emit(event);
+                write_metrics(&metrics);
+                log.sign_head();
+            }
+        });
+    }
+}
+
+// usage: sign-head <auditor.sk> <head.bin> [--proof-from <old_head.bin> <proof.bin>]
+// signs the head of the transparency log over the audit log file; with
+// --proof-from also writes the proof that it extends an older head, for
+// verify-head
+fn sign_head(args: &[String], settings: &Settings) {
+    let usage = "usage: sign-head <auditor.sk> <head.bin> [--proof-from <old_head.bin> <proof.bin>]";
+    let (sk, out) = (read_sk(args.first().expect(usage)), args.get(1).expect(usage));
+    let path = settings.log.as_ref().unwrap_or_else(|| {
+        eprintln!("sign-head needs the `log` setting");
+        process::exit(2)
+    });
+    let tree = transparency::TransparencyLog::from_lines(&fs::read_to_string(path).unwrap());
+    let head = tree.sign_head(&sk, now());
+    fs::write(out, head.to_bytes()).unwrap();
+    println!("head of size {}", head.head.size);
+
+    if let Some(i) = args.iter().position(|a| a == "--proof-from") {
+        let old = transparency::SignedTreeHead::from_bytes(&fs::read(args.get(i + 1).expect(usage)).unwrap()).unwrap();
+        let proof = tree.consistency_proof(old.head.size, head.head.size).expect("the old head is larger than the log");
+        fs::write(args.get(i + 2).expect(usage), proof.concat()).unwrap();
+    }
+}
+
+#[cfg(feature = "watch")]
+fn watch_dir(dir: &Path, mut on_file: impl FnMut(&Path)) {
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).unwrap();
+    watcher.watch(dir, RecursiveMode::NonRecursive).unwrap();
+    for event in rx {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                eprintln!("watch error: {e}");
+                continue;
+            }
+        };
+        // a file still being written fails to parse; it is audited again on the next write
+        if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            for path in event.paths.iter().filter(|p| is_blob_file(p)) {
+                on_file(path);
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "watch"))]
+fn watch_dir(_dir: &Path, _on_file: impl FnMut(&Path)) {
+    panic!("audit --watch requires the `watch` feature");
+}
+
+// usage: puzzle regen --seed <s> [--out <blob.bin>]
+// recreates the blob of a seeded instance and prints its answer
+fn regen(args: &[String], settings: &Settings) {
+    let usage = "usage: puzzle regen --seed <s> [--out <blob.bin>]";
+    let flag = |name: &str| args.iter().position(|a| a == name).map(|i| args.get(i + 1).expect(usage));
+    let seed = flag("--seed").expect(usage).parse().unwrap();
+    let out = flag("--out").map_or(settings.out_path("blob.bin"), PathBuf::from);
+
+    let instance = Instance::from_seed(seed);
+    fs::write(&out, instance.blob_bytes()).unwrap();
+    println!("seed {seed}: wrote {}, answer {} (index {})", out.display(), instance.answer(), instance.index);
+}
+
+fn read_blob(path: &Path) -> Blob {
+    parse_blob(path, &fs::read(path).unwrap())
+}
+
+// exits with a diagnostic naming the failing field instead of panicking
+fn parse_blob(path: &Path, data: &[u8]) -> Blob {
+    Blob::deserialize_uncompressed(data).unwrap_or_else(|e| unparsed(path, data, e))
+}
+
+// reports why `data` doesn't parse as a blob and exits
+fn unparsed(path: &Path, data: &[u8], e: SerializationError) -> ! {
+    match diagnostics::diagnose_blob(data) {
+        Some(diagnostic) => eprintln!("{}: {diagnostic}", path.display()),
+        None => eprintln!("{}: {e}", path.display()),
     }
+    process::exit(1);
+}
+
+// usage: commit-answer <answer> --solver <id> [--out <answer.opening>]
+// prints the commitment to submit and keeps the opening for the reveal
+fn commit_answer(args: &[String], settings: &Settings) {
+    let usage = "usage: commit-answer <answer> --solver <id> [--out <answer.opening>]";
+    let flag = |name: &str| args.iter().position(|a| a == name).map(|i| args.get(i + 1).expect(usage));
+    let answer = args.first().expect(usage).parse().unwrap();
+    let solver = flag("--solver").expect(usage);
+    let blob = read_blob(&settings.blob);
+    let out = flag("--out").map_or(settings.out_path("answer.opening"), PathBuf::from);
+
+    let opening = answer::Opening::new(answer, &mut rand::thread_rng());
+    fs::write(out, opening.to_bytes()).unwrap();
+    println!("{}", hex::encode(&opening.commit(solver.as_bytes(), &blob).0));
+}
+
+// usage: reveal-answer [answer.opening]
+fn reveal_answer(args: &[String], settings: &Settings) {
+    let path = args.first().map_or(settings.out_path("answer.opening"), PathBuf::from);
+    let opening = answer::Opening::from_bytes(&fs::read(path).unwrap()).unwrap();
+    println!("answer {} salt {}", opening.answer, hex::encode(&opening.salt));
 }
 
-impl Auditor {
-    pub fn check_auth(sender_pk: G1Affine, c: &ElGamal, s: G2Affine) -> bool {
-        let lhs = { Bls12_381::pairing(G1Projective::generator(), s) };
+// usage: verify-answer <commitment> <answer> <salt> --solver <id>
+fn verify_answer(args: &[String], settings: &Settings) {
+    let usage = "usage: verify-answer <commitment> <answer> <salt> --solver <id>";
+    let flag = |name: &str| args.iter().position(|a| a == name).map(|i| args.get(i + 1).expect(usage));
+    let [commitment, value, salt, ..] = args else {
+        panic!("{usage}");
+    };
+    let solver = flag("--solver").expect(usage);
+    let blob = read_blob(&settings.blob);
+
+    let commitment = answer::AnswerCommitment(hex::decode(commitment).unwrap().try_into().unwrap());
+    let opening = answer::Opening { answer: value.parse().unwrap(), salt: hex::decode(salt).unwrap().try_into().unwrap() };
+    let valid = answer::verify_reveal(&commitment, solver.as_bytes(), &blob, &opening);
+    println!("reveal: {}", if valid { "valid" } else { "INVALID" });
+}
 
-        let hash_c = c.hash_to_curve();
-        let rhs = { Bls12_381::pairing(sender_pk, hash_c) };
+// usage: submit <answer> --key <solver.sk> --endpoint <url>
+// POSTs the signed submission as application/octet-stream
+fn submit(args: &[String], settings: &Settings) {
+    let usage = "usage: submit <answer> --key <solver.sk> --endpoint <url>";
+    let flag = |name: &str| args.iter().position(|a| a == name).map(|i| args.get(i + 1).expect(usage));
+    let answer = args.first().expect(usage).parse().unwrap();
+    let sk = read_sk(flag("--key").expect(usage));
+    let endpoint = flag("--endpoint").expect(usage);
+    let blob = read_blob(&settings.blob);
 
-        lhs == rhs
+    let submission = submission::Submission::new(&sk, answer, &blob);
+    println!("submitting answer {answer} as {}", short_hex(&submission.solver_pk));
+    println!("{}", post(endpoint, &submission.to_bytes()));
+}
+
+#[cfg(feature = "submit")]
+fn post(url: &str, body: &[u8]) -> String {
+    let response = ureq::post(url).set("Content-Type", "application/octet-stream").send_bytes(body).unwrap();
+    response.into_string().unwrap()
+}
+
+#[cfg(not(feature = "submit"))]
+fn post(_url: &str, _body: &[u8]) -> String {
+    panic!("submit requires the `submit` feature");
+}
+
+fn print_answer(settings: &Settings, m: u128) {
+    match settings.format {
+        Format::Text => println!("msg found = {m}"),
+        Format::Json => println!("{{\"answer\":{m}}}"),
     }
 }
 
-#[derive(CanonicalSerialize, CanonicalDeserialize)]
-pub struct Blob {
-    pub sender_pk: G1Affine,
-    pub c: ElGamal,
-    pub s: G2Affine,
-    pub rec_pk: G1Affine,
+// public key in any of the `keys` formats
+fn read_g1(path: &str) -> G1Affine {
+    keys::decode_pk(&fs::read(path).unwrap()).unwrap()
 }
 
-fn generate_message_space() -> ([u64; 10],[Message; 10]) {
-    let g1 = G1Projective::generator();
-    let msgs = [
-        390183091831u64,
-        4987238947234982,
-        84327489279482,
-        8492374892742,
-        5894274824234,
-        4982748927426,
-        48248927348927427,
-        489274982749828,
-        99084321987189371,
-        8427489729843712893,
-    ];
-    let g_m = msgs.iter()
-        .map(|&msg_i| Message(g1.mul(Fr::from(msg_i)).into_affine()))
-        .collect::<Vec<_>>()
-        .try_into()
+// secret key in any of the `keys` formats
+fn read_sk(path: &str) -> Fr {
+    keys::decode_sk(&fs::read(path).unwrap()).unwrap()
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+// usage: issue-cert <auditor.sk> <identity> <pk.bin> <not_before> <not_after> <cert.bin>
+// times are unix seconds
+fn issue_cert(args: &[String]) {
+    let [sk, identity, pk, not_before, not_after, out] = args else {
+        panic!("usage: issue-cert <auditor.sk> <identity> <pk.bin> <not_before> <not_after> <cert.bin>");
+    };
+    let sk = read_sk(sk);
+    let auditor = Auditor::with_key(SchemeConfig::default(), sk);
+    let cert = auditor
+        .issue_certificate(identity, read_g1(pk), not_before.parse().unwrap(), not_after.parse().unwrap())
         .unwrap();
-    return (msgs,g_m); // modified to return the u64 msgs just for display purposes. 
+    fs::write(out, cert.to_bytes()).unwrap();
 }
 
-pub fn main() {
+// usage: verify-cert <auditor_pk.bin> <cert.bin> [now]
+fn verify_cert(args: &[String]) {
+    let usage = "usage: verify-cert <auditor_pk.bin> <cert.bin> [now]";
+    let issuer_pk = read_g1(args.first().expect(usage));
+    let cert = cert::Certificate::from_bytes(&fs::read(args.get(1).expect(usage)).unwrap()).unwrap();
+    let now = args.get(2).map_or_else(now, |t| t.parse().unwrap());
+
+    println!("{} -> {}", cert.identity, short_hex(&cert.pk));
+    match cert.verify(&issuer_pk, now) {
+        Ok(()) => println!("certificate: valid"),
+        Err(e) => println!("certificate: INVALID ({e:?})"),
+    }
+}
+
+// usage: calibrate [chaos-theory.toml]
+// measures single-core throughput and records it, with a thread count, in the settings file
+fn calibrate(args: &[String], settings: &mut Settings) {
+    let path = args.first().map_or(SETTINGS_FILE, String::as_str);
+    let calibration = tuning::calibrate();
+    println!("GT multiplications: {:.0}/s per core", calibration.gt_mul_rate);
+    println!("pairings:           {:.0}/s per core", calibration.pairing_rate);
+    println!("cores:              {}", calibration.cores);
+    settings.persist_calibration(path, &calibration).unwrap();
+    println!("wrote {path}");
+}
+
+// usage: estimate <bits> [--max-seconds <s>]
+// predicts the cost of brute-forcing an exponent below 2^bits
+fn estimate(args: &[String], settings: &Settings) {
+    let usage = "usage: estimate <bits> [--max-seconds <s>]";
+    let bits = args.first().expect(usage).parse().unwrap();
+    let estimate = attack::estimate(bits, settings.rates().as_ref());
+    println!("{estimate}");
+
+    if let Some(i) = args.iter().position(|a| a == "--max-seconds") {
+        let max_seconds = args.get(i + 1).expect(usage).parse().unwrap();
+        let budget = attack::Budget { max_seconds, max_memory_bytes: u64::MAX };
+        if !estimate.fits(&budget) {
+            eprintln!("refusing: estimate exceeds the budget of {} s", budget.max_seconds);
+            process::exit(1);
+        }
+    }
+}
+
+// prints each step of the attack
+struct Explainer;
+
+impl attack::AttackObserver for Explainer {
+    fn hashed(&mut self, hash_c: &G2Affine) {
+        println!("H(c)                          = {}", full_hex(hash_c));
+    }
+
+    fn pairing(&mut self, name: &str, value: &PairingOutput<Bls12_381>) {
+        println!("{name:<29} = {}", short_hex(value));
+    }
+
+    fn target(&mut self, target: &PairingOutput<Bls12_381>) {
+        println!("e(c.1, H(c)) - e(rec_pk, s)   = {}", short_hex(target));
+        println!("  since c.1 = m + sk * rec_pk and s = sk * H(c), this is e(m, H(c)):");
+    }
+
+    fn candidate(&mut self, index: usize, value: &PairingOutput<Bls12_381>, matched: bool) {
+        let name = format!("e(m[{index}], H(c))");
+        let verdict = if matched { "match" } else { "no match" };
+        println!("{name:<29} = {}  {verdict}", short_hex(value));
+    }
+}
+
+// usage: attack [blob] [--explain] [--symbolic] [--bits <n>|auto [--max-seconds <s>] [--force]]
+//               [--report <report.json> --key <solver.sk>]
+// recovers the plaintext index over the puzzle's message space; with --bits,
+// falls back to searching exponents below 2^n, or with `auto` below 2^16,
+// 2^24, 2^32, ... until found or the budget is exhausted. The search is
+// refused unless `estimate` predicts it to fit in --max-seconds (an hour by
+// default), or --force is given. --symbolic prints
+// the scheme's equations with the recovered values substituted; --report
+// writes the result, timing and machine as a report signed with the key
+fn attack(args: &[String], settings: &Settings) {
+    let usage = "usage: attack [blob] [--explain] [--symbolic] [--bits <n>|auto [--max-seconds <s>] [--force]] \
+                 [--report <report.json> --key <solver.sk>]";
+    let flag = |name: &str| args.iter().position(|a| a == name).map(|i| args.get(i + 1).expect(usage).as_str());
+    let bits = flag("--bits");
+    let max_seconds = flag("--max-seconds").map_or(attack::DEFAULT_MAX_SECONDS, |s| s.parse().expect(usage));
+    let report = flag("--report").map(|path| (path, read_sk(flag("--key").expect(usage))));
+    // the blob is the first argument that is neither a flag nor a flag's value
+    let is_value =
+        |i: usize| i > 0 && ["--bits", "--max-seconds", "--report", "--key"].contains(&args[i - 1].as_str());
+    let path = (0..args.len())
+        .find(|&i| !args[i].starts_with("--") && !is_value(i))
+        .map_or(settings.blob.clone(), |i| PathBuf::from(&args[i]));
+    let blob = read_blob(&path);
+    let config = settings.scheme_config().unwrap();
+    let space = MessageSpace::puzzle();
+    let start = Instant::now();
+    let index = if args.iter().any(|a| a == "--explain") {
+        attack::recover_index_observed(&blob, &config, &[], &space, &mut Explainer)
+    } else {
+        attack::recover_index(&blob, &config, &[], &space)
+    };
+    let answer = match index {
+        Some(i) => Some(space.exponents()[i]),
+        None => {
+            eprintln!("the plaintext is not in the message space");
+            let n = match bits {
+                None => None,
+                // the auto search stops after `budget` exponents
+                Some("auto") => Some(u128::BITS - settings.budget.saturating_sub(1).leading_zeros()),
+                Some(n) => Some(n.parse().expect(usage)),
+            };
+            if let Some(n) = n {
+                let estimate = attack::estimate(n, settings.rates().as_ref());
+                let budget = attack::Budget { max_seconds, max_memory_bytes: u64::MAX };
+                if !estimate.fits(&budget) && !args.iter().any(|a| a == "--force") {
+                    eprintln!("{estimate}");
+                    eprintln!("refusing: estimate exceeds the budget of {max_seconds} s; --force searches anyway");
+                    process::exit(1);
+                }
+            }
+            match (bits, n) {
+                (Some("auto"), _) => attack::recover_exponent_auto(&blob, &config, &[], settings.budget, |bits| {
+                    eprintln!("trying exponents below 2^{bits}");
+                }),
+                (_, Some(n)) => {
+                    let end = if n >= 128 { u128::MAX } else { 1 << n };
+                    attack::recover_exponent(&blob, &config, &[], 0..end)
+                }
+                _ => None,
+            }
+        }
+    };
+    let elapsed = start.elapsed();
+    if args.iter().any(|a| a == "--symbolic") {
+        print_symbolic(&blob, &config, answer.map(Message::from_exponent));
+    }
+    if let (Some((path, sk)), Some(exponent)) = (report, answer) {
+        let result = AttackResult {
+            index,
+            exponent,
+            solver: if index.is_some() { "message-space" } else { "brute-force" }.into(),
+            elapsed_ms: elapsed.as_millis() as u64,
+            machine: format!(
+                "{}/{}/{} cpus",
+                env::consts::OS,
+                env::consts::ARCH,
+                thread::available_parallelism().map_or(1, |n| n.get())
+            ),
+        };
+        fs::write(path, AttackReport::new(&sk, &blob, result).unwrap().to_json()).unwrap();
+    }
+    match answer {
+        Some(m) => print_answer(settings, m),
+        None => process::exit(1),
+    }
+}
+
+// prints each equation of the scheme and the attack, again with the values
+// known from the blob substituted, and whether it holds
+fn print_symbolic(blob: &Blob, config: &SchemeConfig, m: Option<Message>) {
+    let env = symbolic::public_env(blob, config, &[], m);
+    for relation in symbolic::protocol_relations() {
+        println!("{:<13} {relation}", relation.name);
+        println!("{:<13} {} = {}", "", relation.lhs.substituted(&env), relation.rhs.substituted(&env));
+        match relation.check(&env) {
+            Some(true) => println!("{:<13} holds", ""),
+            Some(false) => println!("{:<13} DOES NOT HOLD", ""),
+            None => println!("{:<13} needs {}", "", relation.unbound(&env).join(", ")),
+        }
+    }
+}
+
+// Whether `command` works the same whatever the selected variant. All the
+// others read, write or attack blobs of the puzzle's scheme.
+fn variant_independent(command: Option<&str>) -> bool {
+    matches!(
+        command,
+        Some(
+            "variants"
+                | "h2c-vectors"
+                | "calibrate"
+                | "split-key"
+                | "recover"
+                | "convert-key"
+                | "verify-head"
+                | "sign-head"
+                | "commit-answer"
+                | "reveal-answer"
+                | "verify-answer"
+                | "submit"
+                | "issue-cert"
+                | "verify-cert"
+        )
+    )
+}
+
+// usage: variants
+// the selected one is marked with `*`
+fn variants(settings: &Settings) {
+    for variant in VARIANTS {
+        let p = variant.properties();
+        let mark = if variant == settings.variant { "*" } else { " " };
+        println!(
+            "{mark} {:<9} ind-cpa: {:<3} tag-unforgeable: {:<3} key-private: {:<3} known break: {}",
+            variant.name(),
+            yes_no(p.ind_cpa),
+            yes_no(p.tag_unforgeable),
+            yes_no(p.key_private),
+            p.known_break.unwrap_or("none")
+        );
+    }
+}
+
+// usage: h2c-vectors [--suite g1|g2] [--rfc] [--check <vectors.txt>]
+// prints RFC 9380 vectors for the configured DST (the RFC's with --rfc), or
+// checks the vectors in a file in the RFC's layout, under the file's DST if it
+// names one; exits with 1 on a mismatch.
+fn h2c_vectors(args: &[String], settings: &Settings) {
+    let usage = "usage: h2c-vectors [--suite g1|g2] [--rfc] [--check <vectors.txt>]";
+    let flag = |name: &str| args.iter().position(|a| a == name).map(|i| args.get(i + 1).expect(usage));
+    let suite = match flag("--suite").map(String::as_str) {
+        Some("g1") => Suite::G1,
+        None | Some("g2") => Suite::G2,
+        Some(_) => panic!("{usage}"),
+    };
+    let dst = if args.iter().any(|a| a == "--rfc") { suite.rfc_dst().to_vec() } else { settings.dst.clone() };
+
+    if dst == DST {
+        eprintln!("note: the legacy [1, 3, 3, 7] DST is not a standard one; pass --rfc for the RFC's vectors");
+    }
+
+    let Some(path) = flag("--check") else {
+        let vectors = h2c_vectors::generate(suite, &dst).expect("invalid DST");
+        return print!("{}", h2c_vectors::render(suite, &dst, &vectors));
+    };
+    let (file_dst, vectors) = h2c_vectors::parse(&fs::read_to_string(path).unwrap()).expect("malformed vectors");
+    let dst = file_dst.unwrap_or(dst);
+    let mismatches = h2c_vectors::check(suite, &dst, &vectors).expect("invalid DST");
+    for (i, v) in vectors.iter().enumerate() {
+        let status = if mismatches.contains(&i) { "MISMATCH" } else { "ok" };
+        println!("{status:<8} msg = {}", String::from_utf8_lossy(&v.msg));
+    }
+    if !mismatches.is_empty() {
+        process::exit(1);
+    }
+}
+
+// usage: diff <a.bin> <b.bin>
+// compares the raw encodings field by field; exits with 1 if they differ
+fn diff(args: &[String]) {
+    let usage = "usage: diff <a.bin> <b.bin>";
+    let (a, b) = (fs::read(args.first().expect(usage)).unwrap(), fs::read(args.get(1).expect(usage)).unwrap());
+    let (Ok(ra), Ok(rb)) = (BlobReader::new(&a), BlobReader::new(&b)) else {
+        eprintln!("both files must hold an uncompressed blob of {} bytes", diagnostics::BLOB_LEN);
+        process::exit(2);
+    };
+
+    let mut same = true;
+    for field in Field::ALL {
+        let (x, y) = (ra.raw(field), rb.raw(field));
+        let differing: Vec<usize> = (0..x.len()).filter(|&i| x[i] != y[i]).collect();
+        match (differing.first(), differing.last()) {
+            (Some(first), Some(last)) => {
+                same = false;
+                println!(
+                    "{:<10} differs in {} of {} bytes, offsets {first}..={last}",
+                    field.name(),
+                    differing.len(),
+                    x.len()
+                );
+            }
+            _ => println!("{:<10} same", field.name()),
+        }
+    }
+    let field_eq = |fields: &[Field]| fields.iter().all(|&f| ra.raw(f) == rb.raw(f));
+    println!("same sender:     {}", yes_no(field_eq(&[Field::SenderPk])));
+    println!("same ciphertext: {}", yes_no(field_eq(&[Field::C0, Field::C1])));
+    println!("same tag:        {}", yes_no(field_eq(&[Field::S])));
+    println!("same receiver:   {}", yes_no(field_eq(&[Field::RecPk])));
+    if !same {
+        process::exit(1);
+    }
+}
+
+// usage: simulate [--senders N] [--receivers M] [--messages K] [--record run.transcript]
+// K messages per sender, over the puzzle's message space
+fn simulate(args: &[String]) {
+    let flag = |name: &str, default: usize| {
+        args.iter().position(|a| a == name).map_or(default, |i| args[i + 1].parse().unwrap())
+    };
+    let (senders, receivers, messages) = (flag("--senders", 4), flag("--receivers", 4), flag("--messages", 8));
+    let run = simulate::simulate(senders, receivers, messages, &MessageSpace::puzzle(), &mut rand::thread_rng());
+    println!("{}", run.stats);
+    if let Some(i) = args.iter().position(|a| a == "--record") {
+        fs::write(&args[i + 1], run.transcript().to_bytes()).unwrap();
+    }
+}
+
+// usage: tally [--voters N] [--candidates K] [--trustees n] [--threshold t]
+// runs an election with random votes end to end
+fn tally(args: &[String]) {
+    let flag = |name: &str, default: usize| {
+        args.iter().position(|a| a == name).map_or(default, |i| args[i + 1].parse().unwrap())
+    };
+    let (voters, candidates) = (flag("--voters", 20), flag("--candidates", 3));
+    let (trustees, threshold) = (flag("--trustees", 5), flag("--threshold", 3));
+    let mut rng = rand::thread_rng();
+
+    let election = tally::Election::new(candidates, voters as u64).expect("too many candidates and voters to tally");
+    let tally_sk = Fr::rand(&mut rng);
+    let authority = Receiver::new(bls::public_key(&tally_sk));
+    let shares = recovery::split(&tally_sk, threshold, trustees, &mut rng).unwrap();
+
+    let mut expected = vec![0u64; candidates];
+    let ballots: Vec<ElGamal> = (0..voters)
+        .map(|_| {
+            let choice = rng.gen_range(0..candidates);
+            expected[choice] += 1;
+            let vote = Message::from_exponent(election.encode(choice).unwrap());
+            Sender::new(Fr::rand(&mut rng)).send(vote, &authority)
+        })
+        .collect();
+
+    let total = tally::sum(&ballots);
+    let partials: Vec<_> = shares[..threshold].iter().map(|share| tally::partial_decrypt(share, &total)).collect();
+    let counts = tally::tally(&election, &total, &partials).unwrap();
+    for (candidate, (count, expected)) in counts.iter().zip(&expected).enumerate() {
+        println!("candidate {candidate}: {count} votes (cast: {expected})");
+    }
+}
+
+// usage: replay <run.transcript>
+fn replay(args: &[String], settings: &Settings) {
+    let transcript = Transcript::from_bytes(&fs::read(&args[0]).unwrap()).expect("invalid transcript");
+    let report = transcript.replay(&settings.auditor());
+    println!("{} blobs, {} recorded verdicts", report.blobs, report.verdicts);
+    if !report.is_consistent() {
+        println!("verdicts differ on blobs {:?}", report.mismatches);
+        process::exit(1);
+    }
+}
+
+// usage: [--verbose]
+fn solve(args: &[String], settings: &Settings) {
+    let verbose = args.iter().any(|a| a == "--verbose");
+
     welcome();
     puzzle(PUZZLE_DESCRIPTION);
 
     let (msgs_u64,messages) = generate_message_space();
 
-    let mut file = File::open("blob.bin").unwrap();
+    let mut file = File::open(&settings.blob).unwrap();
     let mut data = Vec::new();
     file.read_to_end(&mut data).unwrap();
-    let blob = Blob::deserialize_uncompressed(data.as_slice()).unwrap();
+    let blob = parse_blob(&settings.blob, &data);
+    if verbose {
+        println!("{}", blob.verbose());
+    }
 
     // ensure that blob is correct
-    assert!(Auditor::check_auth(blob.sender_pk, &blob.c, blob.s));
+    let auditor = settings.auditor();
+    let hashed = HashedCiphertext::new(blob.c.clone(), auditor.config(), &[]);
+    assert!(auditor.verify_hashed(blob.sender_pk, &hashed, blob.s));
 
     /* Implement your attack here, to find the index of the encrypted message */
 
     let divs = { Bls12_381::pairing(blob.rec_pk, blob.s) };
 
-    let hash_c = blob.c.hash_to_curve();
+    let hash_c = hashed.hash();
     let ups = { Bls12_381::pairing(blob.c.1, hash_c) };
 
     let paired_msg = ups-divs;
 
     // assuming the msg space is limitd to that returned by the function "generate_message_space"
     let mut i=0;
+    let mut found = false;
     for msg in messages { 
         let pmi = { Bls12_381::pairing(msg.0, hash_c) };
         if paired_msg == pmi {
-            println!("msg found = {}", msgs_u64[i]);
+            print_answer(settings, msgs_u64[i] as u128);
+            found = true;
         }
         i+=1;
     }
 
+    // otherwise fall back to brute-forcing small exponents, up to the configured budget
+    if !found {
+        println!("msg not in the message space, trying exponents below {}", settings.budget);
+        let base = { Bls12_381::pairing(G1Affine::generator(), hash_c) };
+        match dlog::brute_force(&base, &paired_msg, 0..settings.budget) {
+            Some(m) => print_answer(settings, m),
+            None => println!("msg not found"),
+        }
+    }
+
     // spoiler alert the plaintext msg is: 8492374892742
 
     /* End of attack */
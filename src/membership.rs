@@ -0,0 +1,208 @@
+//! Proofs that a sender's key is in an allowlist, without saying which.
+//!
+//! A `MembershipProof` is a one-out-of-many Schnorr proof (Cramer, Damgård and
+//! Schoenmakers' OR composition): for every key `pk_i` of the list there is a
+//! pair `(c_i, z_i)` with commitment `A_i = z_i * g1 - c_i * pk_i`, and the
+//! `c_i` must add up to the Fiat-Shamir challenge over the list, the context
+//! and every `A_i`. Only the holder of one of the secret keys can close the
+//! sum; the pairs are distributed the same whichever key it was. Proofs grow
+//! linearly with the list, 64 bytes per key.
+//!
+//! Blobs carry `sender_pk` for the tag, so a proof attached to a blob would
+//! hide nothing and, not being bound to the key that signed the tag, could
+//! be made by any listed key for a blob from an unlisted sender. The auditor
+//! therefore checks a blob's sender against the list directly
+//! (`check_auth_with_allowlist`); the proof is for contexts where the key
+//! itself stays private, e.g. a tag that verifies without it.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup, Group, VariableBaseMSM};
+use ark_ff::{UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+
+use crate::{fr_utils, Auditor, ElGamal, Error};
+
+pub const MEMBERSHIP_DST: &[u8] = b"chaos-theory-membership-v1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MembershipProof {
+    /// `(c_i, z_i)` for every key of the allowlist, in its order.
+    pub responses: Vec<(Fr, Fr)>,
+}
+
+fn challenge(allowlist: &[G1Affine], context: &[u8], commitments: &[G1Affine]) -> Fr {
+    let mut transcript = Vec::new();
+    allowlist.serialize_compressed(&mut transcript).unwrap();
+    context.serialize_compressed(&mut transcript).unwrap();
+    commitments.serialize_compressed(&mut transcript).unwrap();
+    fr_utils::hash_to_scalar(&transcript, MEMBERSHIP_DST).unwrap()
+}
+
+// z * g1 - c * pk
+fn commitment(pk: &G1Affine, c: Fr, z: Fr) -> G1Projective {
+    G1Projective::msm(&[G1Affine::generator(), *pk], &[z, -c]).unwrap()
+}
+
+impl MembershipProof {
+    /// Proves that the public key of `sk` is in `allowlist`, bound to
+    /// `context`. Fails with `UnknownSender` if it is not.
+    pub fn prove<R: Rng>(sk: &Fr, allowlist: &[G1Affine], context: &[u8], rng: &mut R) -> Result<Self, Error> {
+        let pk = (G1Projective::generator() * sk).into_affine();
+        let k = allowlist.iter().position(|p| *p == pk).ok_or(Error::UnknownSender)?;
+
+        let r = Fr::rand(rng);
+        let mut responses: Vec<(Fr, Fr)> = (0..allowlist.len()).map(|_| (Fr::rand(rng), Fr::rand(rng))).collect();
+        let commitments: Vec<G1Projective> = allowlist
+            .iter()
+            .zip(&responses)
+            .enumerate()
+            .map(|(i, (pk, &(c, z)))| if i == k { G1Projective::generator() * r } else { commitment(pk, c, z) })
+            .collect();
+
+        let e = challenge(allowlist, context, &G1Projective::normalize_batch(&commitments));
+        let others: Fr = responses.iter().enumerate().filter(|&(i, _)| i != k).map(|(_, &(c, _))| c).sum();
+        let c_k = e - others;
+        responses[k] = (c_k, r + c_k * sk);
+        Ok(MembershipProof { responses })
+    }
+
+    pub fn verify(&self, allowlist: &[G1Affine], context: &[u8]) -> bool {
+        if allowlist.is_empty() || self.responses.len() != allowlist.len() {
+            return false;
+        }
+        let commitments: Vec<G1Projective> =
+            allowlist.iter().zip(&self.responses).map(|(pk, &(c, z))| commitment(pk, c, z)).collect();
+        let sum = self.responses.iter().fold(Fr::zero(), |acc, &(c, _)| acc + c);
+        sum == challenge(allowlist, context, &G1Projective::normalize_batch(&commitments))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.responses.serialize_compressed(&mut out).unwrap();
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let responses = Vec::deserialize_compressed(data).map_err(|_| Error::InvalidEncoding)?;
+        Ok(MembershipProof { responses })
+    }
+}
+
+impl Auditor {
+    /// `verify`, additionally requiring the sender to be in `allowlist`.
+    /// Fails with `UnknownSender` or `InvalidSignature`.
+    pub fn check_auth_with_allowlist(
+        &self,
+        allowlist: &[G1Affine],
+        sender_pk: G1Affine,
+        c: &ElGamal,
+        s: G2Affine,
+        aad: &[u8],
+    ) -> Result<(), Error> {
+        if !allowlist.contains(&sender_pk) {
+            return Err(Error::UnknownSender);
+        }
+        if !self.verify(sender_pk, c, s, aad) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Message, Receiver, Sender};
+
+    #[test]
+    fn proof_for_listed_key_verifies() {
+        let rng = &mut ark_std::test_rng();
+        let sks: Vec<Fr> = (0..4).map(|_| Fr::rand(rng)).collect();
+        let allowlist: Vec<G1Affine> = sks.iter().map(|sk| (G1Projective::generator() * sk).into_affine()).collect();
+        let proof = MembershipProof::prove(&sks[2], &allowlist, b"context", rng).unwrap();
+        assert!(proof.verify(&allowlist, b"context"));
+        assert!(!proof.verify(&allowlist, b"other context"));
+        let outsider = MembershipProof::prove(&Fr::rand(rng), &allowlist, b"context", rng);
+        assert!(matches!(outsider, Err(Error::UnknownSender)));
+    }
+
+    #[test]
+    fn allowlist_check_rejects_unlisted_sender() {
+        let rng = &mut ark_std::test_rng();
+        let (listed, unlisted) = (Sender::new(Fr::rand(rng)), Sender::new(Fr::rand(rng)));
+        let receiver = Receiver::new((G1Projective::generator() * Fr::rand(rng)).into_affine());
+        let auditor = Auditor::default();
+        let allowlist = [listed.pk];
+        let check = |sender: &Sender| {
+            let c = sender.send(Message::from_exponent(7), &receiver);
+            let s = sender.authenticate(&c, b"aad");
+            auditor.check_auth_with_allowlist(&allowlist, sender.pk, &c, s, b"aad")
+        };
+        assert!(check(&listed).is_ok());
+        assert!(matches!(check(&unlisted), Err(Error::UnknownSender)));
+    }
+
+    // Four keys, a proof by the third and the allowlist it was made for.
+    fn listed_proof() -> (Vec<G1Affine>, MembershipProof) {
+        let rng = &mut ark_std::test_rng();
+        let sks: Vec<Fr> = (0..4).map(|_| Fr::rand(rng)).collect();
+        let allowlist: Vec<G1Affine> = sks.iter().map(|sk| (G1Projective::generator() * sk).into_affine()).collect();
+        let proof = MembershipProof::prove(&sks[2], &allowlist, b"context", rng).unwrap();
+        (allowlist, proof)
+    }
+
+    #[test]
+    fn tampered_responses_fail() {
+        let (allowlist, proof) = listed_proof();
+        for i in 0..allowlist.len() {
+            let mut flipped = proof.clone();
+            flipped.responses[i].0 += Fr::from(1u64);
+            assert!(!flipped.verify(&allowlist, b"context"), "c_{i}");
+            let mut flipped = proof.clone();
+            flipped.responses[i].1 += Fr::from(1u64);
+            assert!(!flipped.verify(&allowlist, b"context"), "z_{i}");
+        }
+        // moving weight between two challenges keeps their sum, not the commitments
+        let mut shifted = proof.clone();
+        shifted.responses[0].0 += Fr::from(1u64);
+        shifted.responses[1].0 -= Fr::from(1u64);
+        assert!(!shifted.verify(&allowlist, b"context"));
+    }
+
+    #[test]
+    fn proofs_are_bound_to_their_allowlist() {
+        let (allowlist, proof) = listed_proof();
+        let mut reordered = allowlist.clone();
+        reordered.swap(0, 3);
+        assert!(!proof.verify(&reordered, b"context"));
+        let mut reversed = allowlist.clone();
+        reversed.reverse();
+        assert!(!proof.verify(&reversed, b"context"));
+
+        // an extra key, with or without a response for it
+        let mut extended = allowlist.clone();
+        extended.push(G1Affine::generator());
+        assert!(!proof.verify(&extended, b"context"));
+        let mut padded = proof.clone();
+        padded.responses.push((Fr::from(0u64), Fr::from(0u64)));
+        assert!(!padded.verify(&extended, b"context"));
+        assert!(!proof.verify(&allowlist[..3], b"context"));
+        assert!(!proof.verify(&[], b"context"));
+        assert!(!proof.verify(&allowlist, b""));
+    }
+
+    #[test]
+    fn response_count_must_match_the_allowlist() {
+        let (allowlist, proof) = listed_proof();
+        let mut short = proof.clone();
+        short.responses.pop();
+        assert!(!short.verify(&allowlist, b"context"));
+        let mut long = proof.clone();
+        long.responses.push(proof.responses[0]);
+        assert!(!long.verify(&allowlist, b"context"));
+        assert!(!MembershipProof { responses: Vec::new() }.verify(&[], b"context"));
+        assert!(MembershipProof::from_bytes(&proof.to_bytes()).unwrap().verify(&allowlist, b"context"));
+    }
+}
@@ -0,0 +1,139 @@
+//! Message spaces: the exponents a plaintext may take, with their encodings.
+//!
+//! The attack recovers a plaintext by testing candidates, so the size of the
+//! space and the size of its exponents set how hard the recovery step is.
+//! `Difficulty` and `Layout` give named presets for instructors.
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use ark_std::rand::Rng;
+
+use crate::{generate_message_space, Error, Message};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    /// 16-bit exponents, recoverable by brute force in milliseconds.
+    Tiny,
+    /// 32-bit exponents, seconds to minutes of brute force.
+    Medium,
+    /// 64-bit exponents, out of reach without the message space.
+    Large,
+}
+
+impl Difficulty {
+    pub fn bits(self) -> u32 {
+        match self {
+            Difficulty::Tiny => 16,
+            Difficulty::Medium => 32,
+            Difficulty::Large => 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Exponents drawn uniformly at random.
+    Random,
+    /// Evenly spaced exponents, guessable once the spacing is known.
+    Structured,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageSpace {
+    exponents: Vec<u128>,
+    messages: Vec<Message>,
+}
+
+impl MessageSpace {
+    pub fn from_exponents(exponents: Vec<u128>) -> Self {
+        let messages = exponents.iter().map(|&m| Message::from_exponent(m)).collect();
+        MessageSpace { exponents, messages }
+    }
+
+    /// The ten messages of the original puzzle.
+    pub fn puzzle() -> Self {
+        let (msgs, messages) = generate_message_space();
+        MessageSpace { exponents: msgs.iter().map(|&m| m as u128).collect(), messages: messages.to_vec() }
+    }
+
+    /// `n` distinct exponents below `2^bits`, drawn uniformly at random.
+    pub fn random<R: Rng>(n: usize, bits: u32, rng: &mut R) -> Result<Self, Error> {
+        if bits == 0 || bits > 128 || (bits < 128 && n as u128 > 1 << bits) {
+            return Err(Error::InvalidConfig);
+        }
+        let mask = u128::MAX >> (128 - bits);
+        let mut seen = BTreeSet::new();
+        let mut exponents = Vec::with_capacity(n);
+        while exponents.len() < n {
+            let m = rng.gen::<u128>() & mask;
+            if seen.insert(m) {
+                exponents.push(m);
+            }
+        }
+        Ok(Self::from_exponents(exponents))
+    }
+
+    /// `n` exponents evenly spaced over `[1, 2^bits)`, starting at 1.
+    pub fn structured(n: usize, bits: u32) -> Result<Self, Error> {
+        if n == 0 || bits == 0 || bits > 127 || n as u128 > (1 << bits) - 1 {
+            return Err(Error::InvalidConfig);
+        }
+        // the last exponent, 1 + (n - 1) * step, stays below 2^bits
+        let step = ((1u128 << bits) - 1) / n as u128;
+        Ok(Self::from_exponents((0..n as u128).map(|i| 1 + i * step).collect()))
+    }
+
+    pub fn preset<R: Rng>(difficulty: Difficulty, layout: Layout, n: usize, rng: &mut R) -> Result<Self, Error> {
+        match layout {
+            Layout::Random => Self::random(n, difficulty.bits(), rng),
+            Layout::Structured => Self::structured(n, difficulty.bits()),
+        }
+    }
+
+    pub fn exponents(&self) -> &[u128] {
+        &self.exponents
+    }
+
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    pub fn index_of(&self, m: &Message) -> Option<usize> {
+        self.messages.iter().position(|x| x == m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structured_exponents_stay_in_range() {
+        for (n, bits) in [(1, 1), (3, 2), (15, 4), (10, 16), (255, 8)] {
+            let space = MessageSpace::structured(n, bits).unwrap();
+            assert_eq!(space.len(), n);
+            assert_eq!(space.exponents()[0], 1);
+            assert!(space.exponents().iter().all(|&m| m < 1 << bits));
+            assert!(space.exponents().windows(2).all(|w| w[0] < w[1]));
+        }
+        assert!(MessageSpace::structured(16, 4).is_err());
+        assert!(MessageSpace::structured(2, 1).is_err());
+    }
+
+    #[test]
+    fn random_exponents_are_distinct_and_in_range() {
+        let rng = &mut ark_std::test_rng();
+        let space = MessageSpace::random(16, 4, rng).unwrap();
+        let distinct: BTreeSet<u128> = space.exponents().iter().copied().collect();
+        assert_eq!(distinct.len(), 16);
+        assert!(space.exponents().iter().all(|&m| m < 16));
+        assert!(MessageSpace::random(17, 4, rng).is_err());
+    }
+}
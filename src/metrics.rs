@@ -0,0 +1,98 @@
+//! Counters of audit runs.
+//!
+//! `audit` fills an `AuditMetrics` as it goes and prints it as a summary at
+//! the end of a batch; `to_prometheus` renders it in the Prometheus text
+//! exposition format, e.g. for the node exporter's textfile collector.
+//!
+//! There is no solver queue depth: the binary has no server mode to queue
+//! solver jobs, and the parallel discrete-log search (`dlog::brute_force`)
+//! hands its batches to rayon's work-stealing pool, whose internal queues
+//! rayon doesn't expose. A `/metrics` endpoint likewise waits for a server
+//! mode.
+
+use alloc::{format, string::String};
+
+use crate::tamper::Verdict;
+
+/// Pairings computed by one tag verification: `e(g1, s)` and `e(pk, H(c))`.
+pub const PAIRINGS_PER_VERIFICATION: u64 = 2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AuditMetrics {
+    pub valid: u64,
+    pub invalid: u64,
+    pub malformed: u64,
+    /// Verdicts taken from a cache instead of verified.
+    pub cached: u64,
+}
+
+impl AuditMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, verdict: Verdict, cached: bool) {
+        match verdict {
+            Verdict::Valid => self.valid += 1,
+            Verdict::Invalid => self.invalid += 1,
+            Verdict::Malformed => self.malformed += 1,
+        }
+        if cached {
+            self.cached += 1;
+        }
+    }
+
+    pub fn audited(&self) -> u64 {
+        self.valid + self.invalid + self.malformed
+    }
+
+    /// Tags actually verified: parsed blobs whose verdict wasn't cached.
+    pub fn verifications(&self) -> u64 {
+        self.valid + self.invalid - self.cached
+    }
+
+    pub fn pairings(&self) -> u64 {
+        self.verifications() * PAIRINGS_PER_VERIFICATION
+    }
+
+    /// Verifications per second over `elapsed_secs`; zero for no time.
+    pub fn verifications_per_sec(&self, elapsed_secs: f64) -> f64 {
+        if elapsed_secs > 0.0 {
+            self.verifications() as f64 / elapsed_secs
+        } else {
+            0.0
+        }
+    }
+
+    /// The counters as Prometheus metrics, failures labelled by reason.
+    pub fn to_prometheus(&self, elapsed_secs: f64) -> String {
+        format!(
+            "# HELP chaos_audit_blobs_total Blobs audited.\n\
+             # TYPE chaos_audit_blobs_total counter\n\
+             chaos_audit_blobs_total {}\n\
+             # HELP chaos_audit_failures_total Blobs that failed the audit, by reason.\n\
+             # TYPE chaos_audit_failures_total counter\n\
+             chaos_audit_failures_total{{reason=\"invalid\"}} {}\n\
+             chaos_audit_failures_total{{reason=\"malformed\"}} {}\n\
+             # HELP chaos_audit_cached_total Verdicts taken from the cache.\n\
+             # TYPE chaos_audit_cached_total counter\n\
+             chaos_audit_cached_total {}\n\
+             # HELP chaos_audit_verifications_total Tags verified.\n\
+             # TYPE chaos_audit_verifications_total counter\n\
+             chaos_audit_verifications_total {}\n\
+             # HELP chaos_audit_pairings_total Pairings computed.\n\
+             # TYPE chaos_audit_pairings_total counter\n\
+             chaos_audit_pairings_total {}\n\
+             # HELP chaos_audit_verifications_per_second Tags verified per second over the run.\n\
+             # TYPE chaos_audit_verifications_per_second gauge\n\
+             chaos_audit_verifications_per_second {}\n",
+            self.audited(),
+            self.invalid,
+            self.malformed,
+            self.cached,
+            self.verifications(),
+            self.pairings(),
+            self.verifications_per_sec(elapsed_secs),
+        )
+    }
+}
@@ -0,0 +1,100 @@
+//! Pairing helpers shared by the auditor and the attack, for downstream
+//! experiments that need the same building blocks.
+//!
+//! GT is written additively, as in arkworks: the identity of GT is
+//! `PairingOutput::zero()` and `base^e` is `base * e`.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Bls12_381, G1Affine, G2Affine};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    Group,
+};
+use ark_ff::Zero;
+
+use crate::{point_audit::PointAudit, Error};
+
+pub type Gt = PairingOutput<Bls12_381>;
+pub type G1Prepared = <Bls12_381 as Pairing>::G1Prepared;
+pub type G2Prepared = <Bls12_381 as Pairing>::G2Prepared;
+
+/// `e(a_0, b_0) * e(a_1, b_1) * ...` for points that may come from unchecked
+/// deserialization. Fails with `InvalidEncoding` if the slices differ in
+/// length or a point is off the curve or outside the prime-order subgroup.
+pub fn multi_pairing_checked(a: &[G1Affine], b: &[G2Affine]) -> Result<Gt, Error> {
+    let valid_a = a.iter().all(|p| PointAudit::analyze(p).is_valid());
+    let valid_b = b.iter().all(|p| PointAudit::analyze(p).is_valid());
+    if a.len() != b.len() || !valid_a || !valid_b {
+        return Err(Error::InvalidEncoding);
+    }
+    Ok(Bls12_381::multi_pairing(a, b))
+}
+
+/// Whether `e(a_0, b_0) * e(a_1, b_1) * ...` is one, with a single final
+/// exponentiation. Equations `e(x, y) = e(z, w)` are checked as
+/// `pairing_product_is_one([x, -z], [y, w])`.
+pub fn pairing_product_is_one(
+    a: impl IntoIterator<Item = impl Into<G1Prepared>>,
+    b: impl IntoIterator<Item = impl Into<G2Prepared>>,
+) -> bool {
+    Bls12_381::multi_pairing(a, b).is_zero()
+}
+
+/// G2 points prepared for the Miller loop, for pairing many G1 points against
+/// the same few G2 points (e.g. candidate messages against `H(c)`). Lookups
+/// are linear, so it is meant for a handful of points.
+#[derive(Debug, Clone, Default)]
+pub struct PreparedCache {
+    entries: Vec<(G2Affine, G2Prepared)>,
+}
+
+impl PreparedCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `p` prepared, preparing it on the first call.
+    pub fn prepared(&mut self, p: &G2Affine) -> &G2Prepared {
+        let i = match self.entries.iter().position(|(q, _)| q == p) {
+            Some(i) => i,
+            None => {
+                self.entries.push((*p, G2Prepared::from(*p)));
+                self.entries.len() - 1
+            }
+        };
+        &self.entries[i].1
+    }
+
+    pub fn pairing(&mut self, a: G1Affine, b: &G2Affine) -> Gt {
+        let prepared = self.prepared(b).clone();
+        Bls12_381::pairing(a, prepared)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// `base^e`.
+pub fn gt_pow(base: &Gt, e: u128) -> Gt {
+    base.mul_bigint([e as u64, (e >> 64) as u64])
+}
+
+/// `base^0, base^1, ..., base^(n - 1)`, one multiplication each.
+pub fn gt_powers(base: &Gt, n: usize) -> Vec<Gt> {
+    let mut powers = Vec::with_capacity(n);
+    let mut current = Gt::zero();
+    for _ in 0..n {
+        powers.push(current);
+        current += base;
+    }
+    powers
+}
@@ -0,0 +1,133 @@
+//! Pedersen commitments linked to blobs.
+//!
+//! Systems that track values as Pedersen commitments `P = v * g1 + rho * h`
+//! can check that a blob encrypts the same value: the sender proves knowledge
+//! of `v`, `rho` and its key `sk` with
+//!
+//! ```text
+//! P   = v * g1 + rho * h
+//! c.0 = sk * g1
+//! c.1 = v * g1 + sk * rec_pk
+//! ```
+//!
+//! in one Schnorr proof, bound to the ciphertext, the receiver and `aad`.
+//! `h` is hashed to G1 under `PEDERSEN_DST`, so nobody knows its discrete log
+//! and commitments are binding.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine, G1Projective};
+use ark_ec::{hashing::HashToCurve, AffineRepr, CurveGroup};
+use ark_ff::UniformRand;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::Rng;
+
+use crate::{config::G1Hasher, fr_utils, ElGamal, Message, Receiver, Sender};
+
+pub const PEDERSEN_DST: &[u8] = b"chaos-theory-pedersen-v1";
+
+/// The blinding generator `h`.
+pub fn generator() -> G1Affine {
+    G1Hasher::new(PEDERSEN_DST).unwrap().hash(b"h").unwrap()
+}
+
+/// `v * g1 + blinding * h`.
+pub fn commit(v: Fr, blinding: Fr) -> G1Affine {
+    (G1Affine::generator() * v + generator() * blinding).into_affine()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkProof {
+    pub challenge: Fr,
+    pub z_v: Fr,
+    pub z_blinding: Fr,
+    pub z_sk: Fr,
+}
+
+fn challenge(commitment: &G1Affine, c: &ElGamal, rec_pk: &G1Affine, aad: &[u8], t: &[G1Projective; 3]) -> Fr {
+    let mut transcript = Vec::new();
+    commitment.serialize_compressed(&mut transcript).unwrap();
+    c.serialize_compressed(&mut transcript).unwrap();
+    rec_pk.serialize_compressed(&mut transcript).unwrap();
+    for p in G1Projective::normalize_batch(t) {
+        p.serialize_compressed(&mut transcript).unwrap();
+    }
+    transcript.extend(aad);
+    fr_utils::hash_to_scalar(&transcript, PEDERSEN_DST).unwrap()
+}
+
+// the three left-hand sides for exponents (v, blinding, sk)
+fn images(v: Fr, blinding: Fr, sk: Fr, h: &G1Affine, rec_pk: &G1Affine) -> [G1Projective; 3] {
+    let g = G1Affine::generator();
+    [g * v + *h * blinding, g * sk, g * v + *rec_pk * sk]
+}
+
+impl Sender {
+    /// Proves that `commit(Fr::from(exponent), blinding)` and
+    /// `self.send(Message::from_exponent(exponent), receiver)` hide the same
+    /// value.
+    pub fn prove_link<R: Rng>(
+        &self,
+        exponent: u128,
+        blinding: Fr,
+        receiver: &Receiver,
+        aad: &[u8],
+        rng: &mut R,
+    ) -> LinkProof {
+        let (v, h, rec_pk) = (Fr::from(exponent), generator(), receiver.pk());
+        let commitment = commit(v, blinding);
+        let c = self.send(Message::from_exponent(exponent), receiver);
+
+        let (k_v, k_blinding, k_sk) = (Fr::rand(rng), Fr::rand(rng), Fr::rand(rng));
+        let t = images(k_v, k_blinding, k_sk, &h, &rec_pk);
+        let e = challenge(&commitment, &c, &rec_pk, aad, &t);
+        LinkProof { challenge: e, z_v: k_v + e * v, z_blinding: k_blinding + e * blinding, z_sk: k_sk + e * self.sk }
+    }
+}
+
+impl LinkProof {
+    /// Whether `commitment` and `c`, sent to `rec_pk` under `aad`, hide the
+    /// same value.
+    pub fn verify(&self, commitment: &G1Affine, c: &ElGamal, rec_pk: &G1Affine, aad: &[u8]) -> bool {
+        let [p, q, r] = images(self.z_v, self.z_blinding, self.z_sk, &generator(), rec_pk);
+        let e = self.challenge;
+        let t = [p - *commitment * e, q - c.0 * e, r - c.1 * e];
+        e == challenge(commitment, c, rec_pk, aad, &t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls;
+
+    #[test]
+    fn links_verify_only_for_the_same_value() {
+        let rng = &mut ark_std::test_rng();
+        let sender = Sender::new(Fr::rand(rng));
+        let receiver = Receiver::new(bls::public_key(&Fr::rand(rng)));
+        let blinding = Fr::rand(rng);
+        let (commitment, rec_pk) = (commit(Fr::from(42u64), blinding), receiver.pk());
+        let c = sender.send(Message::from_exponent(42), &receiver);
+        let proof = sender.prove_link(42, blinding, &receiver, b"aad", rng);
+        assert!(proof.verify(&commitment, &c, &rec_pk, b"aad"));
+
+        // another value on either side, another receiver or another aad
+        assert!(!proof.verify(&commit(Fr::from(43u64), blinding), &c, &rec_pk, b"aad"));
+        assert!(!proof.verify(&commit(Fr::from(42u64), Fr::rand(rng)), &c, &rec_pk, b"aad"));
+        assert!(!proof.verify(&commitment, &sender.send(Message::from_exponent(43), &receiver), &rec_pk, b"aad"));
+        assert!(!proof.verify(&commitment, &c, &bls::public_key(&Fr::rand(rng)), b"aad"));
+        assert!(!proof.verify(&commitment, &c, &rec_pk, b"other aad"));
+        for tampered in [
+            LinkProof { z_v: proof.z_v + Fr::from(1u64), ..proof },
+            LinkProof { z_blinding: proof.z_blinding + Fr::from(1u64), ..proof },
+            LinkProof { z_sk: proof.z_sk + Fr::from(1u64), ..proof },
+            LinkProof { challenge: proof.challenge + Fr::from(1u64), ..proof },
+        ] {
+            assert!(!tampered.verify(&commitment, &c, &rec_pk, b"aad"));
+        }
+
+        // a proof for a different value doesn't transfer
+        let other = sender.prove_link(43, blinding, &receiver, b"aad", rng);
+        assert!(!other.verify(&commitment, &c, &rec_pk, b"aad"));
+    }
+}
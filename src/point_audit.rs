@@ -0,0 +1,33 @@
+//! Curve and subgroup checks of a point, reported rather than enforced.
+//!
+//! A point produced by hash-to-curve is on the curve and, once the cofactor
+//! is cleared, in the prime-order subgroup. Interop mismatches usually come
+//! from one side skipping a step; `PointAudit::analyze` says which property a
+//! point lacks.
+
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointAudit {
+    pub is_identity: bool,
+    pub on_curve: bool,
+    /// In the prime-order subgroup, i.e. cofactor-cleared. Only meaningful
+    /// for points on the curve.
+    pub in_subgroup: bool,
+}
+
+impl PointAudit {
+    pub fn analyze<P: SWCurveConfig>(p: &Affine<P>) -> Self {
+        let on_curve = p.is_on_curve();
+        PointAudit {
+            is_identity: p.infinity,
+            on_curve,
+            in_subgroup: on_curve && p.is_in_correct_subgroup_assuming_on_curve(),
+        }
+    }
+
+    /// Accepted by arkworks' checked deserialization.
+    pub fn is_valid(&self) -> bool {
+        self.on_curve && self.in_subgroup
+    }
+}
@@ -0,0 +1,117 @@
+//! Protobuf types for `proto/chaos_theory.proto`, in the shape `prost-build`
+//! generates them (kept by hand so building doesn't need `protoc`), with
+//! conversions from and to the crate's types.
+
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{ElGamal, Error};
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PublicKey {
+    #[prost(bytes = "vec", tag = "1")]
+    pub point: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Signature {
+    #[prost(bytes = "vec", tag = "1")]
+    pub point: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Ciphertext {
+    #[prost(bytes = "vec", tag = "1")]
+    pub c1: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub c2: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Blob {
+    #[prost(message, optional, tag = "1")]
+    pub sender_pk: Option<PublicKey>,
+    #[prost(message, optional, tag = "2")]
+    pub c: Option<Ciphertext>,
+    #[prost(message, optional, tag = "3")]
+    pub s: Option<Signature>,
+    #[prost(message, optional, tag = "4")]
+    pub rec_pk: Option<PublicKey>,
+}
+
+fn to_bytes<T: CanonicalSerialize>(p: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    p.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+fn from_bytes<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, Error> {
+    T::deserialize_compressed(bytes).map_err(|_| Error::InvalidEncoding)
+}
+
+impl From<&G1Affine> for PublicKey {
+    fn from(pk: &G1Affine) -> Self {
+        PublicKey { point: to_bytes(pk) }
+    }
+}
+
+impl TryFrom<&PublicKey> for G1Affine {
+    type Error = Error;
+
+    fn try_from(pk: &PublicKey) -> Result<Self, Error> {
+        from_bytes(&pk.point)
+    }
+}
+
+impl From<&G2Affine> for Signature {
+    fn from(s: &G2Affine) -> Self {
+        Signature { point: to_bytes(s) }
+    }
+}
+
+impl TryFrom<&Signature> for G2Affine {
+    type Error = Error;
+
+    fn try_from(s: &Signature) -> Result<Self, Error> {
+        from_bytes(&s.point)
+    }
+}
+
+impl From<&ElGamal> for Ciphertext {
+    fn from(c: &ElGamal) -> Self {
+        Ciphertext { c1: to_bytes(&c.0), c2: to_bytes(&c.1) }
+    }
+}
+
+impl TryFrom<&Ciphertext> for ElGamal {
+    type Error = Error;
+
+    fn try_from(c: &Ciphertext) -> Result<Self, Error> {
+        Ok(ElGamal(from_bytes(&c.c1)?, from_bytes(&c.c2)?))
+    }
+}
+
+impl From<&crate::Blob> for Blob {
+    fn from(blob: &crate::Blob) -> Self {
+        Blob {
+            sender_pk: Some((&blob.sender_pk).into()),
+            c: Some((&blob.c).into()),
+            s: Some((&blob.s).into()),
+            rec_pk: Some((&blob.rec_pk).into()),
+        }
+    }
+}
+
+impl TryFrom<&Blob> for crate::Blob {
+    type Error = Error;
+
+    fn try_from(blob: &Blob) -> Result<Self, Error> {
+        let missing = || Error::InvalidEncoding;
+        Ok(crate::Blob {
+            sender_pk: blob.sender_pk.as_ref().ok_or_else(missing)?.try_into()?,
+            c: blob.c.as_ref().ok_or_else(missing)?.try_into()?,
+            s: blob.s.as_ref().ok_or_else(missing)?.try_into()?,
+            rec_pk: blob.rec_pk.as_ref().ok_or_else(missing)?.try_into()?,
+        })
+    }
+}
@@ -0,0 +1,175 @@
+//! k-of-n auditor quorums.
+//!
+//! A `QuorumPolicy` fixes a set of auditor keys and how many of them must
+//! attest to a blob. Attestations to the same report are aggregated into one
+//! BLS signature, checked against the sum of the signers' keys in a single
+//! multi-pairing. Since all signers sign the same message, the policy's keys
+//! must be known to be honestly generated (e.g. registered with a proof of
+//! possession) to rule out rogue-key attacks.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::Zero;
+
+use crate::{
+    attest::{AuditReport, SignedAuditReport, ATTESTATION_DST},
+    bls,
+    pairing_utils::pairing_product_is_one,
+    Blob, Error,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumPolicy {
+    auditors: Vec<G1Affine>,
+    threshold: usize,
+}
+
+/// Aggregated attestations of at least `threshold` auditors of a policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumCertificate {
+    pub report: AuditReport,
+    /// Indices of the signers in the policy, strictly increasing.
+    pub signers: Vec<usize>,
+    pub signature: G2Affine,
+}
+
+impl QuorumPolicy {
+    /// Fails with `InvalidConfig` unless `1 <= threshold <= auditors.len()`
+    /// and the keys are distinct.
+    pub fn new(auditors: Vec<G1Affine>, threshold: usize) -> Result<Self, Error> {
+        let distinct = auditors.iter().enumerate().all(|(i, pk)| !auditors[..i].contains(pk));
+        if threshold == 0 || threshold > auditors.len() || !distinct {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(QuorumPolicy { auditors, threshold })
+    }
+
+    pub fn auditors(&self) -> &[G1Affine] {
+        &self.auditors
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Aggregates attestations to one report. Attestations from keys outside
+    /// the policy and duplicates are dropped; fails with `InvalidProof` if the
+    /// reports differ or fewer than `threshold` auditors remain. Signatures
+    /// are not checked here, only by `verify`.
+    pub fn aggregate(&self, attestations: &[SignedAuditReport]) -> Result<QuorumCertificate, Error> {
+        let report = attestations.first().ok_or(Error::InvalidProof)?.report;
+        if attestations.iter().any(|a| a.report != report) {
+            return Err(Error::InvalidProof);
+        }
+
+        let mut signers = Vec::new();
+        let mut signature = G2Projective::zero();
+        for (i, pk) in self.auditors.iter().enumerate() {
+            if let Some(a) = attestations.iter().find(|a| a.auditor_pk == *pk) {
+                signers.push(i);
+                signature += a.signature;
+            }
+        }
+        if signers.len() < self.threshold {
+            return Err(Error::InvalidProof);
+        }
+        Ok(QuorumCertificate { report, signers, signature: signature.into_affine() })
+    }
+
+    /// Checks that `qc` covers `blob` checked under `aad` and carries valid
+    /// signatures of at least `threshold` distinct auditors of the policy.
+    pub fn verify(&self, qc: &QuorumCertificate, blob: &Blob, aad: &[u8]) -> bool {
+        let increasing = qc.signers.windows(2).all(|w| w[0] < w[1]);
+        let in_policy = qc.signers.iter().all(|&i| i < self.auditors.len());
+        if !increasing || !in_policy || qc.signers.len() < self.threshold {
+            return false;
+        }
+        if qc.report != AuditReport::new(blob, aad) {
+            return false;
+        }
+
+        let agg_pk: G1Projective = qc.signers.iter().map(|&i| self.auditors[i]).sum();
+        let hash = bls::hash_to_g2(ATTESTATION_DST, &qc.report.to_bytes());
+        let g1s = G1Projective::normalize_batch(&[-G1Projective::generator(), agg_pk]);
+        pairing_product_is_one(g1s, [qc.signature, hash])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Auditor, Message, Receiver, SchemeConfig, Sender};
+    use ark_bls12_381::Fr;
+    use ark_ff::UniformRand;
+    use ark_std::rand::Rng;
+
+    fn blob<R: Rng>(exponent: u128, aad: &[u8], rng: &mut R) -> Blob {
+        let sender = Sender::new(Fr::rand(rng));
+        let rec_pk = bls::public_key(&Fr::rand(rng));
+        let c = sender.send(Message::from_exponent(exponent), &Receiver::new(rec_pk));
+        let s = sender.authenticate(&c, aad);
+        Blob { sender_pk: sender.pk, c, s, rec_pk }
+    }
+
+    fn auditors<R: Rng>(n: usize, rng: &mut R) -> Vec<Auditor> {
+        (0..n).map(|_| Auditor::with_key(SchemeConfig::default(), Fr::rand(rng))).collect()
+    }
+
+    fn policy(auditors: &[Auditor], threshold: usize) -> QuorumPolicy {
+        QuorumPolicy::new(auditors.iter().map(|a| a.public_key().unwrap()).collect(), threshold).unwrap()
+    }
+
+    #[test]
+    fn quorums_verify() {
+        let rng = &mut ark_std::test_rng();
+        let auditors = auditors(4, rng);
+        let policy = policy(&auditors, 3);
+        let blob = blob(5, b"aad", rng);
+        let attestations: Vec<SignedAuditReport> =
+            [3, 0, 2, 0].iter().map(|&i| auditors[i].attest(&blob, b"aad").unwrap()).collect();
+
+        let qc = policy.aggregate(&attestations).unwrap();
+        assert_eq!(qc.signers, vec![0, 2, 3]);
+        assert!(policy.verify(&qc, &blob, b"aad"));
+        assert!(!policy.verify(&qc, &blob, b"other aad"));
+        assert!(!policy.verify(&qc, &self::blob(5, b"aad", rng), b"aad"));
+    }
+
+    #[test]
+    fn short_or_tampered_quorums_fail() {
+        let rng = &mut ark_std::test_rng();
+        let auditors = auditors(4, rng);
+        let policy = policy(&auditors, 3);
+        let blob = blob(5, b"aad", rng);
+        let attestations: Vec<SignedAuditReport> =
+            auditors[..3].iter().map(|a| a.attest(&blob, b"aad").unwrap()).collect();
+        let qc = policy.aggregate(&attestations).unwrap();
+
+        // too few signers, counting duplicates and outsiders only once
+        let outsider = self::auditors(1, rng).remove(0).attest(&blob, b"aad").unwrap();
+        let short = [attestations[0], attestations[0], attestations[1], outsider];
+        assert!(matches!(policy.aggregate(&short), Err(Error::InvalidProof)));
+        let other = self::blob(6, b"aad", rng);
+        let mixed = [attestations[0], attestations[1], auditors[3].attest(&other, b"aad").unwrap()];
+        assert!(matches!(policy.aggregate(&mixed), Err(Error::InvalidProof)));
+        assert!(matches!(policy.aggregate(&[]), Err(Error::InvalidProof)));
+
+        // a signer claimed but not signing, a signer dropped, a forged signature
+        assert!(!policy.verify(&QuorumCertificate { signers: vec![0, 1, 3], ..qc.clone() }, &blob, b"aad"));
+        assert!(!policy.verify(&QuorumCertificate { signers: vec![0, 1], ..qc.clone() }, &blob, b"aad"));
+        assert!(!policy.verify(&QuorumCertificate { signers: vec![0, 0, 1, 2], ..qc.clone() }, &blob, b"aad"));
+        assert!(!policy.verify(&QuorumCertificate { signers: vec![0, 1, 4], ..qc.clone() }, &blob, b"aad"));
+        let forged = (qc.signature + G2Affine::generator()).into_affine();
+        assert!(!policy.verify(&QuorumCertificate { signature: forged, ..qc.clone() }, &blob, b"aad"));
+    }
+
+    #[test]
+    fn policies_reject_bad_parameters() {
+        let rng = &mut ark_std::test_rng();
+        let keys: Vec<G1Affine> = auditors(2, rng).iter().map(|a| a.public_key().unwrap()).collect();
+        assert!(matches!(QuorumPolicy::new(keys.clone(), 0), Err(Error::InvalidConfig)));
+        assert!(matches!(QuorumPolicy::new(keys.clone(), 3), Err(Error::InvalidConfig)));
+        assert!(matches!(QuorumPolicy::new(vec![keys[0], keys[0]], 1), Err(Error::InvalidConfig)));
+    }
+}
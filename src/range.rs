@@ -0,0 +1,250 @@
+//! Proofs that the encrypted exponent is small.
+//!
+//! A blob's ciphertext is `(sk * g1, m * g1 + sk * rec_pk)`, and the receiver
+//! recovers the exponent of `m` by a discrete-log search that only ends if it
+//! is small. The sender splits the ciphertext into bit ciphertexts
+//! `(r_i * g1, b_i * g1 + r_i * rec_pk)` whose weighted sums
+//! `sum(2^i * ...)` are the two halves of the ciphertext, and proves for each
+//! that it encrypts 0 or 1: an OR of two Chaum-Pedersen proofs that
+//! `log_g1(A_i) = log_rec_pk(C_i - j * g1)` for `j = 0` or `j = 1`. The
+//! exponent is then below `2^bits`.
+//!
+//! Proofs take `bits` pairs of points and four scalars per bit.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine, G1Projective};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, UniformRand};
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::Rng;
+use core::ops::Mul;
+
+use crate::{fr_utils, registry::KeyRegistry, Auditor, Blob, ElGamal, Error, Message, Receiver, Sender};
+
+pub const RANGE_DST: &[u8] = b"chaos-theory-range-v1";
+
+/// Largest supported bound, `2^128`.
+pub const MAX_BITS: u32 = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitProof {
+    /// `r_i * g1`.
+    pub a: G1Affine,
+    /// `b_i * g1 + r_i * rec_pk`.
+    pub c: G1Affine,
+    /// `(c_j, z_j)` for `j = 0, 1`.
+    pub responses: [(Fr, Fr); 2],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeProof {
+    pub bits: Vec<BitProof>,
+}
+
+fn pow2(i: usize) -> Fr {
+    Fr::from(2u64).pow([i as u64])
+}
+
+// Chaum-Pedersen commitments recomputed from a response: z * g1 - c * a and
+// z * rec_pk - c * (ct - j * g1)
+fn commitments(a: &G1Affine, ct: &G1Affine, j: u64, rec_pk: &G1Affine, (c, z): (Fr, Fr)) -> [G1Projective; 2] {
+    let shifted = G1Projective::from(*ct) - G1Affine::generator() * Fr::from(j);
+    [G1Affine::generator() * z - a.mul(c), rec_pk.mul(z) - shifted * c]
+}
+
+fn challenge(context: &[u8], i: usize, a: &G1Affine, ct: &G1Affine, t: &[G1Projective; 4]) -> Fr {
+    let mut transcript = context.to_vec();
+    transcript.extend((i as u64).to_le_bytes());
+    a.serialize_compressed(&mut transcript).unwrap();
+    ct.serialize_compressed(&mut transcript).unwrap();
+    for p in G1Projective::normalize_batch(t) {
+        p.serialize_compressed(&mut transcript).unwrap();
+    }
+    fr_utils::hash_to_scalar(&transcript, RANGE_DST).unwrap()
+}
+
+// What every bit proof is bound to: the ciphertext, the receiver and `aad`.
+fn context(c: &ElGamal, rec_pk: &G1Affine, aad: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    c.serialize_compressed(&mut out).unwrap();
+    rec_pk.serialize_compressed(&mut out).unwrap();
+    out.extend(aad);
+    out
+}
+
+impl Sender {
+    /// Proves that `self.send(Message::from_exponent(exponent), receiver)`
+    /// encrypts an exponent below `2^bits`. Fails with `InvalidMsg` if it
+    /// doesn't, or `InvalidConfig` unless `1 <= bits <= MAX_BITS`.
+    pub fn prove_range<R: Rng>(
+        &self,
+        exponent: u128,
+        bits: u32,
+        receiver: &Receiver,
+        aad: &[u8],
+        rng: &mut R,
+    ) -> Result<RangeProof, Error> {
+        if bits == 0 || bits > MAX_BITS {
+            return Err(Error::InvalidConfig);
+        }
+        if bits < MAX_BITS && exponent >> bits != 0 {
+            return Err(Error::InvalidMsg);
+        }
+        let n = bits as usize;
+        let rec_pk = receiver.pk();
+        let c = self.send(Message::from_exponent(exponent), receiver);
+        let context = context(&c, &rec_pk, aad);
+
+        // r_i at random except the last, which makes sum(2^i * r_i) = sk
+        let mut r: Vec<Fr> = (0..n - 1).map(|_| Fr::rand(rng)).collect();
+        let partial: Fr = r.iter().enumerate().map(|(i, r)| pow2(i) * r).sum();
+        r.push((self.sk - partial) * pow2(n - 1).inverse().unwrap());
+
+        let proofs = (0..n)
+            .map(|i| {
+                let b = ((exponent >> i) & 1) as usize;
+                let a = (G1Affine::generator() * r[i]).into_affine();
+                let ct = (rec_pk * r[i] + G1Affine::generator() * Fr::from(b as u64)).into_affine();
+
+                let mut responses = [(Fr::rand(rng), Fr::rand(rng)); 2];
+                let fake = commitments(&a, &ct, 1 - b as u64, &rec_pk, responses[1 - b]);
+                let w = Fr::rand(rng);
+                let real = [G1Affine::generator() * w, rec_pk * w];
+                let t = if b == 0 {
+                    [real[0], real[1], fake[0], fake[1]]
+                } else {
+                    [fake[0], fake[1], real[0], real[1]]
+                };
+
+                let e = challenge(&context, i, &a, &ct, &t);
+                let c_b = e - responses[1 - b].0;
+                responses[b] = (c_b, w + c_b * r[i]);
+                BitProof { a, c: ct, responses }
+            })
+            .collect();
+        Ok(RangeProof { bits: proofs })
+    }
+}
+
+impl RangeProof {
+    /// Whether `c`, sent to `rec_pk` under `aad`, encrypts an exponent below
+    /// `2^bits`.
+    pub fn verify(&self, c: &ElGamal, rec_pk: &G1Affine, bits: u32, aad: &[u8]) -> bool {
+        if bits == 0 || bits > MAX_BITS || self.bits.len() != bits as usize {
+            return false;
+        }
+        let context = context(c, rec_pk, aad);
+        let bits_ok = self.bits.iter().enumerate().all(|(i, p)| {
+            let [t0, t1] = commitments(&p.a, &p.c, 0, rec_pk, p.responses[0]);
+            let [t2, t3] = commitments(&p.a, &p.c, 1, rec_pk, p.responses[1]);
+            p.responses[0].0 + p.responses[1].0 == challenge(&context, i, &p.a, &p.c, &[t0, t1, t2, t3])
+        });
+
+        let weights: Vec<Fr> = (0..self.bits.len()).map(pow2).collect();
+        let a: Vec<G1Affine> = self.bits.iter().map(|p| p.a).collect();
+        let ct: Vec<G1Affine> = self.bits.iter().map(|p| p.c).collect();
+        bits_ok
+            && G1Projective::msm(&a, &weights).unwrap().into_affine() == c.0
+            && G1Projective::msm(&ct, &weights).unwrap().into_affine() == c.1
+    }
+}
+
+impl Auditor {
+    /// `check_auth_with_policy` for `blob`, additionally requiring a proof
+    /// that its exponent is below `2^bits`, so that decryption terminates.
+    /// Fails with `InvalidProof` if the proof doesn't verify.
+    #[allow(clippy::too_many_arguments)]
+    pub fn check_auth_with_range(
+        &self,
+        registry: &KeyRegistry,
+        identity: &str,
+        now: u64,
+        blob: &Blob,
+        aad: &[u8],
+        proof: &RangeProof,
+        bits: u32,
+    ) -> Result<(), Error> {
+        self.check_auth_with_policy(registry, identity, now, blob.sender_pk, &blob.c, blob.s, aad)?;
+        if !proof.verify(&blob.c, &blob.rec_pk, bits, aad) {
+            return Err(Error::InvalidProof);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bls, registry::RegistryUpdate};
+
+    fn parties<R: Rng>(rng: &mut R) -> (Sender, Receiver) {
+        (Sender::new(Fr::rand(rng)), Receiver::new(bls::public_key(&Fr::rand(rng))))
+    }
+
+    #[test]
+    fn honest_proofs_verify() {
+        let rng = &mut ark_std::test_rng();
+        let (sender, receiver) = parties(rng);
+        for (exponent, bits) in [(0, 1), (1, 1), (200, 8), (255, 8), (u128::MAX, MAX_BITS)] {
+            let proof = sender.prove_range(exponent, bits, &receiver, b"aad", rng).unwrap();
+            let c = sender.send(Message::from_exponent(exponent), &receiver);
+            assert!(proof.verify(&c, &receiver.pk(), bits, b"aad"));
+        }
+    }
+
+    #[test]
+    fn tampered_proofs_and_statements_fail() {
+        let rng = &mut ark_std::test_rng();
+        let (sender, receiver) = parties(rng);
+        let (c, rec_pk) = (sender.send(Message::from_exponent(200), &receiver), receiver.pk());
+        let proof = sender.prove_range(200, 8, &receiver, b"aad", rng).unwrap();
+
+        assert!(!proof.verify(&c, &rec_pk, 9, b"aad"));
+        assert!(!proof.verify(&c, &rec_pk, 8, b"other aad"));
+        assert!(!proof.verify(&sender.send(Message::from_exponent(201), &receiver), &rec_pk, 8, b"aad"));
+        assert!(!proof.verify(&c, &bls::public_key(&Fr::rand(rng)), 8, b"aad"));
+
+        let mut tampered = proof.clone();
+        tampered.bits[3].responses.swap(0, 1);
+        assert!(!tampered.verify(&c, &rec_pk, 8, b"aad"));
+        // bit 0 encrypting b_0 + 2 and bit 1 encrypting b_1 - 1 still sum to
+        // the ciphertext: only the OR proofs catch it
+        let mut tampered = proof.clone();
+        let g1 = G1Projective::from(G1Affine::generator());
+        tampered.bits[0].c = (g1 + g1 + tampered.bits[0].c).into_affine();
+        tampered.bits[1].c = (G1Projective::from(tampered.bits[1].c) - g1).into_affine();
+        assert!(!tampered.verify(&c, &rec_pk, 8, b"aad"));
+    }
+
+    #[test]
+    fn out_of_range_exponents_cannot_be_proven() {
+        let rng = &mut ark_std::test_rng();
+        let (sender, receiver) = parties(rng);
+        assert!(matches!(sender.prove_range(256, 8, &receiver, &[], rng), Err(Error::InvalidMsg)));
+        assert!(matches!(sender.prove_range(1, 0, &receiver, &[], rng), Err(Error::InvalidConfig)));
+        assert!(matches!(sender.prove_range(1, MAX_BITS + 1, &receiver, &[], rng), Err(Error::InvalidConfig)));
+    }
+
+    #[test]
+    fn range_gated_audit() {
+        let rng = &mut ark_std::test_rng();
+        let (sender, receiver) = parties(rng);
+        let authority_sk = Fr::rand(rng);
+        let mut registry = KeyRegistry::new(bls::public_key(&authority_sk));
+        let register =
+            RegistryUpdate::Register { identity: "alice".into(), pk: sender.pk, valid_from: 0, valid_until: None };
+        registry.apply(&register.sign(1, &authority_sk)).unwrap();
+
+        let c = sender.send(Message::from_exponent(42), &receiver);
+        let s = sender.authenticate(&c, b"aad");
+        let blob = Blob { sender_pk: sender.pk, c, s, rec_pk: receiver.pk() };
+        let proof = sender.prove_range(42, 8, &receiver, b"aad", rng).unwrap();
+        let auditor = Auditor::default();
+        assert!(auditor.check_auth_with_range(&registry, "alice", 10, &blob, b"aad", &proof, 8).is_ok());
+        let other = sender.prove_range(43, 8, &receiver, b"aad", rng).unwrap();
+        let result = auditor.check_auth_with_range(&registry, "alice", 10, &blob, b"aad", &other, 8);
+        assert!(matches!(result, Err(Error::InvalidProof)));
+        let result = auditor.check_auth_with_range(&registry, "bob", 10, &blob, b"aad", &proof, 8);
+        assert!(matches!(result, Err(Error::UnknownSender)));
+    }
+}
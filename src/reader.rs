@@ -0,0 +1,104 @@
+//! Field-level access to serialized blobs.
+//!
+//! `BlobReader` deserializes only the fields asked for, so indexing a large
+//! archive by sender or signature doesn't pay for validating every point of
+//! every blob.
+
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
+use core::ops::Range;
+
+use crate::{ElGamal, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    SenderPk,
+    C0,
+    C1,
+    S,
+    RecPk,
+}
+
+impl Field {
+    /// In serialization order.
+    pub const ALL: [Field; 5] = [Field::SenderPk, Field::C0, Field::C1, Field::S, Field::RecPk];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::SenderPk => "sender_pk",
+            Field::C0 => "c.0",
+            Field::C1 => "c.1",
+            Field::S => "s",
+            Field::RecPk => "rec_pk",
+        }
+    }
+
+    // byte range in an uncompressed blob; compressed points take half the space
+    fn range(self, compress: Compress) -> Range<usize> {
+        let (start, len) = match self {
+            Field::SenderPk => (0, 96),
+            Field::C0 => (96, 96),
+            Field::C1 => (192, 96),
+            Field::S => (288, 192),
+            Field::RecPk => (480, 96),
+        };
+        match compress {
+            Compress::Yes => start / 2..(start + len) / 2,
+            Compress::No => start..start + len,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BlobReader<'a> {
+    data: &'a [u8],
+    compress: Compress,
+    validate: Validate,
+}
+
+impl<'a> BlobReader<'a> {
+    /// Reader over an uncompressed blob, as in `blob.bin`.
+    pub fn new(data: &'a [u8]) -> Result<Self, Error> {
+        Self::with_mode(data, Compress::No)
+    }
+
+    /// Fails with `InvalidEncoding` if `data` is too short to hold a blob.
+    pub fn with_mode(data: &'a [u8], compress: Compress) -> Result<Self, Error> {
+        if data.len() < Field::RecPk.range(compress).end {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(BlobReader { data, compress, validate: Validate::Yes })
+    }
+
+    /// Skips the curve and subgroup checks on the fields read, e.g. to show
+    /// malformed points in an inspector.
+    pub fn unchecked(mut self) -> Self {
+        self.validate = Validate::No;
+        self
+    }
+
+    /// The raw encoding of `field`.
+    pub fn raw(&self, field: Field) -> &'a [u8] {
+        &self.data[field.range(self.compress)]
+    }
+
+    fn read<T: CanonicalDeserialize>(&self, field: Field) -> Result<T, Error> {
+        T::deserialize_with_mode(self.raw(field), self.compress, self.validate).map_err(|_| Error::InvalidEncoding)
+    }
+
+    pub fn sender_pk(&self) -> Result<G1Affine, Error> {
+        self.read(Field::SenderPk)
+    }
+
+    pub fn c(&self) -> Result<ElGamal, Error> {
+        Ok(ElGamal(self.read(Field::C0)?, self.read(Field::C1)?))
+    }
+
+    pub fn s(&self) -> Result<G2Affine, Error> {
+        self.read(Field::S)
+    }
+
+    pub fn rec_pk(&self) -> Result<G1Affine, Error> {
+        self.read(Field::RecPk)
+    }
+}
@@ -0,0 +1,138 @@
+//! Shamir secret sharing of secret keys, for recovering a lost key from `t`
+//! of `n` shares held by different parties.
+
+use alloc::vec::Vec;
+use ark_bls12_381::Fr;
+use ark_ff::{Field, One, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+
+use crate::Error;
+
+/// One share, `f(index)` for the secret polynomial `f` with `f(0) = sk`.
+/// Serialized as-is into share files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Share {
+    pub threshold: u64,
+    pub index: u64,
+    pub value: Fr,
+}
+
+impl Share {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Share::deserialize_compressed(bytes).map_err(|_| Error::InvalidEncoding)
+    }
+}
+
+/// Splits `secret` into `n` shares, any `threshold` of which recover it.
+pub fn split<R: Rng>(secret: &Fr, threshold: usize, n: usize, rng: &mut R) -> Result<Vec<Share>, Error> {
+    if threshold == 0 || threshold > n {
+        return Err(Error::InvalidShares);
+    }
+    let mut coeffs = Vec::with_capacity(threshold);
+    coeffs.push(*secret);
+    coeffs.extend((1..threshold).map(|_| Fr::rand(rng)));
+
+    let shares = (1..=n as u64)
+        .map(|index| {
+            let x = Fr::from(index);
+            // Horner evaluation of f(x)
+            let value = coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c);
+            Share { threshold: threshold as u64, index, value }
+        })
+        .collect();
+    Ok(shares)
+}
+
+/// Recovers the secret by Lagrange interpolation at 0.
+///
+/// Needs at least `threshold` shares with distinct indices; extra shares are
+/// ignored.
+pub fn recover(shares: &[Share]) -> Result<Fr, Error> {
+    let threshold = shares.first().ok_or(Error::InvalidShares)?.threshold as usize;
+    if shares.iter().any(|s| s.threshold as usize != threshold || s.index == 0) {
+        return Err(Error::InvalidShares);
+    }
+    let mut used: Vec<&Share> = Vec::with_capacity(threshold);
+    for share in shares {
+        if !used.iter().any(|s| s.index == share.index) {
+            used.push(share);
+        }
+    }
+    if used.len() < threshold {
+        return Err(Error::InvalidShares);
+    }
+    used.truncate(threshold);
+
+    let indices: Vec<u64> = used.iter().map(|s| s.index).collect();
+    Ok(lagrange_at_zero(&indices).into_iter().zip(&used).map(|(lambda, share)| lambda * share.value).sum())
+}
+
+// Lagrange coefficients at 0 for the distinct non-zero `indices`.
+pub(crate) fn lagrange_at_zero(indices: &[u64]) -> Vec<Fr> {
+    indices
+        .iter()
+        .map(|&i| {
+            let x_i = Fr::from(i);
+            let mut lambda = Fr::one();
+            for &j in indices.iter().filter(|&&j| j != i) {
+                let x_j = Fr::from(j);
+                lambda *= x_j * (x_j - x_i).inverse().unwrap();
+            }
+            lambda
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_threshold_of_shares_recovers() {
+        let rng = &mut ark_std::test_rng();
+        let sk = Fr::rand(rng);
+        let shares = split(&sk, 3, 5, rng).unwrap();
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let chosen: Vec<Share> = subset.iter().map(|&i| shares[i]).collect();
+            assert_eq!(recover(&chosen).unwrap(), sk);
+        }
+        assert_eq!(recover(&shares).unwrap(), sk);
+        assert_eq!(recover(&[shares[0], shares[0], shares[3], shares[2]]).unwrap(), sk);
+        assert_eq!(recover(&split(&sk, 1, 1, rng).unwrap()).unwrap(), sk);
+    }
+
+    #[test]
+    fn too_few_or_inconsistent_shares_fail() {
+        let rng = &mut ark_std::test_rng();
+        let sk = Fr::rand(rng);
+        let shares = split(&sk, 3, 5, rng).unwrap();
+        assert!(matches!(recover(&shares[..2]), Err(Error::InvalidShares)));
+        assert!(matches!(recover(&[shares[0], shares[0], shares[1]]), Err(Error::InvalidShares)));
+        assert!(matches!(recover(&[]), Err(Error::InvalidShares)));
+        let other = split(&sk, 2, 5, rng).unwrap();
+        assert!(matches!(recover(&[shares[0], shares[1], other[2]]), Err(Error::InvalidShares)));
+        assert!(matches!(recover(&[Share { index: 0, ..shares[0] }, shares[1], shares[2]]), Err(Error::InvalidShares)));
+
+        // a tampered share recovers some other key
+        let tampered = Share { value: shares[1].value + Fr::one(), ..shares[1] };
+        assert_ne!(recover(&[shares[0], tampered, shares[2]]).unwrap(), sk);
+
+        assert!(matches!(split(&sk, 0, 5, rng), Err(Error::InvalidShares)));
+        assert!(matches!(split(&sk, 6, 5, rng), Err(Error::InvalidShares)));
+    }
+
+    #[test]
+    fn shares_round_trip() {
+        let rng = &mut ark_std::test_rng();
+        let share = split(&Fr::rand(rng), 2, 3, rng).unwrap()[1];
+        assert_eq!(Share::from_bytes(&share.to_bytes()).unwrap(), share);
+        assert!(matches!(Share::from_bytes(&share.to_bytes()[1..]), Err(Error::InvalidEncoding)));
+    }
+}
@@ -0,0 +1,293 @@
+//! Registry of sender identities and their keys over time.
+//!
+//! Every change to the registry is a `RegistryUpdate` signed by the registry
+//! authority and carrying a sequence number, so a registry can be rebuilt and
+//! checked from the list of updates alone. The auditor consults it through
+//! `Auditor::check_auth_with_policy`.
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_serialize::CanonicalSerialize;
+
+use crate::{bls, Auditor, ElGamal, Error};
+
+pub const REGISTRY_DST: &[u8] = b"chaos-theory-registry-v1";
+
+/// Times are opaque `u64`s (e.g. unix seconds); the registry only compares them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEntry {
+    pub pk: G1Affine,
+    pub valid_from: u64,
+    pub valid_until: Option<u64>,
+    pub revoked_at: Option<u64>,
+}
+
+impl KeyEntry {
+    fn check(&self, now: u64) -> Result<(), Error> {
+        if self.revoked_at.is_some_and(|at| at <= now) {
+            return Err(Error::RevokedKey);
+        }
+        if now < self.valid_from || self.valid_until.is_some_and(|until| now >= until) {
+            return Err(Error::ExpiredKey);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryUpdate {
+    Register { identity: String, pk: G1Affine, valid_from: u64, valid_until: Option<u64> },
+    Revoke { identity: String, pk: G1Affine, at: u64 },
+}
+
+pub(crate) fn push_str(out: &mut Vec<u8>, s: &str) {
+    out.extend((s.len() as u64).to_le_bytes());
+    out.extend(s.as_bytes());
+}
+
+pub(crate) fn push_point(out: &mut Vec<u8>, p: &G1Affine) {
+    p.serialize_compressed(&mut *out).unwrap();
+}
+
+impl RegistryUpdate {
+    /// The signed message: sequence number followed by the update fields.
+    pub fn to_bytes(&self, seq: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend(seq.to_le_bytes());
+        match self {
+            RegistryUpdate::Register { identity, pk, valid_from, valid_until } => {
+                out.push(0);
+                push_str(&mut out, identity);
+                push_point(&mut out, pk);
+                out.extend(valid_from.to_le_bytes());
+                out.extend(valid_until.unwrap_or(u64::MAX).to_le_bytes());
+            }
+            RegistryUpdate::Revoke { identity, pk, at } => {
+                out.push(1);
+                push_str(&mut out, identity);
+                push_point(&mut out, pk);
+                out.extend(at.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    pub fn sign(self, seq: u64, authority_sk: &Fr) -> SignedUpdate {
+        let signature = bls::sign(authority_sk, REGISTRY_DST, &self.to_bytes(seq));
+        SignedUpdate { update: self, seq, signature }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedUpdate {
+    pub update: RegistryUpdate,
+    pub seq: u64,
+    pub signature: G2Affine,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyRegistry {
+    authority_pk: G1Affine,
+    entries: BTreeMap<String, Vec<KeyEntry>>,
+    seq: u64,
+}
+
+impl KeyRegistry {
+    pub fn new(authority_pk: G1Affine) -> Self {
+        KeyRegistry { authority_pk, entries: BTreeMap::new(), seq: 0 }
+    }
+
+    /// Sequence number of the last applied update; the next must be `seq() + 1`.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    pub fn apply(&mut self, signed: &SignedUpdate) -> Result<(), Error> {
+        if signed.seq != self.seq + 1 {
+            return Err(Error::InvalidUpdate);
+        }
+        let msg = signed.update.to_bytes(signed.seq);
+        if !bls::verify(&self.authority_pk, REGISTRY_DST, &msg, &signed.signature) {
+            return Err(Error::InvalidSignature);
+        }
+
+        match &signed.update {
+            RegistryUpdate::Register { identity, pk, valid_from, valid_until } => {
+                self.entries.entry(identity.clone()).or_default().push(KeyEntry {
+                    pk: *pk,
+                    valid_from: *valid_from,
+                    valid_until: *valid_until,
+                    revoked_at: None,
+                });
+            }
+            RegistryUpdate::Revoke { identity, pk, at } => {
+                let entry = self
+                    .entries
+                    .get_mut(identity)
+                    .and_then(|keys| keys.iter_mut().find(|e| e.pk == *pk))
+                    .ok_or(Error::UnknownSender)?;
+                entry.revoked_at = Some(entry.revoked_at.map_or(*at, |prev| prev.min(*at)));
+            }
+        }
+        self.seq = signed.seq;
+        Ok(())
+    }
+
+    pub fn keys(&self, identity: &str) -> &[KeyEntry] {
+        self.entries.get(identity).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// The key of `identity` valid at `now`, the most recently registered one
+    /// if several are.
+    pub fn current_key(&self, identity: &str, now: u64) -> Option<&KeyEntry> {
+        self.keys(identity).iter().rev().find(|e| e.check(now).is_ok())
+    }
+
+    /// Checks that `pk` is a key of `identity` that is valid at `now`.
+    pub fn check_key(&self, identity: &str, pk: &G1Affine, now: u64) -> Result<(), Error> {
+        self.keys(identity)
+            .iter()
+            .rev()
+            .find(|e| e.pk == *pk)
+            .ok_or(Error::UnknownSender)?
+            .check(now)
+    }
+}
+
+impl Auditor {
+    /// `verify`, additionally requiring that `sender_pk` is registered to
+    /// `identity` and neither expired nor revoked at `now`.
+    pub fn check_auth_with_policy(
+        &self,
+        registry: &KeyRegistry,
+        identity: &str,
+        now: u64,
+        sender_pk: G1Affine,
+        c: &ElGamal,
+        s: G2Affine,
+        aad: &[u8],
+    ) -> Result<(), Error> {
+        registry.check_key(identity, &sender_pk, now)?;
+        if !self.verify(sender_pk, c, s, aad) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+
+    use crate::{Message, Receiver, Sender};
+
+    fn register(identity: &str, pk: G1Affine, valid_from: u64, valid_until: Option<u64>) -> RegistryUpdate {
+        RegistryUpdate::Register { identity: identity.into(), pk, valid_from, valid_until }
+    }
+
+    fn revoke(identity: &str, pk: G1Affine, at: u64) -> RegistryUpdate {
+        RegistryUpdate::Revoke { identity: identity.into(), pk, at }
+    }
+
+    // A registry and its authority's secret key.
+    fn registry() -> (KeyRegistry, Fr) {
+        let authority_sk = Fr::rand(&mut ark_std::test_rng());
+        (KeyRegistry::new(bls::public_key(&authority_sk)), authority_sk)
+    }
+
+    fn key(seed: u64) -> G1Affine {
+        bls::public_key(&Fr::from(seed))
+    }
+
+    #[test]
+    fn updates_apply_in_sequence() {
+        let (mut registry, authority_sk) = registry();
+        let update = register("alice", key(1), 0, None);
+        assert!(matches!(registry.apply(&update.clone().sign(2, &authority_sk)), Err(Error::InvalidUpdate)));
+        assert!(matches!(registry.apply(&update.clone().sign(0, &authority_sk)), Err(Error::InvalidUpdate)));
+        let signed = update.sign(1, &authority_sk);
+        registry.apply(&signed).unwrap();
+        assert_eq!(registry.seq(), 1);
+        // replaying an applied update is out of sequence
+        assert!(matches!(registry.apply(&signed), Err(Error::InvalidUpdate)));
+        assert_eq!(registry.keys("alice").len(), 1);
+    }
+
+    #[test]
+    fn updates_need_the_authority_signature() {
+        let (mut registry, authority_sk) = registry();
+        let forged = register("alice", key(1), 0, None).sign(1, &Fr::from(7u64));
+        assert!(matches!(registry.apply(&forged), Err(Error::InvalidSignature)));
+        // the signature covers the sequence number and the fields
+        let mut moved = register("alice", key(1), 0, None).sign(1, &authority_sk);
+        moved.update = register("mallory", key(1), 0, None);
+        assert!(matches!(registry.apply(&moved), Err(Error::InvalidSignature)));
+        assert_eq!(registry.seq(), 0);
+        assert!(registry.keys("alice").is_empty() && registry.keys("mallory").is_empty());
+    }
+
+    #[test]
+    fn revoked_keys_fail_from_the_revocation_on() {
+        let (mut registry, authority_sk) = registry();
+        registry.apply(&register("alice", key(1), 0, None).sign(1, &authority_sk)).unwrap();
+        registry.apply(&revoke("alice", key(1), 100).sign(2, &authority_sk)).unwrap();
+        registry.check_key("alice", &key(1), 99).unwrap();
+        assert!(matches!(registry.check_key("alice", &key(1), 100), Err(Error::RevokedKey)));
+        assert!(matches!(registry.check_key("alice", &key(1), 1000), Err(Error::RevokedKey)));
+
+        // an earlier revocation wins, a later one doesn't undo it
+        registry.apply(&revoke("alice", key(1), 50).sign(3, &authority_sk)).unwrap();
+        registry.apply(&revoke("alice", key(1), 200).sign(4, &authority_sk)).unwrap();
+        assert!(matches!(registry.check_key("alice", &key(1), 50), Err(Error::RevokedKey)));
+        let unknown = revoke("alice", key(2), 0).sign(5, &authority_sk);
+        assert!(matches!(registry.apply(&unknown), Err(Error::UnknownSender)));
+    }
+
+    #[test]
+    fn keys_expire_outside_their_validity() {
+        let (mut registry, authority_sk) = registry();
+        registry.apply(&register("alice", key(1), 10, Some(20)).sign(1, &authority_sk)).unwrap();
+        assert!(matches!(registry.check_key("alice", &key(1), 9), Err(Error::ExpiredKey)));
+        registry.check_key("alice", &key(1), 10).unwrap();
+        registry.check_key("alice", &key(1), 19).unwrap();
+        assert!(matches!(registry.check_key("alice", &key(1), 20), Err(Error::ExpiredKey)));
+        assert!(matches!(registry.check_key("alice", &key(2), 15), Err(Error::UnknownSender)));
+        assert!(matches!(registry.check_key("bob", &key(1), 15), Err(Error::UnknownSender)));
+    }
+
+    #[test]
+    fn rotation_picks_the_newest_valid_key() {
+        let (mut registry, authority_sk) = registry();
+        registry.apply(&register("alice", key(1), 0, None).sign(1, &authority_sk)).unwrap();
+        registry.apply(&register("alice", key(2), 100, None).sign(2, &authority_sk)).unwrap();
+        assert_eq!(registry.current_key("alice", 50).unwrap().pk, key(1));
+        assert_eq!(registry.current_key("alice", 100).unwrap().pk, key(2));
+
+        registry.apply(&revoke("alice", key(2), 150).sign(3, &authority_sk)).unwrap();
+        assert_eq!(registry.current_key("alice", 150).unwrap().pk, key(1));
+        registry.apply(&revoke("alice", key(1), 150).sign(4, &authority_sk)).unwrap();
+        assert_eq!(registry.current_key("alice", 150), None);
+        assert_eq!(registry.current_key("bob", 0), None);
+    }
+
+    #[test]
+    fn policy_check_rejects_revoked_keys() {
+        let rng = &mut ark_std::test_rng();
+        let (mut registry, authority_sk) = registry();
+        let sender = Sender::new(Fr::rand(rng));
+        let c = sender.send(Message(key(3)), &Receiver::new(key(4)));
+        let s = sender.authenticate(&c, b"aad");
+        registry.apply(&register("alice", sender.pk, 0, None).sign(1, &authority_sk)).unwrap();
+        registry.apply(&revoke("alice", sender.pk, 100).sign(2, &authority_sk)).unwrap();
+
+        let auditor = Auditor::default();
+        auditor.check_auth_with_policy(&registry, "alice", 99, sender.pk, &c, s, b"aad").unwrap();
+        let revoked = auditor.check_auth_with_policy(&registry, "alice", 100, sender.pk, &c, s, b"aad");
+        assert!(matches!(revoked, Err(Error::RevokedKey)));
+        let forged = auditor.check_auth_with_policy(&registry, "alice", 99, sender.pk, &c, s, b"other");
+        assert!(matches!(forged, Err(Error::InvalidSignature)));
+        let unknown = auditor.check_auth_with_policy(&registry, "bob", 99, sender.pk, &c, s, b"aad");
+        assert!(matches!(unknown, Err(Error::UnknownSender)));
+    }
+}
@@ -0,0 +1,131 @@
+//! Signed reports of attack runs, for graded submissions.
+//!
+//! A report says which blob was attacked, what was recovered, by which solver,
+//! how long it took and on what machine, signed under the solver's BLS key
+//! like a `submission::Submission`. It is written as a flat JSON object of
+//! strings, in the style of the `keys` JSON format:
+//!
+//! ```json
+//! {"blob_hash":"<hex>","index":"3","exponent":"8492374892742","solver":"message-space",
+//!  "elapsed_ms":"12","machine":"linux/x86_64/8 cpus","solver_pk":"<hex>","signature":"<hex>"}
+//! ```
+//!
+//! `index` is empty when the exponent was found outside the message space.
+//! The leaderboard server checks a report with `verify_report`.
+
+use alloc::{format, string::String, vec::Vec};
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{bls, hex, keys, Blob, Error};
+
+pub const REPORT_DST: &[u8] = b"chaos-theory-report-v1";
+
+/// What an attack run found, before it is signed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttackResult {
+    /// Index in the message space, if the plaintext was in it.
+    pub index: Option<usize>,
+    pub exponent: u128,
+    /// E.g. `message-space` or `brute-force`.
+    pub solver: String,
+    pub elapsed_ms: u64,
+    /// Free-form description, e.g. `linux/x86_64/8 cpus`.
+    pub machine: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttackReport {
+    pub blob_hash: [u8; 32],
+    pub result: AttackResult,
+    pub solver_pk: G1Affine,
+    pub signature: G2Affine,
+}
+
+// The signed bytes: blob hash, exponent (16 bytes, big-endian), index plus
+// one (8 bytes, 0 for none), elapsed time (8 bytes), then the solver and
+// machine strings, each prefixed by its length (8 bytes).
+fn statement(blob_hash: &[u8; 32], result: &AttackResult) -> Vec<u8> {
+    let mut out = blob_hash.to_vec();
+    out.extend_from_slice(&result.exponent.to_be_bytes());
+    out.extend_from_slice(&result.index.map_or(0, |i| i as u64 + 1).to_be_bytes());
+    out.extend_from_slice(&result.elapsed_ms.to_be_bytes());
+    for s in [&result.solver, &result.machine] {
+        out.extend_from_slice(&(s.len() as u64).to_be_bytes());
+        out.extend_from_slice(s.as_bytes());
+    }
+    out
+}
+
+fn to_bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+impl AttackReport {
+    /// Fails with `InvalidConfig` if `solver` or `machine` contains `"`, `\`
+    /// or `,`, which the JSON format can't carry.
+    pub fn new(solver_sk: &Fr, blob: &Blob, result: AttackResult) -> Result<Self, Error> {
+        if [&result.solver, &result.machine].iter().any(|s| s.contains(['"', '\\', ','])) {
+            return Err(Error::InvalidConfig);
+        }
+        let blob_hash = blob.content_hash();
+        let signature = bls::sign(solver_sk, REPORT_DST, &statement(&blob_hash, &result));
+        Ok(AttackReport { blob_hash, result, solver_pk: bls::public_key(solver_sk), signature })
+    }
+
+    pub fn to_json(&self) -> String {
+        let r = &self.result;
+        format!(
+            "{{\"blob_hash\":\"{}\",\"index\":\"{}\",\"exponent\":\"{}\",\"solver\":\"{}\",\"elapsed_ms\":\"{}\",\
+             \"machine\":\"{}\",\"solver_pk\":\"{}\",\"signature\":\"{}\"}}",
+            hex::encode(&self.blob_hash),
+            r.index.map_or(String::new(), |i| format!("{i}")),
+            r.exponent,
+            r.solver,
+            r.elapsed_ms,
+            r.machine,
+            hex::encode(&to_bytes(&self.solver_pk)),
+            hex::encode(&to_bytes(&self.signature)),
+        )
+    }
+
+    /// Fails with `InvalidEncoding` on a missing or malformed member. The
+    /// signature is not checked; see `verify_report`.
+    pub fn from_json(s: &str) -> Result<Self, Error> {
+        let members = keys::parse_json(s)?;
+        let get = |name: &str| {
+            let value = members.iter().find(|(k, _)| *k == name).map(|&(_, v)| v);
+            value.ok_or(Error::InvalidEncoding)
+        };
+        let invalid = |_| Error::InvalidEncoding;
+        let point = |name: &str| hex::decode(get(name)?);
+
+        let signature = point("signature")?;
+        let index = match get("index")? {
+            "" => None,
+            i => Some(i.parse().map_err(invalid)?),
+        };
+        Ok(AttackReport {
+            blob_hash: hex::decode(get("blob_hash")?)?.try_into().map_err(|_| Error::InvalidEncoding)?,
+            result: AttackResult {
+                index,
+                exponent: get("exponent")?.parse().map_err(invalid)?,
+                solver: get("solver")?.into(),
+                elapsed_ms: get("elapsed_ms")?.parse().map_err(invalid)?,
+                machine: get("machine")?.into(),
+            },
+            solver_pk: keys::pk_from_bytes(&point("solver_pk")?)?,
+            signature: G2Affine::deserialize_compressed(signature.as_slice()).map_err(|_| Error::InvalidEncoding)?,
+        })
+    }
+}
+
+/// Server-side check: the signature is valid for the report's solver key and
+/// the report is about `blob`. Whether the exponent is correct is up to the
+/// caller.
+pub fn verify_report(report: &AttackReport, blob: &Blob) -> bool {
+    report.blob_hash == blob.content_hash()
+        && bls::verify(&report.solver_pk, REPORT_DST, &statement(&report.blob_hash, &report.result), &report.signature)
+}
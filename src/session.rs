@@ -0,0 +1,300 @@
+//! Sessions: many messages under keys agreed once.
+//!
+//! The sender opens a session with a `Hello` carrying a fresh ephemeral key
+//! `x * g1`, signed under its long-term key together with the receiver's key
+//! it is meant for. The receiver answers with its own ephemeral `y * g1`,
+//! signed together with the sender's hello, so each party's signature covers
+//! the handshake so far (SIGMA-style). Both sides then derive two keys, one
+//! per direction, from the shared `x * y * g1` and the hash of the
+//! handshake, which every record is bound to.
+//!
+//! A record carries a message as `m + k * g1`, with `k` derived from the
+//! direction's key and the record's sequence number, and a tag over the
+//! handshake hash, the sequence number, the masked point and `aad`. Records
+//! must be opened in order: a replayed, dropped or reordered record fails.
+//! Long-term keys only sign, so a later compromise of them doesn't reveal
+//! past sessions' keys.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::UniformRand;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::Rng;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use crate::{bls, fr_utils, h2c, Error, Message, Receiver, Sender};
+
+pub const SESSION_DST: &[u8] = b"chaos-theory-session-v1";
+pub const SESSION_MASK_DST: &[u8] = b"chaos-theory-session-mask-v1";
+pub const SESSION_TAG_DST: &[u8] = b"chaos-theory-session-tag-v1";
+
+/// One side's handshake message: its long-term key, its ephemeral key and a
+/// signature over the handshake so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hello {
+    pub pk: G1Affine,
+    pub ephemeral: G1Affine,
+    pub signature: G2Affine,
+}
+
+/// A message sent within a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Record {
+    pub seq: u64,
+    /// `m + k * g1`.
+    pub c: G1Affine,
+    pub tag: [u8; 32],
+}
+
+/// The sender's side of a handshake waiting for the receiver's answer.
+#[derive(Clone)]
+pub struct PendingSession {
+    ephemeral_sk: Fr,
+    rec_pk: G1Affine,
+    hello: Hello,
+}
+
+#[derive(Clone)]
+pub struct Session {
+    peer_pk: G1Affine,
+    transcript: [u8; 32],
+    send_key: [u8; 32],
+    recv_key: [u8; 32],
+    sent: u64,
+    received: u64,
+}
+
+fn push_point<T: CanonicalSerialize>(out: &mut Vec<u8>, p: &T) {
+    p.serialize_compressed(&mut *out).unwrap();
+}
+
+// What the sender signs: its key, the receiver's and its ephemeral.
+fn init_statement(pk: &G1Affine, rec_pk: &G1Affine, ephemeral: &G1Affine) -> Vec<u8> {
+    let mut out = b"init".to_vec();
+    for p in [pk, rec_pk, ephemeral] {
+        push_point(&mut out, p);
+    }
+    out
+}
+
+// What the receiver signs: the sender's whole hello, then its key and ephemeral.
+fn reply_statement(hello: &Hello, pk: &G1Affine, ephemeral: &G1Affine) -> Vec<u8> {
+    let mut out = b"reply".to_vec();
+    push_point(&mut out, &hello.pk);
+    push_point(&mut out, &hello.ephemeral);
+    push_point(&mut out, &hello.signature);
+    push_point(&mut out, pk);
+    push_point(&mut out, ephemeral);
+    out
+}
+
+fn transcript_hash(hello: &Hello, reply: &Hello) -> [u8; 32] {
+    let mut data = reply_statement(hello, &reply.pk, &reply.ephemeral);
+    push_point(&mut data, &reply.signature);
+    Sha256::digest(&data).into()
+}
+
+// The sender's and the receiver's sending keys.
+fn derive_keys(shared: &G1Projective, transcript: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
+    let mut data = Vec::new();
+    push_point(&mut data, &shared.into_affine());
+    data.extend(transcript);
+    let okm = h2c::expand_message_xmd(&data, SESSION_DST, 64).unwrap();
+    (okm[..32].try_into().unwrap(), okm[32..].try_into().unwrap())
+}
+
+fn mask(key: &[u8; 32], seq: u64) -> Fr {
+    let mut data = key.to_vec();
+    data.extend(seq.to_le_bytes());
+    fr_utils::hash_to_scalar(&data, SESSION_MASK_DST).unwrap()
+}
+
+fn tag(key: &[u8; 32], transcript: &[u8; 32], seq: u64, c: &G1Affine, aad: &[u8]) -> [u8; 32] {
+    let mut data = key.to_vec();
+    data.extend(transcript);
+    data.extend(seq.to_le_bytes());
+    push_point(&mut data, c);
+    data.extend((aad.len() as u64).to_le_bytes());
+    data.extend(aad);
+    h2c::expand_message_xmd(&data, SESSION_TAG_DST, 32).unwrap().try_into().unwrap()
+}
+
+impl Sender {
+    /// Starts a session with `receiver`: send the `Hello` and finish with the
+    /// receiver's answer in `PendingSession::complete`.
+    pub fn open_session<R: Rng>(&self, receiver: &Receiver, rng: &mut R) -> (PendingSession, Hello) {
+        let ephemeral_sk = Fr::rand(rng);
+        let ephemeral = bls::public_key(&ephemeral_sk);
+        let signature = bls::sign(&self.sk, SESSION_DST, &init_statement(&self.pk, &receiver.pk(), &ephemeral));
+        let hello = Hello { pk: self.pk, ephemeral, signature };
+        (PendingSession { ephemeral_sk, rec_pk: receiver.pk(), hello }, hello)
+    }
+}
+
+impl PendingSession {
+    /// Checks the receiver's answer. Fails with `IdentityMismatch` if it comes
+    /// from another key than the receiver the session was opened with,
+    /// `InvalidSignature` if its signature doesn't cover this handshake and
+    /// `InvalidMsg` on an identity ephemeral key.
+    pub fn complete(self, reply: &Hello) -> Result<Session, Error> {
+        if reply.pk != self.rec_pk {
+            return Err(Error::IdentityMismatch);
+        }
+        if reply.ephemeral.is_zero() {
+            return Err(Error::InvalidMsg);
+        }
+        let statement = reply_statement(&self.hello, &reply.pk, &reply.ephemeral);
+        if !bls::verify(&reply.pk, SESSION_DST, &statement, &reply.signature) {
+            return Err(Error::InvalidSignature);
+        }
+        let transcript = transcript_hash(&self.hello, reply);
+        let (send_key, recv_key) = derive_keys(&(reply.ephemeral * self.ephemeral_sk), &transcript);
+        Ok(Session { peer_pk: reply.pk, transcript, send_key, recv_key, sent: 0, received: 0 })
+    }
+}
+
+impl Session {
+    /// The receiver's side of the handshake: checks the sender's `hello` and
+    /// answers it under the receiver's secret key `rec_sk`. Fails with
+    /// `InvalidSignature` if the hello isn't signed by its key for this
+    /// receiver and `InvalidMsg` on an identity ephemeral key. Whether to
+    /// talk to `peer()` is up to the caller.
+    pub fn accept<R: Rng>(rec_sk: &Fr, hello: &Hello, rng: &mut R) -> Result<(Session, Hello), Error> {
+        let rec_pk = bls::public_key(rec_sk);
+        if hello.ephemeral.is_zero() {
+            return Err(Error::InvalidMsg);
+        }
+        let statement = init_statement(&hello.pk, &rec_pk, &hello.ephemeral);
+        if !bls::verify(&hello.pk, SESSION_DST, &statement, &hello.signature) {
+            return Err(Error::InvalidSignature);
+        }
+
+        let ephemeral_sk = Fr::rand(rng);
+        let ephemeral = bls::public_key(&ephemeral_sk);
+        let signature = bls::sign(rec_sk, SESSION_DST, &reply_statement(hello, &rec_pk, &ephemeral));
+        let reply = Hello { pk: rec_pk, ephemeral, signature };
+
+        let transcript = transcript_hash(hello, &reply);
+        let (recv_key, send_key) = derive_keys(&(hello.ephemeral * ephemeral_sk), &transcript);
+        Ok((Session { peer_pk: hello.pk, transcript, send_key, recv_key, sent: 0, received: 0 }, reply))
+    }
+
+    /// The other party's long-term key.
+    pub fn peer(&self) -> G1Affine {
+        self.peer_pk
+    }
+
+    /// Hash of the handshake, the same on both sides.
+    pub fn transcript_hash(&self) -> [u8; 32] {
+        self.transcript
+    }
+
+    pub fn seal(&mut self, m: Message, aad: &[u8]) -> Record {
+        let seq = self.sent;
+        let c = (G1Affine::generator() * mask(&self.send_key, seq) + m.0).into_affine();
+        self.sent += 1;
+        Record { seq, c, tag: tag(&self.send_key, &self.transcript, seq, &c, aad) }
+    }
+
+    /// Opens the next record from the peer. Fails with `InvalidMsg` if it is
+    /// out of order and `InvalidSignature` if its tag doesn't match; a
+    /// failed record is not counted.
+    pub fn open(&mut self, record: &Record, aad: &[u8]) -> Result<Message, Error> {
+        if record.seq != self.received {
+            return Err(Error::InvalidMsg);
+        }
+        let expected = tag(&self.recv_key, &self.transcript, record.seq, &record.c, aad);
+        if !bool::from(expected[..].ct_eq(&record.tag[..])) {
+            return Err(Error::InvalidSignature);
+        }
+        self.received += 1;
+        let m = G1Projective::from(record.c) - G1Affine::generator() * mask(&self.recv_key, record.seq);
+        Ok(Message(m.into_affine()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handshake<R: Rng>(rng: &mut R) -> (Sender, Fr, Session, Session) {
+        let sender = Sender::new(Fr::rand(rng));
+        let rec_sk = Fr::rand(rng);
+        let (pending, hello) = sender.open_session(&Receiver::new(bls::public_key(&rec_sk)), rng);
+        let (accepted, reply) = Session::accept(&rec_sk, &hello, rng).unwrap();
+        (sender, rec_sk, pending.complete(&reply).unwrap(), accepted)
+    }
+
+    #[test]
+    fn messages_flow_both_ways() {
+        let rng = &mut ark_std::test_rng();
+        let (sender, rec_sk, mut ours, mut theirs) = handshake(rng);
+        assert_eq!(ours.transcript_hash(), theirs.transcript_hash());
+        assert_eq!((ours.peer(), theirs.peer()), (bls::public_key(&rec_sk), sender.pk));
+
+        for e in 1..=3 {
+            let record = ours.seal(Message::from_exponent(e), b"aad");
+            assert!(theirs.open(&record, b"aad").unwrap() == Message::from_exponent(e));
+        }
+        let record = theirs.seal(Message::from_exponent(7), &[]);
+        assert!(ours.open(&record, &[]).unwrap() == Message::from_exponent(7));
+        // the same message twice doesn't repeat on the wire
+        let (a, b) = (ours.seal(Message::from_exponent(1), &[]), ours.seal(Message::from_exponent(1), &[]));
+        assert_ne!(a.c, b.c);
+    }
+
+    #[test]
+    fn tampered_or_misordered_records_fail() {
+        let rng = &mut ark_std::test_rng();
+        let (_, _, mut ours, mut theirs) = handshake(rng);
+        let first = ours.seal(Message::from_exponent(1), b"aad");
+        let second = ours.seal(Message::from_exponent(2), b"aad");
+
+        assert!(matches!(theirs.open(&second, b"aad"), Err(Error::InvalidMsg)));
+        assert!(matches!(theirs.open(&first, b"other aad"), Err(Error::InvalidSignature)));
+        let moved = Record { c: (first.c + G1Affine::generator()).into_affine(), ..first };
+        assert!(matches!(theirs.open(&moved, b"aad"), Err(Error::InvalidSignature)));
+        let mut flipped = first;
+        flipped.tag[0] ^= 1;
+        assert!(matches!(theirs.open(&flipped, b"aad"), Err(Error::InvalidSignature)));
+        // a record of one direction can't be reflected back
+        assert!(matches!(ours.open(&first, b"aad"), Err(Error::InvalidSignature)));
+
+        assert!(theirs.open(&first, b"aad").is_ok());
+        assert!(matches!(theirs.open(&first, b"aad"), Err(Error::InvalidMsg)));
+        assert!(theirs.open(&second, b"aad").is_ok());
+
+        // records of another session between the same parties don't open
+        let mut other = ours.clone();
+        other.transcript[0] ^= 1;
+        let record = other.seal(Message::from_exponent(3), b"aad");
+        assert!(matches!(theirs.open(&record, b"aad"), Err(Error::InvalidSignature)));
+    }
+
+    #[test]
+    fn handshakes_are_authenticated() {
+        let rng = &mut ark_std::test_rng();
+        let sender = Sender::new(Fr::rand(rng));
+        let (rec_sk, eve_sk) = (Fr::rand(rng), Fr::rand(rng));
+        let receiver = Receiver::new(bls::public_key(&rec_sk));
+        let (pending, hello) = sender.open_session(&receiver, rng);
+
+        // a hello for someone else, or with a swapped ephemeral
+        assert!(matches!(Session::accept(&eve_sk, &hello, rng), Err(Error::InvalidSignature)));
+        let swapped = Hello { ephemeral: bls::public_key(&Fr::rand(rng)), ..hello };
+        assert!(matches!(Session::accept(&rec_sk, &swapped, rng), Err(Error::InvalidSignature)));
+        let identity = Hello { ephemeral: G1Affine::zero(), ..hello };
+        assert!(matches!(Session::accept(&rec_sk, &identity, rng), Err(Error::InvalidMsg)));
+
+        // an answer from someone else, or to another hello
+        let (_, to_eve) = sender.open_session(&Receiver::new(bls::public_key(&eve_sk)), rng);
+        let (_, eve_reply) = Session::accept(&eve_sk, &to_eve, rng).unwrap();
+        assert!(matches!(pending.clone().complete(&eve_reply), Err(Error::IdentityMismatch)));
+        let (_, stale) = Session::accept(&rec_sk, &sender.open_session(&receiver, rng).1, rng).unwrap();
+        assert!(matches!(pending.clone().complete(&stale), Err(Error::InvalidSignature)));
+        let (_, reply) = Session::accept(&rec_sk, &hello, rng).unwrap();
+        assert!(pending.complete(&reply).is_ok());
+    }
+}
@@ -0,0 +1,271 @@
+//! Settings of the `chaos-theory` binary.
+//!
+//! Built in layers, later ones overriding earlier ones: the defaults, the
+//! `chaos-theory.toml` file (or the one named by `--config`), `CHAOS_*`
+//! environment variables (`CHAOS_BLOB`, `CHAOS_THREADS`, ...), then the global
+//! command-line flags, which come before the subcommand so that its own
+//! arguments are never taken for them. Unknown keys in the file are rejected
+//! so that typos don't go unnoticed.
+//!
+//! ```toml
+//! curve = "bls12-381"
+//! dst = "01030307"          # hex
+//! blob = "blob.bin"
+//! out_dir = "out"
+//! budget = 1048576          # exponents tried once the message space is exhausted
+//! threads = 8               # with the `parallel` feature
+//! format = "json"           # "text" or "json"
+//! log = "audit.jsonl"       # audit events are appended here
+//! gt_mul_rate = 1.2e6       # written by `calibrate`
+//! pairing_rate = 1500.0
+//! variant = "legacy"        # see `variants`; blob commands need "legacy"
+//! ```
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{attack::Rates, hex, tuning::Calibration, variants::SchemeVariant, Auditor, Error, SchemeConfig, DST};
+
+pub const SETTINGS_FILE: &str = "chaos-theory.toml";
+
+/// The only curve the scheme is implemented over.
+pub const CURVE: &str = "bls12-381";
+
+/// Every setting, as named in the file. The flag is `--` followed by the key
+/// with `-` for `_`, the environment variable `CHAOS_` followed by the key in
+/// upper case.
+pub const KEYS: [&str; 11] = [
+    "curve",
+    "dst",
+    "blob",
+    "out_dir",
+    "budget",
+    "threads",
+    "format",
+    "log",
+    "gt_mul_rate",
+    "pairing_rate",
+    "variant",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub curve: String,
+    pub dst: Vec<u8>,
+    /// Blob read by commands that take one, when not given on the command line.
+    pub blob: PathBuf,
+    /// Directory files are written to, when not given on the command line.
+    pub out_dir: PathBuf,
+    /// Exponents the solver brute-forces when the message space is exhausted.
+    pub budget: u128,
+    /// Size of the rayon pool; `None` lets rayon decide.
+    pub threads: Option<usize>,
+    /// Output format of results.
+    pub format: Format,
+    /// File audit events are appended to.
+    pub log: Option<PathBuf>,
+    /// Measured throughput on one core, see `tuning::calibrate`.
+    pub gt_mul_rate: Option<f64>,
+    pub pairing_rate: Option<f64>,
+    /// Only the legacy variant has blob commands (`attack`, `audit`, ...);
+    /// the CLI refuses to run them under another.
+    pub variant: SchemeVariant,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            curve: CURVE.to_string(),
+            dst: DST.to_vec(),
+            blob: PathBuf::from("blob.bin"),
+            out_dir: PathBuf::from("."),
+            budget: 1 << 20,
+            threads: None,
+            format: Format::Text,
+            log: None,
+            gt_mul_rate: None,
+            pairing_rate: None,
+            variant: SchemeVariant::Legacy,
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the settings file and applies the global flags, removing them
+    /// from the front of `args`. A missing default file is not an error; a
+    /// missing file named by `--config` is.
+    pub fn load(args: &mut Vec<String>) -> Result<Self, Error> {
+        let mut settings = Settings::default();
+        let mut flags = take_global_flags(args)?;
+        let config = flags.iter().rposition(|(key, _)| key == "config").map(|i| flags.remove(i).1);
+        flags.retain(|(key, _)| key != "config");
+        match config {
+            Some(path) => settings.merge_toml(&fs::read_to_string(path).map_err(|_| Error::InvalidConfig)?)?,
+            None => {
+                if let Ok(s) = fs::read_to_string(SETTINGS_FILE) {
+                    settings.merge_toml(&s)?;
+                }
+            }
+        }
+        for key in KEYS {
+            if let Ok(value) = env::var(format!("CHAOS_{}", key.to_uppercase())) {
+                settings.set(key, &value)?;
+            }
+        }
+        for (key, value) in flags {
+            settings.set(&key, &value)?;
+        }
+        settings.scheme_config()?;
+        Ok(settings)
+    }
+
+    pub fn merge_toml(&mut self, s: &str) -> Result<(), Error> {
+        let table: toml::Table = s.parse().map_err(|_| Error::InvalidConfig)?;
+        for (key, value) in table {
+            let value = match value {
+                toml::Value::String(s) => s,
+                toml::Value::Integer(i) => i.to_string(),
+                toml::Value::Float(f) => f.to_string(),
+                _ => return Err(Error::InvalidConfig),
+            };
+            self.set(&key, &value)?;
+        }
+        Ok(())
+    }
+
+    /// Sets one setting from its textual value.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        let invalid = |_| Error::InvalidConfig;
+        match key {
+            "curve" if value.eq_ignore_ascii_case(CURVE) => self.curve = CURVE.to_string(),
+            "dst" => self.dst = hex::decode(value)?,
+            "blob" => self.blob = PathBuf::from(value),
+            "out_dir" => self.out_dir = PathBuf::from(value),
+            "budget" => self.budget = value.parse().map_err(invalid)?,
+            "threads" => self.threads = Some(value.parse().map_err(invalid)?),
+            "format" if value == "text" => self.format = Format::Text,
+            "format" if value == "json" => self.format = Format::Json,
+            "log" => self.log = Some(PathBuf::from(value)),
+            "gt_mul_rate" => self.gt_mul_rate = Some(value.parse().map_err(|_| Error::InvalidConfig)?),
+            "pairing_rate" => self.pairing_rate = Some(value.parse().map_err(|_| Error::InvalidConfig)?),
+            "variant" => self.variant = value.parse()?,
+            _ => return Err(Error::InvalidConfig),
+        }
+        Ok(())
+    }
+
+    pub fn scheme_config(&self) -> Result<SchemeConfig, Error> {
+        SchemeConfig::builder().dst(&self.dst).build()
+    }
+
+    pub fn auditor(&self) -> Auditor {
+        Auditor::new(self.scheme_config().unwrap())
+    }
+
+    pub fn out_path(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.out_dir.join(name)
+    }
+
+    /// Records `calibration` in the settings file at `path`, editing only the
+    /// calibration keys so that the rest of the file, comments included,
+    /// stays as written, and in `self`.
+    pub fn persist_calibration(&mut self, path: impl AsRef<Path>, calibration: &Calibration) -> Result<(), Error> {
+        let mut doc: toml_edit::DocumentMut = match fs::read_to_string(&path) {
+            Ok(s) => s.parse().map_err(|_| Error::InvalidConfig)?,
+            Err(_) => toml_edit::DocumentMut::new(),
+        };
+        doc["gt_mul_rate"] = toml_edit::value(calibration.gt_mul_rate);
+        doc["pairing_rate"] = toml_edit::value(calibration.pairing_rate);
+        doc["threads"] = toml_edit::value(calibration.recommended_threads() as i64);
+        fs::write(path, doc.to_string()).map_err(|_| Error::Storage)?;
+
+        self.gt_mul_rate = Some(calibration.gt_mul_rate);
+        self.pairing_rate = Some(calibration.pairing_rate);
+        self.threads = Some(calibration.recommended_threads());
+        Ok(())
+    }
+
+    /// Calibrated rates, if `calibrate` has been run.
+    pub fn rates(&self) -> Option<Rates> {
+        Some(Rates {
+            gt_mul_rate: self.gt_mul_rate?,
+            pairing_rate: self.pairing_rate?,
+            threads: self.threads.unwrap_or(1),
+        })
+    }
+
+    /// Sizes the global rayon pool. Must run before any parallel work.
+    #[cfg(feature = "parallel")]
+    pub fn init_threads(&self) {
+        if let Some(n) = self.threads {
+            rayon::ThreadPoolBuilder::new().num_threads(n).build_global().unwrap();
+        }
+    }
+}
+
+// Removes the global flags, `--config` and `--<key> <value>` for every key,
+// from the front of `args` up to the first other argument (the subcommand),
+// and returns them as `(key, value)` in order.
+fn take_global_flags(args: &mut Vec<String>) -> Result<Vec<(String, String)>, Error> {
+    let mut flags = Vec::new();
+    while let Some(key) = args.first().and_then(|a| a.strip_prefix("--")).map(|name| name.replace('-', "_")) {
+        if key != "config" && !KEYS.contains(&key.as_str()) {
+            break;
+        }
+        if args.len() < 2 {
+            return Err(Error::InvalidConfig);
+        }
+        let value = args.remove(1);
+        args.remove(0);
+        flags.push((key, value));
+    }
+    Ok(flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn global_flags_stop_at_the_subcommand() {
+        let mut argv = args("--blob a.bin --log audit.jsonl audit --log other.jsonl --format json");
+        let flags = take_global_flags(&mut argv).unwrap();
+        let expected = [("blob", "a.bin"), ("log", "audit.jsonl")].map(|(k, v)| (k.to_string(), v.to_string()));
+        assert_eq!(flags, expected);
+        assert_eq!(argv, args("audit --log other.jsonl --format json"));
+
+        let mut argv = args("--verbose --blob a.bin");
+        assert!(take_global_flags(&mut argv).unwrap().is_empty());
+        assert!(take_global_flags(&mut args("--blob")).is_err());
+    }
+
+    #[test]
+    fn calibration_keeps_comments() {
+        let path = env::temp_dir().join(format!("chaos-theory-settings-{}.toml", std::process::id()));
+        fs::write(&path, "# my settings\nblob = \"x.bin\" # the blob\nthreads = 2\n").unwrap();
+        let calibration = Calibration { gt_mul_rate: 1e6, pairing_rate: 1500.0, cores: 4 };
+        let mut settings = Settings::default();
+        settings.persist_calibration(&path, &calibration).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(written.starts_with("# my settings\nblob = \"x.bin\" # the blob\n"));
+        let mut reloaded = Settings::default();
+        reloaded.merge_toml(&written).unwrap();
+        assert_eq!(reloaded.blob, PathBuf::from("x.bin"));
+        assert_eq!(reloaded.rates(), settings.rates());
+    }
+}
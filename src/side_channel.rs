@@ -0,0 +1,104 @@
+//! Blinded variants of the secret-key exponentiation in `authenticate`.
+//!
+//! `Sender::authenticate` multiplies `H(c)` by `sk` directly, so every call
+//! walks the same scalar bits. On shared hardware that makes simple power or
+//! timing analysis of `sk` easier; the blinded policies randomize the scalar
+//! actually used on each call while producing the same tag.
+
+use ark_bls12_381::{Fr, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, UniformRand};
+use ark_std::rand::Rng;
+use core::ops::Mul;
+
+use crate::{ElGamal, Sender};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SideChannelPolicy {
+    /// `H(c)^sk`, as `Sender::authenticate`.
+    #[default]
+    None,
+    /// `H(c)^r * H(c)^(sk - r)` for a fresh random scalar `r`.
+    SplitScalar,
+    /// `H(c)^(sk + k*q)` for a fresh random 64-bit `k`, `q` being the group order.
+    GroupOrderBlinding,
+}
+
+// sk + k*q as a 320-bit little-endian integer
+fn blind_scalar(sk: &Fr, k: u64) -> [u64; 5] {
+    let sk = sk.into_bigint().0;
+    let q = Fr::MODULUS.0;
+    let mut out = [0u64; 5];
+    let mut carry = 0u128;
+    for (o, (&q_i, &sk_i)) in out.iter_mut().zip(q.iter().zip(sk.iter())) {
+        let t = q_i as u128 * k as u128 + sk_i as u128 + carry;
+        *o = t as u64;
+        carry = t >> 64;
+    }
+    out[4] = carry as u64;
+    out
+}
+
+impl Sender {
+    pub fn authenticate_with_policy<R: Rng>(
+        &self,
+        c: &ElGamal,
+        aad: &[u8],
+        policy: SideChannelPolicy,
+        rng: &mut R,
+    ) -> G2Affine {
+        match policy {
+            SideChannelPolicy::None => self.authenticate(c, aad),
+            SideChannelPolicy::SplitScalar => {
+                let hash_c = self.config().hash_to_curve(c, aad);
+                let r = Fr::rand(rng);
+                (hash_c.mul(r) + hash_c.mul(self.sk - r)).into_affine()
+            }
+            SideChannelPolicy::GroupOrderBlinding => {
+                let hash_c = self.config().hash_to_curve(c, aad);
+                hash_c.mul_bigint(blind_scalar(&self.sk, rng.gen())).into_affine()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::G2Projective;
+    use ark_ec::Group;
+
+    use crate::{bls, Message, Receiver, SchemeConfig};
+
+    const POLICIES: [SideChannelPolicy; 3] =
+        [SideChannelPolicy::None, SideChannelPolicy::SplitScalar, SideChannelPolicy::GroupOrderBlinding];
+
+    #[test]
+    fn every_policy_gives_the_plain_tag() {
+        let rng = &mut ark_std::test_rng();
+        let receiver = Receiver::new(bls::public_key(&Fr::rand(rng)));
+        for config in [SchemeConfig::default(), SchemeConfig::standard()] {
+            let sender = Sender::with_config(Fr::rand(rng), config);
+            let c = sender.send(Message(bls::public_key(&Fr::rand(rng))), &receiver);
+            let expected = sender.authenticate(&c, b"aad");
+            for policy in POLICIES {
+                // fresh blinding on every call
+                for _ in 0..4 {
+                    assert_eq!(sender.authenticate_with_policy(&c, b"aad", policy, rng), expected, "{policy:?}");
+                }
+                assert_ne!(sender.authenticate_with_policy(&c, b"other", policy, rng), expected, "{policy:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn blinded_scalars_are_congruent_to_the_key() {
+        let sk = Fr::rand(&mut ark_std::test_rng());
+        let limbs = sk.into_bigint().0;
+        assert_eq!(blind_scalar(&sk, 0), [limbs[0], limbs[1], limbs[2], limbs[3], 0]);
+        for k in [1, 2, u64::MAX] {
+            let p = G2Projective::generator();
+            assert_eq!(p.mul_bigint(blind_scalar(&sk, k)), p * sk);
+        }
+    }
+}
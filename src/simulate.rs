@@ -0,0 +1,146 @@
+//! Honest runs of the protocol between many parties, as a stress test.
+//!
+//! Every sender sends its messages to receivers chosen at random; the blobs
+//! are tagged, audited in batches and finally attacked, and each phase is
+//! timed.
+
+use ark_bls12_381::Fr;
+use ark_ff::UniformRand;
+use ark_std::rand::Rng;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    attack, bls,
+    message_space::MessageSpace,
+    transcript::{Entry, Transcript},
+    Auditor, Blob, Receiver, Sender,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationStats {
+    pub senders: usize,
+    pub receivers: usize,
+    pub blobs: usize,
+    pub send: Duration,
+    pub authenticate: Duration,
+    pub audit: Duration,
+    pub attack: Duration,
+    /// Blobs the auditor accepted.
+    pub valid: usize,
+    /// Blobs whose plaintext the attack recovered correctly.
+    pub recovered: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub senders: Vec<Sender>,
+    pub receivers: Vec<Receiver>,
+    pub blobs: Vec<Blob>,
+    /// The auditor's verdict on each blob.
+    pub verdicts: Vec<bool>,
+    pub stats: SimulationStats,
+}
+
+fn per_sec(n: usize, d: Duration) -> f64 {
+    n as f64 / d.as_secs_f64().max(f64::EPSILON)
+}
+
+/// Runs `messages` messages per sender from `senders` senders to `receivers`
+/// receivers, with plaintexts drawn from `space` and an empty `aad`.
+pub fn simulate<R: Rng>(
+    senders: usize,
+    receivers: usize,
+    messages: usize,
+    space: &MessageSpace,
+    rng: &mut R,
+) -> Simulation {
+    let sender_keys: Vec<Sender> = (0..senders).map(|_| Sender::new(Fr::rand(rng))).collect();
+    let receiver_keys: Vec<Receiver> =
+        (0..receivers).map(|_| Receiver::new(bls::public_key(&Fr::rand(rng)))).collect();
+
+    let mut plan = Vec::with_capacity(senders * messages);
+    for sender in 0..senders {
+        for _ in 0..messages {
+            plan.push((sender, rng.gen_range(0..receivers), rng.gen_range(0..space.len())));
+        }
+    }
+
+    let start = Instant::now();
+    let cs: Vec<_> = plan
+        .iter()
+        .map(|&(i, j, m)| sender_keys[i].send(space.messages()[m], &receiver_keys[j]))
+        .collect();
+    let send = start.elapsed();
+
+    let start = Instant::now();
+    let blobs: Vec<Blob> = plan
+        .iter()
+        .zip(cs)
+        .map(|(&(i, j, _), c)| {
+            let s = sender_keys[i].authenticate(&c, &[]);
+            Blob { sender_pk: sender_keys[i].pk, c, s, rec_pk: receiver_keys[j].pk() }
+        })
+        .collect();
+    let authenticate = start.elapsed();
+
+    let auditor = Auditor::default();
+    let start = Instant::now();
+    let verdicts = auditor.verify_batch(&blobs, &[], rng);
+    let audit = start.elapsed();
+    let valid = verdicts.iter().filter(|&&v| v).count();
+
+    let start = Instant::now();
+    let recovered = blobs
+        .iter()
+        .zip(&plan)
+        .filter(|(blob, &(_, _, m))| attack::recover_index(blob, auditor.config(), &[], space) == Some(m))
+        .count();
+    let attack = start.elapsed();
+
+    let stats = SimulationStats {
+        senders,
+        receivers,
+        blobs: blobs.len(),
+        send,
+        authenticate,
+        audit,
+        attack,
+        valid,
+        recovered,
+    };
+    Simulation { senders: sender_keys, receivers: receiver_keys, blobs, verdicts, stats }
+}
+
+impl Simulation {
+    /// Everything exchanged in the run: the public keys, the blobs and the
+    /// auditor's verdicts.
+    pub fn transcript(&self) -> Transcript {
+        let mut transcript = Transcript::new();
+        for sender in &self.senders {
+            transcript.record(Entry::SenderKey(sender.pk));
+        }
+        for receiver in &self.receivers {
+            transcript.record(Entry::ReceiverKey(receiver.pk()));
+        }
+        for blob in &self.blobs {
+            transcript.record(Entry::Blob { blob: blob.clone(), aad: Vec::new() });
+        }
+        for (index, &valid) in self.verdicts.iter().enumerate() {
+            transcript.record(Entry::Verdict { index: index as u64, valid });
+        }
+        transcript
+    }
+}
+
+impl fmt::Display for SimulationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} senders, {} receivers, {} blobs", self.senders, self.receivers, self.blobs)?;
+        writeln!(f, "send:         {:>10.1} blobs/s", per_sec(self.blobs, self.send))?;
+        writeln!(f, "authenticate: {:>10.1} blobs/s", per_sec(self.blobs, self.authenticate))?;
+        writeln!(f, "audit:        {:>10.1} blobs/s ({} valid)", per_sec(self.blobs, self.audit), self.valid)?;
+        write!(f, "attack:       {:>10.1} blobs/s ({} recovered)", per_sec(self.blobs, self.attack), self.recovered)
+    }
+}
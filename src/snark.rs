@@ -0,0 +1,213 @@
+//! Groth16 verification over BLS12-381.
+//!
+//! `VerifyingKey` and `Proof` have the field order of `ark-groth16`, so their
+//! canonical encodings load the files written by arkworks-based provers. A
+//! proof for public inputs `x_1..x_n` is accepted when
+//!
+//! ```text
+//! e(A, B) = e(alpha, beta) * e(IC_0 + sum(x_i * IC_i), gamma) * e(C, delta)
+//! ```
+//!
+//! checked with a single multi-pairing. Policies that require a proof about
+//! a blob's payload pass `payload_input(c, aad)` as the first public input,
+//! which binds the proof to that ciphertext.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{fr_utils, pairing_utils::pairing_product_is_one, Auditor, ElGamal, Error};
+
+pub const PAYLOAD_DST: &[u8] = b"chaos-theory-snark-payload-v1";
+
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifyingKey {
+    pub alpha_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub gamma_g2: G2Affine,
+    pub delta_g2: G2Affine,
+    /// `IC_0..IC_n`, one more than the number of public inputs.
+    pub gamma_abc_g1: Vec<G1Affine>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof {
+    pub a: G1Affine,
+    pub b: G2Affine,
+    pub c: G1Affine,
+}
+
+impl VerifyingKey {
+    /// Loads a compressed verifying key, as written by `ark-groth16`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        Self::deserialize_compressed(data).map_err(|_| Error::InvalidEncoding)
+    }
+
+    pub fn public_inputs(&self) -> usize {
+        self.gamma_abc_g1.len().saturating_sub(1)
+    }
+
+    /// Fails with `InvalidConfig` if the number of inputs doesn't match the key.
+    pub fn verify(&self, proof: &Proof, inputs: &[Fr]) -> Result<bool, Error> {
+        if self.gamma_abc_g1.is_empty() || inputs.len() != self.public_inputs() {
+            return Err(Error::InvalidConfig);
+        }
+        let acc = G1Projective::from(self.gamma_abc_g1[0])
+            + G1Projective::msm(&self.gamma_abc_g1[1..], inputs).unwrap();
+        let g1 = [proof.a, -self.alpha_g1, -acc.into_affine(), -proof.c];
+        let g2 = [proof.b, self.beta_g2, self.gamma_g2, self.delta_g2];
+        Ok(pairing_product_is_one(g1, g2))
+    }
+}
+
+impl Proof {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        Self::deserialize_compressed(data).map_err(|_| Error::InvalidEncoding)
+    }
+}
+
+/// The public input binding a proof to the ciphertext `c` tagged under `aad`.
+pub fn payload_input(c: &ElGamal, aad: &[u8]) -> Fr {
+    let mut data = Vec::new();
+    c.serialize_compressed(&mut data).unwrap();
+    data.extend(aad);
+    fr_utils::hash_to_scalar(&data, PAYLOAD_DST).unwrap()
+}
+
+impl Auditor {
+    /// `verify`, additionally requiring a Groth16 proof under `vk` whose
+    /// public inputs are `payload_input(c, aad)` followed by `inputs`.
+    /// Fails with `InvalidProof` or `InvalidSignature`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn check_auth_with_snark(
+        &self,
+        vk: &VerifyingKey,
+        proof: &Proof,
+        inputs: &[Fr],
+        sender_pk: G1Affine,
+        c: &ElGamal,
+        s: G2Affine,
+        aad: &[u8],
+    ) -> Result<(), Error> {
+        let mut all = Vec::with_capacity(inputs.len() + 1);
+        all.push(payload_input(c, aad));
+        all.extend_from_slice(inputs);
+        if !vk.verify(proof, &all)? {
+            return Err(Error::InvalidProof);
+        }
+        if !self.verify(sender_pk, c, s, aad) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bls, Receiver, Sender};
+    use ark_bls12_381::G2Projective;
+    use ark_ec::Group;
+    use ark_ff::{Field, UniformRand};
+    use ark_std::rand::Rng;
+
+    // A key for `n` public inputs with its trapdoor, from which proofs of any
+    // inputs can be simulated: this checks the verification equation, not a
+    // circuit.
+    struct Setup {
+        vk: VerifyingKey,
+        alpha: Fr,
+        beta: Fr,
+        gamma: Fr,
+        delta: Fr,
+        ic: Vec<Fr>,
+    }
+
+    impl Setup {
+        fn new<R: Rng>(n: usize, rng: &mut R) -> Self {
+            let [alpha, beta, gamma, delta] = [(); 4].map(|_| Fr::rand(rng));
+            let ic: Vec<Fr> = (0..=n).map(|_| Fr::rand(rng)).collect();
+            let g1 = |x: Fr| (G1Projective::generator() * x).into_affine();
+            let g2 = |x: Fr| (G2Projective::generator() * x).into_affine();
+            let vk = VerifyingKey {
+                alpha_g1: g1(alpha),
+                beta_g2: g2(beta),
+                gamma_g2: g2(gamma),
+                delta_g2: g2(delta),
+                gamma_abc_g1: ic.iter().map(|&x| g1(x)).collect(),
+            };
+            Setup { vk, alpha, beta, gamma, delta, ic }
+        }
+
+        // a * b = alpha * beta + acc * gamma + c * delta, solved for c
+        fn prove<R: Rng>(&self, inputs: &[Fr], rng: &mut R) -> Proof {
+            let acc = self.ic[0] + inputs.iter().zip(&self.ic[1..]).map(|(x, u)| *x * u).sum::<Fr>();
+            let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+            let c = (a * b - self.alpha * self.beta - acc * self.gamma) * self.delta.inverse().unwrap();
+            Proof {
+                a: (G1Projective::generator() * a).into_affine(),
+                b: (G2Projective::generator() * b).into_affine(),
+                c: (G1Projective::generator() * c).into_affine(),
+            }
+        }
+    }
+
+    #[test]
+    fn proofs_verify_only_for_their_inputs() {
+        let rng = &mut ark_std::test_rng();
+        let setup = Setup::new(2, rng);
+        let inputs = [Fr::from(3u64), Fr::from(5u64)];
+        let proof = setup.prove(&inputs, rng);
+        assert!(setup.vk.verify(&proof, &inputs).unwrap());
+
+        assert!(!setup.vk.verify(&proof, &[Fr::from(3u64), Fr::from(6u64)]).unwrap());
+        let tampered = Proof { c: (proof.c + G1Affine::generator()).into_affine(), ..proof };
+        assert!(!setup.vk.verify(&tampered, &inputs).unwrap());
+        let tampered = Proof { a: setup.prove(&inputs, rng).a, ..proof };
+        assert!(!setup.vk.verify(&tampered, &inputs).unwrap());
+        assert!(!Setup::new(2, rng).vk.verify(&proof, &inputs).unwrap());
+        assert!(matches!(setup.vk.verify(&proof, &inputs[..1]), Err(Error::InvalidConfig)));
+    }
+
+    #[test]
+    fn encodings_round_trip() {
+        let rng = &mut ark_std::test_rng();
+        let setup = Setup::new(1, rng);
+        let proof = setup.prove(&[Fr::from(1u64)], rng);
+        let (mut vk_bytes, mut proof_bytes) = (Vec::new(), Vec::new());
+        setup.vk.serialize_compressed(&mut vk_bytes).unwrap();
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+        assert_eq!(VerifyingKey::from_bytes(&vk_bytes).unwrap(), setup.vk);
+        assert_eq!(Proof::from_bytes(&proof_bytes).unwrap(), proof);
+        assert!(matches!(Proof::from_bytes(&proof_bytes[1..]), Err(Error::InvalidEncoding)));
+    }
+
+    #[test]
+    fn snark_gated_audit() {
+        let rng = &mut ark_std::test_rng();
+        let setup = Setup::new(2, rng);
+        let sender = Sender::new(Fr::rand(rng));
+        let receiver = Receiver::new(bls::public_key(&Fr::rand(rng)));
+        let c = sender.send(crate::Message::from_exponent(7), &receiver);
+        let s = sender.authenticate(&c, b"aad");
+        let extra = [Fr::from(42u64)];
+        let proof = setup.prove(&[payload_input(&c, b"aad"), extra[0]], rng);
+
+        let auditor = Auditor::default();
+        assert!(auditor.check_auth_with_snark(&setup.vk, &proof, &extra, sender.pk, &c, s, b"aad").is_ok());
+        // a proof about another payload or other inputs
+        let other = sender.send(crate::Message::from_exponent(8), &receiver);
+        let s_other = sender.authenticate(&other, b"aad");
+        let result = auditor.check_auth_with_snark(&setup.vk, &proof, &extra, sender.pk, &other, s_other, b"aad");
+        assert!(matches!(result, Err(Error::InvalidProof)));
+        let result = auditor.check_auth_with_snark(&setup.vk, &proof, &[Fr::from(43u64)], sender.pk, &c, s, b"aad");
+        assert!(matches!(result, Err(Error::InvalidProof)));
+        // a valid proof on a forged tag
+        let forged = sender.authenticate(&other, b"aad");
+        let result = auditor.check_auth_with_snark(&setup.vk, &proof, &extra, sender.pk, &c, forged, b"aad");
+        assert!(matches!(result, Err(Error::InvalidSignature)));
+        let result = auditor.check_auth_with_snark(&setup.vk, &proof, &[], sender.pk, &c, s, b"aad");
+        assert!(matches!(result, Err(Error::InvalidConfig)));
+    }
+}
@@ -0,0 +1,43 @@
+//! Solidity source for an on-chain version of `Auditor::check_auth`.
+//!
+//! The contract runs `e(-g1, s) * e(pk, H(c)) == 1` through the EIP-2537
+//! pairing-check precompile. Hash-to-curve is not done on-chain: callers pass
+//! `H(c)` (from `ElGamal::hash_to_curve`) alongside the tag, all encoded with
+//! the helpers in [`crate::eip2537`].
+
+use alloc::{format, string::String};
+use ark_bls12_381::G1Affine;
+use ark_ec::AffineRepr;
+
+use crate::{eip2537, hex};
+
+/// Address of `BLS12_PAIRING_CHECK` as deployed with EIP-2537.
+pub const PAIRING_CHECK_PRECOMPILE: u8 = 0x0f;
+
+pub fn verifier_contract(name: &str) -> String {
+    let neg_g1 = hex::encode(&eip2537::encode_g1(&-G1Affine::generator()));
+    let (g1_len, g2_len) = (eip2537::G1_LEN, eip2537::G2_LEN);
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.24;
+
+/// Verifies authentication tags of the encrypt + sign scheme: e(g1, s) == e(pk, H(c)).
+/// All points use the EIP-2537 encoding; H(c) is computed off-chain.
+contract {name} {{
+    address constant BLS12_PAIRING_CHECK = address(0x{PAIRING_CHECK_PRECOMPILE:02x});
+
+    bytes constant NEG_G1 = hex"{neg_g1}";
+
+    function checkAuth(bytes calldata senderPk, bytes calldata s, bytes calldata hashC) external view returns (bool) {{
+        require(senderPk.length == {g1_len}, "invalid sender pk");
+        require(s.length == {g2_len} && hashC.length == {g2_len}, "invalid G2 point");
+
+        bytes memory input = abi.encodePacked(NEG_G1, s, senderPk, hashC);
+        (bool ok, bytes memory out) = BLS12_PAIRING_CHECK.staticcall(input);
+        require(ok && out.length == 32, "pairing check failed");
+        return abi.decode(out, (uint256)) == 1;
+    }}
+}}
+"#
+    )
+}
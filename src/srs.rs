@@ -0,0 +1,328 @@
+//! Loading powers-of-tau structured reference strings.
+//!
+//! An SRS is `tau^i * g1` for `i = 0..=degree` and `tau^i * g2` for the first
+//! few `i`. Two file layouts are read:
+//!
+//! - `SrsFormat::Arkworks`: the compressed canonical encoding of the G1
+//!   powers followed by the G2 powers, each a length-prefixed `Vec`.
+//! - `SrsFormat::Zcash`: the uncompressed `challenge` files of the
+//!   `powersoftau` ceremonies for BLS12-381, a 64-byte hash followed by
+//!   `2^(power + 1) - 1` G1 powers and `2^power` G2 powers in the Zcash point
+//!   encoding (big-endian coordinates, flags in the top bits).
+//!
+//! Only the powers up to the requested degree are read, every point is
+//! checked to be in the prime-order subgroup, and `check_powers` verifies
+//! with one multi-pairing that the points are powers of the same `tau`.
+//! `SrsCache` keeps loaded files and reloads one only when a larger degree
+//! is asked for.
+
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{AffineRepr, VariableBaseMSM};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use crate::{eip2537, h2c, pairing_utils::pairing_product_is_one, Error};
+
+pub const SRS_DST: &[u8] = b"chaos-theory-srs-v1";
+
+const HASH_LEN: u64 = 64;
+const ZCASH_G1_LEN: usize = 96;
+const ZCASH_G2_LEN: usize = 192;
+const COMPRESSED_G1_LEN: u64 = 48;
+/// G2 powers read: `g2` and `tau * g2`, what KZG opening checks need.
+pub const G2_POWERS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SrsFormat {
+    Arkworks,
+    /// The ceremony supports polynomials of degree below `2^power`.
+    Zcash { power: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Srs {
+    pub g1: Vec<G1Affine>,
+    pub g2: Vec<G2Affine>,
+}
+
+fn io_err(_: std::io::Error) -> Error {
+    Error::InvalidEncoding
+}
+
+// The Zcash encoding of a coordinate list as the EIP-2537 one: each 48-byte
+// value padded to 64 bytes. Fails on the compression flag; `None` for the
+// point at infinity.
+fn zcash_to_eip2537(bytes: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+    let (compressed, infinity) = (bytes[0] & 0x80 != 0, bytes[0] & 0x40 != 0);
+    if compressed || bytes[0] & 0x20 != 0 {
+        return Err(Error::InvalidEncoding);
+    }
+    if infinity {
+        return Ok(None);
+    }
+    let mut out = Vec::with_capacity(bytes.len() / 48 * eip2537::FP_LEN);
+    for (i, fp) in bytes.chunks(48).enumerate() {
+        out.extend([0u8; eip2537::FP_LEN - 48]);
+        out.extend(fp);
+        if i == 0 {
+            out[eip2537::FP_LEN - 48] &= 0x1f;
+        }
+    }
+    Ok(Some(out))
+}
+
+fn zcash_g1(bytes: &[u8]) -> Result<G1Affine, Error> {
+    match zcash_to_eip2537(bytes)? {
+        Some(data) => eip2537::decode_g1(&data),
+        None => Ok(G1Affine::zero()),
+    }
+}
+
+// Zcash orders the Fq2 components c1 before c0, EIP-2537 c0 before c1.
+fn zcash_g2(bytes: &[u8]) -> Result<G2Affine, Error> {
+    let Some(data) = zcash_to_eip2537(bytes)? else {
+        return Ok(G2Affine::zero());
+    };
+    let fp = |i: usize| &data[i * eip2537::FP_LEN..(i + 1) * eip2537::FP_LEN];
+    eip2537::decode_g2(&[fp(1), fp(0), fp(3), fp(2)].concat())
+}
+
+fn read_points<R: Read, T>(
+    reader: &mut R,
+    count: usize,
+    len: usize,
+    decode: fn(&[u8]) -> Result<T, Error>,
+) -> Result<Vec<T>, Error> {
+    let mut buf = vec![0u8; len];
+    (0..count)
+        .map(|_| {
+            reader.read_exact(&mut buf).map_err(io_err)?;
+            decode(&buf)
+        })
+        .collect()
+}
+
+// Reads the length prefix of a canonical `Vec` and checks it holds `needed` elements.
+fn read_len<R: Read>(reader: &mut R, needed: usize) -> Result<u64, Error> {
+    let len = u64::deserialize_compressed(&mut *reader).map_err(|_| Error::InvalidEncoding)?;
+    if len < needed as u64 {
+        return Err(Error::InvalidConfig);
+    }
+    Ok(len)
+}
+
+impl Srs {
+    /// The largest polynomial degree the SRS supports.
+    pub fn degree(&self) -> usize {
+        self.g1.len().saturating_sub(1)
+    }
+
+    /// Reads the powers needed for polynomials up to `degree`. Fails with
+    /// `InvalidConfig` if the file has fewer and `InvalidEncoding` on
+    /// malformed or invalid points.
+    pub fn read<R: Read + Seek>(reader: &mut R, format: SrsFormat, degree: usize) -> Result<Self, Error> {
+        let needed = degree.checked_add(1).ok_or(Error::InvalidConfig)?;
+        match format {
+            SrsFormat::Arkworks => {
+                let len = read_len(reader, needed)?;
+                let g1 = (0..needed)
+                    .map(|_| G1Affine::deserialize_compressed(&mut *reader).map_err(|_| Error::InvalidEncoding))
+                    .collect::<Result<Vec<_>, _>>()?;
+                // the length header is untrusted: the rest of the G1 powers may not fit a seek
+                let rest = (len - needed as u64)
+                    .checked_mul(COMPRESSED_G1_LEN)
+                    .and_then(|n| i64::try_from(n).ok())
+                    .ok_or(Error::InvalidEncoding)?;
+                reader.seek(SeekFrom::Current(rest)).map_err(io_err)?;
+                read_len(reader, G2_POWERS)?;
+                let g2 = (0..G2_POWERS)
+                    .map(|_| G2Affine::deserialize_compressed(&mut *reader).map_err(|_| Error::InvalidEncoding))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Srs { g1, g2 })
+            }
+            SrsFormat::Zcash { power } => {
+                // the G2 powers follow the hash and the `2^(power + 1) - 1` G1
+                // powers, which overflow the offset for `power` above 56
+                let g2_offset = power
+                    .checked_add(1)
+                    .and_then(|p| 1u64.checked_shl(p))
+                    .and_then(|n| (n - 1).checked_mul(ZCASH_G1_LEN as u64))
+                    .and_then(|n| n.checked_add(HASH_LEN))
+                    .ok_or(Error::InvalidConfig)?;
+                if needed as u64 > 1 << power {
+                    return Err(Error::InvalidConfig);
+                }
+                reader.seek(SeekFrom::Start(HASH_LEN)).map_err(io_err)?;
+                let g1 = read_points(reader, needed, ZCASH_G1_LEN, zcash_g1)?;
+                reader.seek(SeekFrom::Start(g2_offset)).map_err(io_err)?;
+                let g2 = read_points(reader, G2_POWERS, ZCASH_G2_LEN, zcash_g2)?;
+                Ok(Srs { g1, g2 })
+            }
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>, format: SrsFormat, degree: usize) -> Result<Self, Error> {
+        let mut reader = BufReader::new(File::open(path).map_err(|_| Error::Storage)?);
+        Self::read(&mut reader, format, degree)
+    }
+
+    /// Whether `g1[i] = tau^i * g1` and `g2[1] = tau * g2` for one `tau`, by
+    /// checking `e(g1[i + 1], g2[0]) = e(g1[i], g2[1])` for a random linear
+    /// combination of all `i`. Both bases must be the generators: the ratio
+    /// checks alone pass for `g2` scaled by any constant.
+    pub fn check_powers(&self) -> bool {
+        if self.g1.len() < 2
+            || self.g2.len() < 2
+            || self.g1[0] != G1Affine::generator()
+            || self.g2[0] != G2Affine::generator()
+        {
+            return false;
+        }
+        let mut data = Vec::new();
+        self.g1.serialize_compressed(&mut data).unwrap();
+        self.g2.serialize_compressed(&mut data).unwrap();
+        let r: Vec<Fr> = h2c::hash_to_field(&data, SRS_DST, self.g1.len() - 1).unwrap();
+        let n = self.g1.len() - 1;
+        let shifted = G1Projective::msm(&self.g1[1..], &r).unwrap();
+        let base = G1Projective::msm(&self.g1[..n], &r).unwrap();
+        pairing_product_is_one([shifted, -base], [self.g2[0], self.g2[1]])
+    }
+}
+
+/// Loaded SRS files, by path.
+#[derive(Debug, Default)]
+pub struct SrsCache {
+    entries: HashMap<(PathBuf, SrsFormat), Srs>,
+}
+
+impl SrsCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The SRS in `path` with at least `degree`, loading it on first use or
+    /// when a larger degree than the cached one is asked for.
+    pub fn get(&mut self, path: impl AsRef<Path>, format: SrsFormat, degree: usize) -> Result<&Srs, Error> {
+        let key = (path.as_ref().to_path_buf(), format);
+        if !matches!(self.entries.get(&key), Some(srs) if srs.degree() >= degree) {
+            let srs = Srs::load(&key.0, format, degree)?;
+            self.entries.insert(key.clone(), srs);
+        }
+        Ok(&self.entries[&key])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::G2Projective;
+    use ark_ec::{CurveGroup, Group};
+    use ark_ff::{Field, UniformRand};
+    use std::io::Cursor;
+
+    fn powers(tau: Fr, degree: usize) -> Srs {
+        let g1 = (0..=degree as u64).map(|i| (G1Projective::generator() * tau.pow([i])).into_affine()).collect();
+        let g2 = vec![G2Affine::generator(), (G2Projective::generator() * tau).into_affine()];
+        Srs { g1, g2 }
+    }
+
+    #[test]
+    fn powers_of_one_tau_pass() {
+        let srs = powers(Fr::rand(&mut ark_std::test_rng()), 8);
+        assert!(srs.check_powers());
+
+        let mut data = Vec::new();
+        srs.g1.serialize_compressed(&mut data).unwrap();
+        srs.g2.serialize_compressed(&mut data).unwrap();
+        let read = Srs::read(&mut Cursor::new(&data), SrsFormat::Arkworks, 4).unwrap();
+        assert_eq!(read, Srs { g1: srs.g1[..5].to_vec(), g2: srs.g2.clone() });
+        assert!(read.check_powers());
+        assert!(matches!(Srs::read(&mut Cursor::new(&data), SrsFormat::Arkworks, 9), Err(Error::InvalidConfig)));
+
+        // a length header whose skipped powers overflow the seek
+        data[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(Srs::read(&mut Cursor::new(&data), SrsFormat::Arkworks, 4), Err(Error::InvalidEncoding)));
+        let degree = usize::MAX;
+        assert!(matches!(Srs::read(&mut Cursor::new(&data), SrsFormat::Arkworks, degree), Err(Error::InvalidConfig)));
+    }
+
+    // The Zcash encoding from the EIP-2537 one: 48-byte coordinates, with the
+    // Fq2 components of G2 points swapped.
+    fn zcash_g1_bytes(p: &G1Affine) -> Vec<u8> {
+        eip2537::encode_g1(p).chunks(eip2537::FP_LEN).flat_map(|fp| fp[eip2537::FP_LEN - 48..].to_vec()).collect()
+    }
+
+    fn zcash_g2_bytes(p: &G2Affine) -> Vec<u8> {
+        let data = eip2537::encode_g2(p);
+        let fp = |i: usize| &data[(i + 1) * eip2537::FP_LEN - 48..(i + 1) * eip2537::FP_LEN];
+        [fp(1), fp(0), fp(3), fp(2)].concat()
+    }
+
+    #[test]
+    fn zcash_files_are_read() {
+        let tau = Fr::rand(&mut ark_std::test_rng());
+        // power 2: a hash, 7 G1 powers and 4 G2 powers
+        let srs = powers(tau, 6);
+        let g2: Vec<G2Affine> = (0..4u64).map(|i| (G2Projective::generator() * tau.pow([i])).into_affine()).collect();
+        let mut data = vec![0xaa; HASH_LEN as usize];
+        data.extend(srs.g1.iter().flat_map(zcash_g1_bytes));
+        data.extend(g2.iter().flat_map(zcash_g2_bytes));
+        let path = std::env::temp_dir().join(format!("chaos-theory-srs-{}.challenge", std::process::id()));
+        std::fs::write(&path, &data).unwrap();
+
+        let format = SrsFormat::Zcash { power: 2 };
+        let read = Srs::load(&path, format, 3);
+        std::fs::remove_file(&path).unwrap();
+        let read = read.unwrap();
+        assert_eq!(read, Srs { g1: srs.g1[..4].to_vec(), g2: g2[..2].to_vec() });
+        assert!(read.check_powers());
+        // degrees up to 2^power - 1 only
+        assert!(matches!(Srs::read(&mut Cursor::new(&data), format, 4), Err(Error::InvalidConfig)));
+
+        // the compression flag is rejected, the infinity flag reads the identity
+        let mut flagged = data.clone();
+        flagged[HASH_LEN as usize] |= 0x80;
+        assert!(matches!(Srs::read(&mut Cursor::new(&flagged), format, 3), Err(Error::InvalidEncoding)));
+        let at = HASH_LEN as usize + ZCASH_G1_LEN;
+        flagged = data.clone();
+        flagged[at..at + ZCASH_G1_LEN].fill(0);
+        flagged[at] = 0x40;
+        let read = Srs::read(&mut Cursor::new(&flagged), format, 3).unwrap();
+        assert!(read.g1[1].is_zero());
+        assert!(!read.check_powers());
+    }
+
+    #[test]
+    fn oversized_zcash_powers_are_rejected() {
+        let mut data = Cursor::new(vec![0u8; 1024]);
+        for power in [57, 63, 64, u32::MAX] {
+            assert!(matches!(Srs::read(&mut data, SrsFormat::Zcash { power }, 1), Err(Error::InvalidConfig)));
+        }
+    }
+
+    #[test]
+    fn tampered_powers_fail() {
+        let rng = &mut ark_std::test_rng();
+        let (tau, k) = (Fr::rand(rng), Fr::rand(rng));
+        let srs = powers(tau, 8);
+
+        let mut tampered = srs.clone();
+        tampered.g1[3] = (tampered.g1[3] + G1Affine::generator()).into_affine();
+        assert!(!tampered.check_powers());
+        let mut tampered = srs.clone();
+        tampered.g2[1] = powers(Fr::rand(rng), 1).g2[1];
+        assert!(!tampered.check_powers());
+        let mut tampered = srs.clone();
+        tampered.g1[0] = tampered.g1[1];
+        assert!(!tampered.check_powers());
+
+        // k * g2 and k * tau * g2 keep every pairing ratio
+        let shifted = Srs { g1: srs.g1.clone(), g2: srs.g2.iter().map(|p| (*p * k).into_affine()).collect() };
+        assert!(!shifted.check_powers());
+    }
+}
@@ -0,0 +1,152 @@
+//! One-time stealth receiver keys.
+//!
+//! A receiver publishes a meta-address `(A, B) = (a * g1, b * g1)` from a
+//! scan key `a` and a spend key `b`. For each blob the sender draws an
+//! ephemeral `r`, sends to the one-time key `P = H(r * A) * g1 + B` and
+//! publishes `R = r * g1` alongside the blob. The receiver recognises its
+//! blobs with `a` alone, since `H(a * R) * g1 + B = P`, and recovers the
+//! one-time secret key `H(a * R) + b` with `b`. One-time keys of the same
+//! receiver are unlinkable without `a`.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine, G1Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::UniformRand;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::Rng;
+
+use crate::{bls, fr_utils, Receiver};
+
+pub const STEALTH_DST: &[u8] = b"chaos-theory-stealth-v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetaAddress {
+    pub scan_pk: G1Affine,
+    pub spend_pk: G1Affine,
+}
+
+#[derive(Clone)]
+pub struct StealthKeys {
+    pub scan_sk: Fr,
+    pub spend_sk: Fr,
+}
+
+/// A one-time receiver and the ephemeral key published with the blob.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct StealthAddress {
+    pub receiver: Receiver,
+    pub ephemeral: G1Affine,
+}
+
+// H(shared secret), the scalar added to the spend key
+fn tweak(shared: &G1Projective) -> Fr {
+    let mut data = Vec::new();
+    shared.into_affine().serialize_compressed(&mut data).unwrap();
+    fr_utils::hash_to_scalar(&data, STEALTH_DST).unwrap()
+}
+
+impl MetaAddress {
+    /// A fresh one-time receiver for this meta-address.
+    pub fn derive<R: Rng>(&self, rng: &mut R) -> StealthAddress {
+        let r = Fr::rand(rng);
+        let p = G1Affine::generator() * tweak(&(self.scan_pk * r)) + self.spend_pk;
+        StealthAddress { receiver: Receiver::new(p.into_affine()), ephemeral: bls::public_key(&r) }
+    }
+}
+
+impl StealthKeys {
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
+        StealthKeys { scan_sk: Fr::rand(rng), spend_sk: Fr::rand(rng) }
+    }
+
+    pub fn meta_address(&self) -> MetaAddress {
+        MetaAddress { scan_pk: bls::public_key(&self.scan_sk), spend_pk: bls::public_key(&self.spend_sk) }
+    }
+
+    /// Whether `rec_pk` is a one-time key of this receiver, given the
+    /// ephemeral key published with it. Needs only the scan key, so it can
+    /// be delegated to a scanning service.
+    pub fn owns(scan_sk: &Fr, spend_pk: &G1Affine, rec_pk: &G1Affine, ephemeral: &G1Affine) -> bool {
+        (G1Affine::generator() * tweak(&(*ephemeral * scan_sk)) + spend_pk).into_affine() == *rec_pk
+    }
+
+    /// The secret key of the one-time key `rec_pk`, if it is one of ours.
+    pub fn one_time_sk(&self, rec_pk: &G1Affine, ephemeral: &G1Affine) -> Option<Fr> {
+        let sk = tweak(&(*ephemeral * self.scan_sk)) + self.spend_sk;
+        (bls::public_key(&sk) == *rec_pk).then_some(sk)
+    }
+
+    /// Indices of the `(rec_pk, ephemeral)` pairs addressed to this receiver.
+    pub fn scan(&self, addresses: &[(G1Affine, G1Affine)]) -> Vec<usize> {
+        let spend_pk = bls::public_key(&self.spend_sk);
+        addresses
+            .iter()
+            .enumerate()
+            .filter(|(_, (rec_pk, ephemeral))| Self::owns(&self.scan_sk, &spend_pk, rec_pk, ephemeral))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn receiver_owns_and_spends_derived_keys() {
+        let rng = &mut ark_std::test_rng();
+        let keys = StealthKeys::new(rng);
+        let meta = keys.meta_address();
+        let address = meta.derive(rng);
+        let rec_pk = address.receiver.pk();
+
+        assert!(StealthKeys::owns(&keys.scan_sk, &meta.spend_pk, &rec_pk, &address.ephemeral));
+        let sk = keys.one_time_sk(&rec_pk, &address.ephemeral).unwrap();
+        assert_eq!(bls::public_key(&sk), rec_pk);
+    }
+
+    #[test]
+    fn other_receivers_dont_own_derived_keys() {
+        let rng = &mut ark_std::test_rng();
+        let (keys, other) = (StealthKeys::new(rng), StealthKeys::new(rng));
+        let address = keys.meta_address().derive(rng);
+        let rec_pk = address.receiver.pk();
+
+        let other_spend_pk = other.meta_address().spend_pk;
+        assert!(!StealthKeys::owns(&other.scan_sk, &other_spend_pk, &rec_pk, &address.ephemeral));
+        assert_eq!(other.one_time_sk(&rec_pk, &address.ephemeral), None);
+        // the scan key alone isn't enough with another spend key, nor the reverse
+        assert!(!StealthKeys::owns(&keys.scan_sk, &other_spend_pk, &rec_pk, &address.ephemeral));
+        assert!(!StealthKeys::owns(&other.scan_sk, &keys.meta_address().spend_pk, &rec_pk, &address.ephemeral));
+    }
+
+    #[test]
+    fn scan_finds_exactly_own_addresses() {
+        let rng = &mut ark_std::test_rng();
+        let (keys, other) = (StealthKeys::new(rng), StealthKeys::new(rng));
+        let (mine, theirs) = (keys.meta_address(), other.meta_address());
+        let addresses: Vec<(G1Affine, G1Affine)> = [&theirs, &mine, &mine, &theirs, &mine]
+            .iter()
+            .map(|meta| meta.derive(rng))
+            .map(|address| (address.receiver.pk(), address.ephemeral))
+            .collect();
+
+        assert_eq!(keys.scan(&addresses), vec![1, 2, 4]);
+        assert_eq!(other.scan(&addresses), vec![0, 3]);
+        assert_eq!(keys.scan(&[]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn derivations_are_unlinkable() {
+        let rng = &mut ark_std::test_rng();
+        let keys = StealthKeys::new(rng);
+        let meta = keys.meta_address();
+        let (first, second) = (meta.derive(rng), meta.derive(rng));
+
+        assert_ne!(first.receiver.pk(), second.receiver.pk());
+        assert_ne!(first.ephemeral, second.ephemeral);
+        assert_ne!(first.receiver.pk(), meta.spend_pk);
+        // each one-time key only opens with its own ephemeral key
+        assert_eq!(keys.one_time_sk(&first.receiver.pk(), &second.ephemeral), None);
+    }
+}
@@ -0,0 +1,233 @@
+//! SQLite persistence for received blobs, their audit verdicts and sender
+//! registry entries.
+//!
+//! Blobs are keyed by the SHA-256 of their uncompressed encoding, so storing
+//! the same blob twice keeps a single row, and stored compressed; rows written
+//! uncompressed by earlier versions still load. Times are opaque `u64`s, as in
+//! `registry`. A verdict only holds for the auditor configuration it was
+//! reached under; `bind_auditor` ties a database to one.
+
+use ark_bls12_381::G1Affine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::path::Path;
+
+use crate::{diagnostics::COMPRESSED_BLOB_LEN, registry::KeyEntry, Blob, Error};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS blobs (
+    id          INTEGER PRIMARY KEY,
+    hash        BLOB NOT NULL UNIQUE,
+    sender_pk   BLOB NOT NULL,
+    data        BLOB NOT NULL,
+    received_at INTEGER NOT NULL,
+    verdict     INTEGER,
+    checked_at  INTEGER
+);
+CREATE INDEX IF NOT EXISTS blobs_sender ON blobs (sender_pk);
+CREATE INDEX IF NOT EXISTS blobs_received ON blobs (received_at);
+CREATE TABLE IF NOT EXISTS auditor (
+    id          INTEGER PRIMARY KEY CHECK (id = 0),
+    dst         BLOB NOT NULL,
+    strict      INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS keys (
+    identity    TEXT NOT NULL,
+    pk          BLOB NOT NULL,
+    valid_from  INTEGER NOT NULL,
+    valid_until INTEGER,
+    revoked_at  INTEGER,
+    PRIMARY KEY (identity, pk)
+);
+";
+
+const BLOB_COLUMNS: &str = "id, data, received_at, verdict";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredBlob {
+    pub id: i64,
+    pub blob: Blob,
+    pub received_at: u64,
+    /// `None` until the blob has been audited.
+    pub verdict: Option<bool>,
+}
+
+fn db_err(_: rusqlite::Error) -> Error {
+    Error::Storage
+}
+
+fn to_bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_uncompressed(&mut bytes).unwrap();
+    bytes
+}
+
+fn compressed(blob: &Blob) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    blob.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+fn blob_from_bytes(data: &[u8]) -> Result<Blob, Error> {
+    let blob = if data.len() == COMPRESSED_BLOB_LEN {
+        Blob::deserialize_compressed(data)
+    } else {
+        Blob::deserialize_uncompressed(data)
+    };
+    blob.map_err(|_| Error::InvalidEncoding)
+}
+
+fn stored_blob(row: &Row) -> rusqlite::Result<(i64, Vec<u8>, i64, Option<bool>)> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+}
+
+pub struct AuditDb {
+    conn: Connection,
+}
+
+impl AuditDb {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::init(Connection::open(path).map_err(db_err)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::init(Connection::open_in_memory().map_err(db_err)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, Error> {
+        conn.execute_batch(SCHEMA).map_err(db_err)?;
+        Ok(AuditDb { conn })
+    }
+
+    /// Ties the stored verdicts to the auditor configuration they are reached
+    /// under: records `dst` and `strict` on first use, and fails with
+    /// `InvalidConfig` if the database is tied to another configuration.
+    pub fn bind_auditor(&self, dst: &[u8], strict: bool) -> Result<(), Error> {
+        self.conn
+            .execute("INSERT OR IGNORE INTO auditor (id, dst, strict) VALUES (0, ?1, ?2)", params![dst, strict])
+            .map_err(db_err)?;
+        let (bound_dst, bound_strict): (Vec<u8>, bool) = self
+            .conn
+            .query_row("SELECT dst, strict FROM auditor WHERE id = 0", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(db_err)?;
+        if bound_dst != dst || bound_strict != strict {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    /// Stores `blob` unless it is already present; returns its row id either way.
+    pub fn insert_blob(&self, blob: &Blob, received_at: u64) -> Result<i64, Error> {
+        let hash = blob.content_hash();
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO blobs (hash, sender_pk, data, received_at) VALUES (?1, ?2, ?3, ?4)",
+                params![&hash[..], to_bytes(&blob.sender_pk), compressed(blob), received_at as i64],
+            )
+            .map_err(db_err)?;
+        self.conn
+            .query_row("SELECT id FROM blobs WHERE hash = ?1", [&hash[..]], |row| row.get(0))
+            .map_err(db_err)
+    }
+
+    pub fn record_verdict(&self, id: i64, valid: bool, checked_at: u64) -> Result<(), Error> {
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE blobs SET verdict = ?1, checked_at = ?2 WHERE id = ?3",
+                params![valid, checked_at as i64, id],
+            )
+            .map_err(db_err)?;
+        if updated == 0 {
+            return Err(Error::Storage);
+        }
+        Ok(())
+    }
+
+    /// The stored verdict for `blob`, or `None` if it is unknown or unaudited.
+    pub fn verdict(&self, blob: &Blob) -> Result<Option<bool>, Error> {
+        self.conn
+            .query_row("SELECT verdict FROM blobs WHERE hash = ?1", [&blob.content_hash()[..]], |row| row.get(0))
+            .optional()
+            .map(Option::flatten)
+            .map_err(db_err)
+    }
+
+    fn query_blobs<P: rusqlite::Params>(&self, filter: &str, params: P) -> Result<Vec<StoredBlob>, Error> {
+        let sql = format!("SELECT {BLOB_COLUMNS} FROM blobs WHERE {filter} ORDER BY id");
+        let mut stmt = self.conn.prepare(&sql).map_err(db_err)?;
+        let rows = stmt.query_map(params, stored_blob).map_err(db_err)?;
+        rows.map(|row| {
+            let (id, data, received_at, verdict) = row.map_err(db_err)?;
+            let blob = blob_from_bytes(&data)?;
+            Ok(StoredBlob { id, blob, received_at: received_at as u64, verdict })
+        })
+        .collect()
+    }
+
+    pub fn by_sender(&self, sender_pk: &G1Affine) -> Result<Vec<StoredBlob>, Error> {
+        self.query_blobs("sender_pk = ?1", [to_bytes(sender_pk)])
+    }
+
+    /// Blobs received in `[from, to)`.
+    pub fn by_time_range(&self, from: u64, to: u64) -> Result<Vec<StoredBlob>, Error> {
+        self.query_blobs("received_at >= ?1 AND received_at < ?2", [from as i64, to as i64])
+    }
+
+    pub fn unverified(&self) -> Result<Vec<StoredBlob>, Error> {
+        self.query_blobs("verdict IS NULL", [])
+    }
+
+    /// Stores a registry entry, replacing any previous state of the same key.
+    pub fn put_key(&self, identity: &str, entry: &KeyEntry) -> Result<(), Error> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO keys (identity, pk, valid_from, valid_until, revoked_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    identity,
+                    to_bytes(&entry.pk),
+                    entry.valid_from as i64,
+                    entry.valid_until.map(|t| t as i64),
+                    entry.revoked_at.map(|t| t as i64),
+                ],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn keys(&self, identity: &str) -> Result<Vec<KeyEntry>, Error> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT pk, valid_from, valid_until, revoked_at FROM keys WHERE identity = ?1 ORDER BY valid_from")
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map([identity], |row| {
+                Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, i64>(1)?, row.get::<_, Option<i64>>(2)?, row.get::<_, Option<i64>>(3)?))
+            })
+            .map_err(db_err)?;
+        rows.map(|row| {
+            let (pk, valid_from, valid_until, revoked_at) = row.map_err(db_err)?;
+            Ok(KeyEntry {
+                pk: G1Affine::deserialize_uncompressed(pk.as_slice()).map_err(|_| Error::InvalidEncoding)?,
+                valid_from: valid_from as u64,
+                valid_until: valid_until.map(|t| t as u64),
+                revoked_at: revoked_at.map(|t| t as u64),
+            })
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verdicts_are_bound_to_one_auditor_configuration() {
+        let db = AuditDb::open_in_memory().unwrap();
+        db.bind_auditor(&[1, 3, 3, 7], false).unwrap();
+        db.bind_auditor(&[1, 3, 3, 7], false).unwrap();
+        assert!(matches!(db.bind_auditor(b"other", false), Err(Error::InvalidConfig)));
+        assert!(matches!(db.bind_auditor(&[1, 3, 3, 7], true), Err(Error::InvalidConfig)));
+    }
+}
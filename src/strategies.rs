@@ -0,0 +1,116 @@
+//! `proptest` strategies for the scheme's types, for downstream property and
+//! fuzz-style tests. Everything is derived from uniformly drawn bytes, so
+//! shrinking works on the underlying scalars.
+
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+use proptest::prelude::*;
+use std::ops::Mul;
+
+use crate::{Blob, ElGamal, Message, Receiver, Sender};
+
+pub fn scalar() -> impl Strategy<Value = Fr> {
+    any::<[u8; 32]>().prop_map(|bytes| Fr::from_le_bytes_mod_order(&bytes))
+}
+
+pub fn g1() -> impl Strategy<Value = G1Affine> {
+    scalar().prop_map(|k| G1Projective::generator().mul(k).into_affine())
+}
+
+pub fn g2() -> impl Strategy<Value = G2Affine> {
+    scalar().prop_map(|k| G2Projective::generator().mul(k).into_affine())
+}
+
+/// Messages `g1^m` with a small exponent, like the puzzle's message space.
+pub fn message() -> impl Strategy<Value = Message> {
+    any::<u64>().prop_map(|m| Message(G1Projective::generator().mul(Fr::from(m)).into_affine()))
+}
+
+pub fn sender() -> impl Strategy<Value = Sender> {
+    scalar().prop_map(Sender::new)
+}
+
+pub fn receiver() -> impl Strategy<Value = Receiver> {
+    g1().prop_map(Receiver::new)
+}
+
+/// Arbitrary, not necessarily well-formed, ciphertexts.
+pub fn elgamal() -> impl Strategy<Value = ElGamal> {
+    (g1(), g1()).prop_map(|(c_1, c_2)| ElGamal(c_1, c_2))
+}
+
+/// Signatures that are valid G2 points but not tags of anything.
+pub fn signature() -> impl Strategy<Value = G2Affine> {
+    g2()
+}
+
+/// Honestly generated blobs: encrypted, authenticated and passing the audit.
+pub fn blob() -> impl Strategy<Value = Blob> {
+    (sender(), receiver(), message()).prop_map(|(sender, receiver, m)| {
+        let c = sender.send(m, &receiver);
+        let s = sender.authenticate(&c, &[]);
+        Blob { sender_pk: sender.pk, c, s, rec_pk: receiver.pk() }
+    })
+}
+
+#[cfg(all(test, feature = "proptest"))]
+mod tests {
+    use super::*;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    use crate::{
+        bls,
+        keys::{self, KeyFormat},
+        Auditor,
+    };
+
+    const FORMATS: [KeyFormat; 3] = [KeyFormat::Raw, KeyFormat::Hex, KeyFormat::Json];
+
+    proptest! {
+        #[test]
+        fn decrypt_inverts_encrypt(sender in sender(), rec_sk in scalar(), m in message()) {
+            let receiver = Receiver::new(G1Projective::generator().mul(rec_sk).into_affine());
+            let c = sender.send(m, &receiver);
+            prop_assert!(G1Projective::from(c.1) - c.0.mul(rec_sk) == G1Projective::from(m.0));
+        }
+
+        #[test]
+        fn honest_blobs_verify(blob in blob()) {
+            prop_assert!(Auditor::check_auth(blob.sender_pk, &blob.c, blob.s, &[]));
+            prop_assert!(!Auditor::check_auth(blob.sender_pk, &blob.c, blob.s, b"other aad"));
+        }
+
+        #[test]
+        fn unrelated_signatures_fail(blob in blob(), s in signature()) {
+            prop_assume!(s != blob.s);
+            prop_assert!(!Auditor::check_auth(blob.sender_pk, &blob.c, s, &[]));
+        }
+
+        #[test]
+        fn bls_verifies_own_signatures(sk in scalar(), msg in any::<Vec<u8>>()) {
+            let sig = bls::sign(&sk, b"chaos-theory-test-v1", &msg);
+            prop_assert!(bls::verify(&bls::public_key(&sk), b"chaos-theory-test-v1", &msg, &sig));
+            prop_assert!(!bls::verify(&bls::public_key(&sk), b"chaos-theory-other-v1", &msg, &sig));
+        }
+
+        #[test]
+        fn blobs_round_trip(blob in blob()) {
+            let mut compressed = Vec::new();
+            blob.serialize_compressed(&mut compressed).unwrap();
+            prop_assert!(Blob::deserialize_compressed(compressed.as_slice()).unwrap() == blob);
+            let mut uncompressed = Vec::new();
+            blob.serialize_uncompressed(&mut uncompressed).unwrap();
+            prop_assert!(Blob::deserialize_uncompressed(uncompressed.as_slice()).unwrap() == blob);
+        }
+
+        #[test]
+        fn keys_round_trip(sk in scalar()) {
+            let pk = bls::public_key(&sk);
+            for format in FORMATS {
+                prop_assert_eq!(keys::decode_sk(&keys::encode_sk(&sk, format)).unwrap(), sk);
+                prop_assert_eq!(keys::decode_pk(&keys::encode_pk(&pk, format)).unwrap(), pk);
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Streaming verification of blob archives in bounded memory.
+//!
+//! Blobs are pulled from the input in chunks. Each chunk is checked with one
+//! multi-pairing over a random linear combination of the individual checks:
+//!
+//! `e(-g1, sum r_i s_i) * prod e(r_i pk_i, H(c_i)) == 1`
+//!
+//! which holds for honest tags and fails except with probability ~2^-128 if
+//! any tag is wrong. Only chunks that fail are re-checked blob by blob.
+
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use ark_bls12_381::{Fr, G1Projective, G2Projective};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::Zero;
+use ark_std::rand::Rng;
+use core::ops::Mul;
+
+use crate::{pairing_utils::pairing_product_is_one, Auditor, Blob};
+
+pub const DEFAULT_CHUNK_SIZE: usize = 256;
+
+impl Auditor {
+    /// Verdicts for `blobs`, in order, using one batched multi-pairing when
+    /// all tags are valid.
+    pub fn verify_batch<R: Rng>(&self, blobs: &[Blob], aad: &[u8], rng: &mut R) -> Vec<bool> {
+        if blobs.is_empty() {
+            return Vec::new();
+        }
+        let mut pks = Vec::with_capacity(blobs.len());
+        let mut hashes = Vec::with_capacity(blobs.len() + 1);
+        let mut s_sum = G2Projective::zero();
+        for blob in blobs {
+            let r = Fr::from(rng.gen::<u128>());
+            pks.push(blob.sender_pk.mul(r));
+            hashes.push(self.config().hash_to_curve(&blob.c, aad));
+            s_sum += blob.s.mul(r);
+        }
+        pks.push(-G1Projective::generator());
+        hashes.push(s_sum.into_affine());
+
+        let strict_ok = blobs.iter().all(|blob| self.strict_inputs_ok(&blob.sender_pk, &blob.s));
+        if strict_ok && pairing_product_is_one(G1Projective::normalize_batch(&pks), hashes) {
+            return vec![true; blobs.len()];
+        }
+        blobs.iter().map(|blob| self.verify(blob.sender_pk, &blob.c, blob.s, aad)).collect()
+    }
+
+    /// Verifies `blobs` lazily, `chunk_size` at a time, yielding each blob with
+    /// its verdict. At most one chunk is held in memory.
+    pub fn verify_stream<'a, I, R>(
+        &'a self,
+        blobs: I,
+        aad: &'a [u8],
+        chunk_size: usize,
+        rng: R,
+    ) -> VerifyStream<'a, I::IntoIter, R>
+    where
+        I: IntoIterator<Item = Blob>,
+        R: Rng,
+    {
+        VerifyStream {
+            auditor: self,
+            blobs: blobs.into_iter(),
+            aad,
+            chunk_size: chunk_size.max(1),
+            rng,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+pub struct VerifyStream<'a, I, R> {
+    auditor: &'a Auditor,
+    blobs: I,
+    aad: &'a [u8],
+    chunk_size: usize,
+    rng: R,
+    pending: VecDeque<(Blob, bool)>,
+}
+
+impl<I: Iterator<Item = Blob>, R: Rng> Iterator for VerifyStream<'_, I, R> {
+    type Item = (Blob, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() {
+            let chunk: Vec<Blob> = self.blobs.by_ref().take(self.chunk_size).collect();
+            let verdicts = self.auditor.verify_batch(&chunk, self.aad, &mut self.rng);
+            self.pending.extend(chunk.into_iter().zip(verdicts));
+        }
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::G2Affine;
+
+    use crate::{bls, strict::StrictVerification, tamper::off_subgroup_g2, Message, Receiver, Sender};
+
+    const AAD: &[u8] = b"stream";
+
+    // `n` valid blobs from different senders to one receiver.
+    fn blobs<R: Rng>(n: usize, rng: &mut R) -> Vec<Blob> {
+        let receiver = Receiver::new(bls::public_key(&Fr::rand(rng)));
+        (0..n)
+            .map(|_| {
+                let sender = Sender::new(Fr::rand(rng));
+                let c = sender.send(Message(bls::public_key(&Fr::rand(rng))), &receiver);
+                let s = sender.authenticate(&c, AAD);
+                Blob { sender_pk: sender.pk, c, s, rec_pk: receiver.pk() }
+            })
+            .collect()
+    }
+
+    fn tamper(blob: &mut Blob) {
+        blob.s = (blob.s + G2Affine::generator()).into_affine();
+    }
+
+    #[test]
+    fn valid_chunks_pass() {
+        let rng = &mut ark_std::test_rng();
+        let blobs = blobs(5, rng);
+        let auditor = Auditor::default();
+        assert_eq!(auditor.verify_batch(&blobs, AAD, rng), vec![true; 5]);
+        assert_eq!(auditor.verify_batch(&[], AAD, rng), Vec::<bool>::new());
+        assert_eq!(auditor.verify_batch(&blobs, b"other", rng), vec![false; 5]);
+    }
+
+    #[test]
+    fn tampered_tag_is_singled_out() {
+        let rng = &mut ark_std::test_rng();
+        let auditor = Auditor::default();
+        for i in 0..4 {
+            let mut blobs = blobs(4, rng);
+            tamper(&mut blobs[i]);
+            let verdicts = auditor.verify_batch(&blobs, AAD, rng);
+            assert_eq!(verdicts, (0..4).map(|j| j != i).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn streams_keep_order_across_chunk_sizes() {
+        let rng = &mut ark_std::test_rng();
+        let mut blobs = blobs(7, rng);
+        tamper(&mut blobs[2]);
+        tamper(&mut blobs[6]);
+        let expected: Vec<bool> = (0..7).map(|i| i != 2 && i != 6).collect();
+        let auditor = Auditor::default();
+
+        // 0 is read as 1, and 3 leaves a last chunk of 1
+        for chunk_size in [0, 1, 2, 3, 7, 8] {
+            let verified: Vec<(Blob, bool)> =
+                auditor.verify_stream(blobs.clone(), AAD, chunk_size, ark_std::test_rng()).collect();
+            assert!(verified.iter().map(|(blob, _)| blob).eq(blobs.iter()));
+            assert_eq!(verified.iter().map(|&(_, ok)| ok).collect::<Vec<_>>(), expected);
+        }
+        assert_eq!(auditor.verify_stream(Vec::new(), AAD, 4, ark_std::test_rng()).count(), 0);
+    }
+
+    #[test]
+    fn off_subgroup_tags_are_not_batched_in() {
+        let rng = &mut ark_std::test_rng();
+        let mut blobs = blobs(3, rng);
+        // a valid tag shifted by a point outside the prime-order subgroup
+        blobs[1].s = (blobs[1].s + off_subgroup_g2()).into_affine();
+
+        let strict = Auditor::default().with_strict_verification(StrictVerification::On);
+        assert_eq!(strict.verify_batch(&blobs, AAD, rng), vec![true, false, true]);
+        // without strict checks, the batch agrees with blob-by-blob pairing
+        let auditor = Auditor::default();
+        let single: Vec<bool> = blobs.iter().map(|b| auditor.verify(b.sender_pk, &b.c, b.s, AAD)).collect();
+        assert_eq!(auditor.verify_batch(&blobs, AAD, rng), single);
+        blobs[1].s = off_subgroup_g2();
+        assert_eq!(auditor.verify_batch(&blobs, AAD, rng), vec![true, false, true]);
+    }
+}
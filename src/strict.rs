@@ -0,0 +1,49 @@
+//! Strict verification, following the validation steps of the IETF BLS
+//! signature draft (KeyValidate and signature subgroup checks).
+//!
+//! By default the auditor pairs whatever points it is given, like the
+//! original puzzle. With `StrictVerification::On` it also rejects the identity
+//! as public key or tag, points outside the prime-order subgroups, and, in
+//! `verify_encoded`, blob encodings that don't re-encode to the same bytes.
+
+use ark_bls12_381::{G1Affine, G2Affine};
+
+use crate::{point_audit::PointAudit, Auditor, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrictVerification {
+    #[default]
+    Off,
+    On,
+}
+
+impl Auditor {
+    pub fn with_strict_verification(mut self, strict: StrictVerification) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn strict_verification(&self) -> StrictVerification {
+        self.strict
+    }
+
+    // whether the key and tag pass the strict checks; always true when off
+    pub(crate) fn strict_inputs_ok(&self, sender_pk: &G1Affine, s: &G2Affine) -> bool {
+        if self.strict == StrictVerification::Off {
+            return true;
+        }
+        let (pk, s) = (PointAudit::analyze(sender_pk), PointAudit::analyze(s));
+        !pk.is_identity && pk.is_valid() && !s.is_identity && s.is_valid()
+    }
+
+    /// Parses a blob encoded under this auditor's configuration and verifies
+    /// it under `aad`. In strict mode, encodings other than the canonical one
+    /// fail with `InvalidEncoding`.
+    pub fn verify_encoded(&self, data: &[u8], aad: &[u8]) -> Result<bool, Error> {
+        let blob = self.config().deserialize_blob(data)?;
+        if self.strict == StrictVerification::On && self.config().serialize_blob(&blob) != data {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(self.verify(blob.sender_pk, &blob.c, blob.s, aad))
+    }
+}
@@ -0,0 +1,79 @@
+//! Signed solution submissions for leaderboards.
+//!
+//! A solver signs their answer together with the content hash of the blob it
+//! answers, under their own BLS key. The leaderboard server parses the body
+//! with `Submission::from_bytes` and checks it with `verify_submission`.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{bls, Blob, Error};
+
+pub const SUBMISSION_DST: &[u8] = b"chaos-theory-submission-v1";
+
+const STATEMENT_LEN: usize = 16 + 32;
+const PK_LEN: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Submission {
+    pub answer: u128,
+    pub blob_hash: [u8; 32],
+    pub solver_pk: G1Affine,
+    pub signature: G2Affine,
+}
+
+fn statement(answer: u128, blob_hash: &[u8; 32]) -> [u8; STATEMENT_LEN] {
+    let mut out = [0u8; STATEMENT_LEN];
+    out[..16].copy_from_slice(&answer.to_be_bytes());
+    out[16..].copy_from_slice(blob_hash);
+    out
+}
+
+impl Submission {
+    pub fn new(solver_sk: &Fr, answer: u128, blob: &Blob) -> Self {
+        let blob_hash = blob.content_hash();
+        Submission {
+            answer,
+            blob_hash,
+            solver_pk: bls::public_key(solver_sk),
+            signature: bls::sign(solver_sk, SUBMISSION_DST, &statement(answer, &blob_hash)),
+        }
+    }
+
+    /// Answer (16 bytes, big-endian), blob hash, then the compressed solver
+    /// key and signature.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = statement(self.answer, &self.blob_hash).to_vec();
+        self.solver_pk.serialize_compressed(&mut out).unwrap();
+        self.signature.serialize_compressed(&mut out).unwrap();
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < STATEMENT_LEN + PK_LEN {
+            return Err(Error::InvalidEncoding);
+        }
+        let (stmt, rest) = bytes.split_at(STATEMENT_LEN);
+        let (pk, signature) = rest.split_at(PK_LEN);
+        Ok(Submission {
+            answer: u128::from_be_bytes(stmt[..16].try_into().unwrap()),
+            blob_hash: stmt[16..].try_into().unwrap(),
+            solver_pk: G1Affine::deserialize_compressed(pk).map_err(|_| Error::InvalidEncoding)?,
+            signature: G2Affine::deserialize_compressed(signature).map_err(|_| Error::InvalidEncoding)?,
+        })
+    }
+}
+
+/// Server-side check: the signature is valid for the submission's solver key
+/// and the submission is about `blob`. Whether the answer is correct is up to
+/// the caller.
+pub fn verify_submission(submission: &Submission, blob: &Blob) -> bool {
+    submission.blob_hash == blob.content_hash()
+        && bls::verify(
+            &submission.solver_pk,
+            SUBMISSION_DST,
+            &statement(submission.answer, &submission.blob_hash),
+            &submission.signature,
+        )
+}
@@ -0,0 +1,242 @@
+//! The scheme's equations as expression trees, for teaching.
+//!
+//! An `Expr` is built from named variables (`sk_s`, `sk_r`, `m`, `H(c)`, ...)
+//! with scalar multiplication, addition, subtraction and the pairing. It
+//! prints as written on a whiteboard, prints again with the variables bound
+//! in an `Env` replaced by their values, and evaluates once every variable is
+//! bound. `protocol_relations` lists the equations of the scheme and of the
+//! attack; `public_env` binds what anyone holding a blob knows.
+
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    Group,
+};
+use core::fmt;
+
+use crate::{display::short_hex, Blob, Message, SchemeConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Scalar(Fr),
+    G1(G1Projective),
+    G2(G2Projective),
+    Gt(PairingOutput<Bls12_381>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Scalar(x) => write!(f, "{}", short_hex(x)),
+            Value::G1(p) => write!(f, "{}", short_hex(p)),
+            Value::G2(p) => write!(f, "{}", short_hex(p)),
+            Value::Gt(x) => write!(f, "{}", short_hex(x)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Var(&'static str),
+    /// A scalar times a scalar, a point or a GT element.
+    Mul(Box<Expr>, Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    /// `e(G1, G2)`.
+    Pairing(Box<Expr>, Box<Expr>),
+}
+
+pub fn var(name: &'static str) -> Expr {
+    Expr::Var(name)
+}
+
+pub fn mul(a: Expr, b: Expr) -> Expr {
+    Expr::Mul(Box::new(a), Box::new(b))
+}
+
+pub fn add(a: Expr, b: Expr) -> Expr {
+    Expr::Add(Box::new(a), Box::new(b))
+}
+
+pub fn sub(a: Expr, b: Expr) -> Expr {
+    Expr::Sub(Box::new(a), Box::new(b))
+}
+
+pub fn e(a: Expr, b: Expr) -> Expr {
+    Expr::Pairing(Box::new(a), Box::new(b))
+}
+
+/// Values bound to variable names.
+#[derive(Debug, Clone, Default)]
+pub struct Env {
+    vars: Vec<(&'static str, Value)>,
+}
+
+impl Env {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds `name`, replacing an earlier binding.
+    pub fn set(&mut self, name: &'static str, value: Value) {
+        self.vars.retain(|(n, _)| *n != name);
+        self.vars.push((name, value));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.vars.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)
+    }
+}
+
+impl Expr {
+    /// The value of the expression, or `None` if a variable is unbound or
+    /// the operands don't fit the operation (e.g. adding G1 to G2).
+    pub fn eval(&self, env: &Env) -> Option<Value> {
+        use Value::*;
+        Some(match self {
+            Expr::Var(name) => env.get(name)?,
+            Expr::Mul(a, b) => match (a.eval(env)?, b.eval(env)?) {
+                (Scalar(x), Scalar(y)) => Scalar(x * y),
+                (Scalar(x), G1(p)) => G1(p * x),
+                (Scalar(x), G2(p)) => G2(p * x),
+                (Scalar(x), Gt(p)) => Gt(p * x),
+                _ => return None,
+            },
+            Expr::Add(a, b) => match (a.eval(env)?, b.eval(env)?) {
+                (Scalar(x), Scalar(y)) => Scalar(x + y),
+                (G1(p), G1(q)) => G1(p + q),
+                (G2(p), G2(q)) => G2(p + q),
+                (Gt(p), Gt(q)) => Gt(p + q),
+                _ => return None,
+            },
+            Expr::Sub(a, b) => match (a.eval(env)?, b.eval(env)?) {
+                (Scalar(x), Scalar(y)) => Scalar(x - y),
+                (G1(p), G1(q)) => G1(p - q),
+                (G2(p), G2(q)) => G2(p - q),
+                (Gt(p), Gt(q)) => Gt(p - q),
+                _ => return None,
+            },
+            Expr::Pairing(a, b) => match (a.eval(env)?, b.eval(env)?) {
+                (G1(p), G2(q)) => Gt(Bls12_381::pairing(p, q)),
+                _ => return None,
+            },
+        })
+    }
+
+    /// Unbound variables, in order of appearance, without repeats.
+    pub fn unbound(&self, env: &Env) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        self.collect_unbound(env, &mut names);
+        names
+    }
+
+    fn collect_unbound(&self, env: &Env, names: &mut Vec<&'static str>) {
+        match self {
+            Expr::Var(name) => {
+                if env.get(name).is_none() && !names.contains(name) {
+                    names.push(name);
+                }
+            }
+            Expr::Mul(a, b) | Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Pairing(a, b) => {
+                a.collect_unbound(env, names);
+                b.collect_unbound(env, names);
+            }
+        }
+    }
+
+    /// The expression with bound variables replaced by their values.
+    pub fn substituted(&self, env: &Env) -> String {
+        self.render(&|name| env.get(name).map_or(String::from(name), |v| format!("{v}")))
+    }
+
+    fn render(&self, leaf: &dyn Fn(&'static str) -> String) -> String {
+        // sums need parentheses as operands of products and on the right of `-`
+        let operand = |x: &Expr| match x {
+            Expr::Add(..) | Expr::Sub(..) => format!("({})", x.render(leaf)),
+            _ => x.render(leaf),
+        };
+        match self {
+            Expr::Var(name) => leaf(name),
+            Expr::Mul(a, b) => format!("{} * {}", operand(a), operand(b)),
+            Expr::Add(a, b) => format!("{} + {}", a.render(leaf), b.render(leaf)),
+            Expr::Sub(a, b) => format!("{} - {}", a.render(leaf), operand(b)),
+            Expr::Pairing(a, b) => format!("e({}, {})", a.render(leaf), b.render(leaf)),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(&|name| name.into()))
+    }
+}
+
+/// An equation between two expressions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relation {
+    pub name: &'static str,
+    pub lhs: Expr,
+    pub rhs: Expr,
+}
+
+impl Relation {
+    /// Whether both sides evaluate to the same value, or `None` if a variable
+    /// is unbound.
+    pub fn check(&self, env: &Env) -> Option<bool> {
+        Some(self.lhs.eval(env)? == self.rhs.eval(env)?)
+    }
+
+    pub fn unbound(&self, env: &Env) -> Vec<&'static str> {
+        let mut names = self.lhs.unbound(env);
+        for name in self.rhs.unbound(env) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.lhs, self.rhs)
+    }
+}
+
+/// The equations of key generation, encryption, tagging, verification,
+/// decryption and the pairing-cancellation attack, with `m` the message point.
+pub fn protocol_relations() -> Vec<Relation> {
+    let relation = |name, lhs, rhs| Relation { name, lhs, rhs };
+    vec![
+        relation("sender key", var("sender_pk"), mul(var("sk_s"), var("g1"))),
+        relation("receiver key", var("rec_pk"), mul(var("sk_r"), var("g1"))),
+        relation("ciphertext", var("c.0"), var("sender_pk")),
+        relation("ciphertext", var("c.1"), add(var("m"), mul(var("sk_s"), var("rec_pk")))),
+        relation("tag", var("s"), mul(var("sk_s"), var("H(c)"))),
+        relation("verification", e(var("g1"), var("s")), e(var("sender_pk"), var("H(c)"))),
+        relation("decryption", sub(var("c.1"), mul(var("sk_r"), var("c.0"))), var("m")),
+        relation(
+            "attack",
+            sub(e(var("c.1"), var("H(c)")), e(var("rec_pk"), var("s"))),
+            e(var("m"), var("H(c)")),
+        ),
+    ]
+}
+
+/// Binds `g1` and the public values of `blob`, with `H(c)` hashed under
+/// `config` and `aad`, and `m` if the plaintext is known.
+pub fn public_env(blob: &Blob, config: &SchemeConfig, aad: &[u8], m: Option<Message>) -> Env {
+    let mut env = Env::new();
+    env.set("g1", Value::G1(G1Projective::generator()));
+    env.set("sender_pk", Value::G1(blob.sender_pk.into()));
+    env.set("rec_pk", Value::G1(blob.rec_pk.into()));
+    env.set("c.0", Value::G1(blob.c.0.into()));
+    env.set("c.1", Value::G1(blob.c.1.into()));
+    env.set("s", Value::G2(blob.s.into()));
+    env.set("H(c)", Value::G2(config.hash_to_curve(&blob.c, aad).into()));
+    if let Some(m) = m {
+        env.set("m", Value::G1(m.0.into()));
+    }
+    env
+}
@@ -0,0 +1,199 @@
+//! Vote tallying on the additive homomorphism of the scheme's ElGamal.
+//!
+//! A vote for candidate `j` is the exponent `B^j`, with `B` one more than the
+//! number of voters, encrypted by the voter to the tally key as any other
+//! message. Adding the ciphertexts componentwise adds the exponents, so the
+//! sum encrypts `sum(count_j * B^j)`, whose base-`B` digits are the counts.
+//!
+//! The tally key is shared among trustees with `recovery::split`. Each
+//! trustee publishes `share * c.0` for the summed ciphertext, any threshold
+//! of them combine into `sk * c.0` and thus `total * g1`, and the total is
+//! recovered with the bounded discrete-log search of `dlog`.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{G1Affine, G1Projective};
+use ark_ec::{CurveGroup, Group, VariableBaseMSM};
+use ark_ff::Zero;
+
+use crate::{
+    dlog,
+    recovery::{lagrange_at_zero, Share},
+    ElGamal, Error,
+};
+
+/// The largest `Election::bound` accepted: `tally` searches the total
+/// linearly, which takes minutes at 2^32.
+pub const MAX_TALLY_BOUND: u128 = 1 << 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Election {
+    pub candidates: usize,
+    pub max_voters: u64,
+}
+
+/// A trustee's contribution to decrypting the summed ciphertext.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialDecryption {
+    pub threshold: u64,
+    pub index: u64,
+    /// `share * c.0`.
+    pub value: G1Affine,
+}
+
+impl Election {
+    /// Fails with `InvalidConfig` if the encoded totals can reach
+    /// `MAX_TALLY_BOUND`.
+    pub fn new(candidates: usize, max_voters: u64) -> Result<Self, Error> {
+        let election = Election { candidates, max_voters };
+        let bound = u32::try_from(candidates).ok().and_then(|c| election.base().checked_pow(c));
+        if candidates == 0 || !bound.is_some_and(|b| b <= MAX_TALLY_BOUND) {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(election)
+    }
+
+    fn base(&self) -> u128 {
+        self.max_voters as u128 + 1
+    }
+
+    /// The exponent a vote for `candidate` is encrypted as. Fails with
+    /// `InvalidMsg` for an unknown candidate.
+    pub fn encode(&self, candidate: usize) -> Result<u128, Error> {
+        if candidate >= self.candidates {
+            return Err(Error::InvalidMsg);
+        }
+        Ok(self.base().pow(candidate as u32))
+    }
+
+    /// Exclusive upper bound of the summed exponent.
+    pub fn bound(&self) -> u128 {
+        self.base().pow(self.candidates as u32)
+    }
+
+    /// The counts of every candidate from the summed exponent.
+    pub fn decode(&self, mut total: u128) -> Vec<u64> {
+        (0..self.candidates)
+            .map(|_| {
+                let count = total % self.base();
+                total /= self.base();
+                count as u64
+            })
+            .collect()
+    }
+}
+
+/// The componentwise sum of `ballots`, encrypting the sum of their exponents.
+pub fn sum(ballots: &[ElGamal]) -> ElGamal {
+    let (c0, c1) = ballots.iter().fold((G1Projective::zero(), G1Projective::zero()), |(a, b), c| (a + c.0, b + c.1));
+    ElGamal(c0.into_affine(), c1.into_affine())
+}
+
+pub fn partial_decrypt(share: &Share, total: &ElGamal) -> PartialDecryption {
+    PartialDecryption { threshold: share.threshold, index: share.index, value: (total.0 * share.value).into_affine() }
+}
+
+/// Combines at least a threshold of partial decryptions of `total` and
+/// recovers the counts. Fails with `InvalidShares` if there are too few, or
+/// `InvalidMsg` if the total is out of the election's range, which means
+/// some partial decryption or ballot was wrong.
+pub fn tally(election: &Election, total: &ElGamal, partials: &[PartialDecryption]) -> Result<Vec<u64>, Error> {
+    let threshold = partials.first().ok_or(Error::InvalidShares)?.threshold as usize;
+    let mut used: Vec<&PartialDecryption> = Vec::with_capacity(threshold);
+    for p in partials {
+        if p.threshold as usize != threshold || p.index == 0 {
+            return Err(Error::InvalidShares);
+        }
+        if !used.iter().any(|u| u.index == p.index) {
+            used.push(p);
+        }
+    }
+    if used.len() < threshold {
+        return Err(Error::InvalidShares);
+    }
+    used.truncate(threshold);
+
+    let indices: Vec<u64> = used.iter().map(|p| p.index).collect();
+    let values: Vec<G1Affine> = used.iter().map(|p| p.value).collect();
+    let blinding = G1Projective::msm(&values, &lagrange_at_zero(&indices)).unwrap();
+    let message = G1Projective::from(total.1) - blinding;
+
+    let exponent =
+        dlog::brute_force(&G1Projective::generator(), &message, 0..election.bound()).ok_or(Error::InvalidMsg)?;
+    Ok(election.decode(exponent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use ark_ff::UniformRand;
+
+    use crate::{bls, recovery, Message, Receiver, Sender};
+
+    // An election with 3 candidates and 5 trustees, threshold 3, where voter
+    // `i` votes for candidate `i % 3`; returns the summed ballots, every
+    // trustee's partial decryption and the expected counts.
+    fn election(voters: u64) -> (Election, ElGamal, Vec<PartialDecryption>, Vec<u64>) {
+        let rng = &mut ark_std::test_rng();
+        let election = Election::new(3, voters).unwrap();
+        let sk = Fr::rand(rng);
+        let authority = Receiver::new(bls::public_key(&sk));
+        let mut expected = vec![0; 3];
+        let ballots: Vec<ElGamal> = (0..voters as usize)
+            .map(|i| {
+                expected[i % 3] += 1;
+                let vote = Message::from_exponent(election.encode(i % 3).unwrap());
+                Sender::new(Fr::rand(rng)).send(vote, &authority)
+            })
+            .collect();
+        let total = sum(&ballots);
+        let shares = recovery::split(&sk, 3, 5, rng).unwrap();
+        let partials = shares.iter().map(|share| partial_decrypt(share, &total)).collect();
+        (election, total, partials, expected)
+    }
+
+    #[test]
+    fn any_threshold_of_trustees_tallies() {
+        let (election, total, partials, expected) = election(10);
+        assert_eq!(tally(&election, &total, &partials[..3]).unwrap(), expected);
+        assert_eq!(tally(&election, &total, &partials[2..]).unwrap(), expected);
+        assert_eq!(tally(&election, &total, &partials).unwrap(), expected);
+    }
+
+    #[test]
+    fn below_threshold_is_rejected() {
+        let (election, total, partials, _) = election(10);
+        assert!(matches!(tally(&election, &total, &partials[..2]), Err(Error::InvalidShares)));
+        let repeated = [partials[0], partials[1], partials[1]];
+        assert!(matches!(tally(&election, &total, &repeated), Err(Error::InvalidShares)));
+        assert!(matches!(tally(&election, &total, &[]), Err(Error::InvalidShares)));
+    }
+
+    #[test]
+    fn tampered_share_is_detected() {
+        let (election, total, mut partials, _) = election(10);
+        partials[1].value = (G1Projective::from(partials[1].value) + G1Projective::generator()).into_affine();
+        assert!(matches!(tally(&election, &total, &partials[..3]), Err(Error::InvalidMsg)));
+    }
+
+    #[test]
+    fn votes_round_trip_through_the_encoding() {
+        let election = Election::new(4, 9).unwrap();
+        let total: u128 = [0, 2, 2, 3].iter().map(|&c| election.encode(c).unwrap()).sum();
+        assert_eq!(election.decode(total), vec![1, 0, 2, 1]);
+        assert!(matches!(election.encode(4), Err(Error::InvalidMsg)));
+        assert!(matches!(Election::new(200, u64::MAX), Err(Error::InvalidConfig)));
+    }
+
+    #[test]
+    fn unsearchable_elections_are_rejected() {
+        // 1001^5 ~ 10^15 totals would take tally years to search
+        assert!(matches!(Election::new(5, 1000), Err(Error::InvalidConfig)));
+        assert!(matches!(Election::new(0, 10), Err(Error::InvalidConfig)));
+        assert!(matches!(Election::new(usize::MAX, 1), Err(Error::InvalidConfig)));
+        // 2^32 itself is the largest bound accepted
+        assert_eq!(Election::new(2, u16::MAX as u64).unwrap().bound(), MAX_TALLY_BOUND);
+        assert!(matches!(Election::new(2, 1 << 16), Err(Error::InvalidConfig)));
+        assert!(matches!(Election::new(1, u64::MAX), Err(Error::InvalidConfig)));
+    }
+}
@@ -0,0 +1,134 @@
+//! Systematically corrupted blobs with the verdict an auditor must reach on
+//! each, for negative-testing other implementations.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::One;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{Auditor, Blob, ElGamal};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Parses and the tag verifies.
+    Valid,
+    /// Parses but the tag does not verify.
+    Invalid,
+    /// Rejected while parsing (bad length, off-curve or off-subgroup point).
+    Malformed,
+}
+
+/// Parses `data` as an uncompressed blob with full point validation and
+/// audits it with an empty `aad`.
+pub fn audit_bytes(data: &[u8]) -> Verdict {
+    match Blob::deserialize_uncompressed(data) {
+        Ok(blob) if Auditor::check_auth(blob.sender_pk, &blob.c, blob.s, &[]) => Verdict::Valid,
+        Ok(_) => Verdict::Invalid,
+        Err(_) => Verdict::Malformed,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mutation {
+    /// `c.0` and `c.1` swapped, changing the tagged hash.
+    SwappedCiphertext,
+    /// Lowest bit of the signature encoding flipped, knocking it off the curve.
+    FlippedSignatureBit,
+    /// Signature replaced by a point on the curve but outside the G2 subgroup.
+    WrongSubgroupSignature,
+    /// Signature replaced by the point at infinity.
+    IdentitySignature,
+    /// Sender key replaced by a different valid key.
+    WrongSender,
+    /// Receiver key replaced by a different valid key. The tag doesn't cover
+    /// `rec_pk`, so the auditor still accepts it.
+    MismatchedReceiver,
+    /// Last byte dropped.
+    Truncated,
+}
+
+pub const ALL_MUTATIONS: [Mutation; 7] = [
+    Mutation::SwappedCiphertext,
+    Mutation::FlippedSignatureBit,
+    Mutation::WrongSubgroupSignature,
+    Mutation::IdentitySignature,
+    Mutation::WrongSender,
+    Mutation::MismatchedReceiver,
+    Mutation::Truncated,
+];
+
+fn to_bytes(blob: &Blob) -> Vec<u8> {
+    let mut data = Vec::new();
+    blob.serialize_uncompressed(&mut data).unwrap();
+    data
+}
+
+fn next_key(pk: &G1Affine) -> G1Affine {
+    (*pk + G1Affine::generator()).into_affine()
+}
+
+/// A point on the G2 curve outside the prime-order subgroup: the first valid
+/// x-coordinate `1 + k` with `k = 0, 1, ...` gives one with overwhelming
+/// probability given the size of the cofactor.
+pub fn off_subgroup_g2() -> G2Affine {
+    let mut x = Fq2::new(Fq::one(), Fq::one());
+    loop {
+        if let Some(p) = G2Affine::get_point_from_x_unchecked(x, false) {
+            if !p.is_in_correct_subgroup_assuming_on_curve() {
+                return p;
+            }
+        }
+        x += Fq2::one();
+    }
+}
+
+impl Mutation {
+    pub fn name(self) -> &'static str {
+        match self {
+            Mutation::SwappedCiphertext => "swapped-ciphertext",
+            Mutation::FlippedSignatureBit => "flipped-signature-bit",
+            Mutation::WrongSubgroupSignature => "wrong-subgroup-signature",
+            Mutation::IdentitySignature => "identity-signature",
+            Mutation::WrongSender => "wrong-sender",
+            Mutation::MismatchedReceiver => "mismatched-receiver",
+            Mutation::Truncated => "truncated",
+        }
+    }
+
+    pub fn expected_verdict(self) -> Verdict {
+        match self {
+            Mutation::SwappedCiphertext | Mutation::IdentitySignature | Mutation::WrongSender => Verdict::Invalid,
+            Mutation::FlippedSignatureBit | Mutation::WrongSubgroupSignature | Mutation::Truncated => {
+                Verdict::Malformed
+            }
+            Mutation::MismatchedReceiver => Verdict::Valid,
+        }
+    }
+
+    /// The uncompressed encoding of `blob` with this corruption applied.
+    /// `blob` itself is expected to be valid.
+    pub fn apply(self, blob: &Blob) -> Vec<u8> {
+        let mut blob = blob.clone();
+        match self {
+            Mutation::SwappedCiphertext => blob.c = ElGamal(blob.c.1, blob.c.0),
+            Mutation::WrongSubgroupSignature => blob.s = off_subgroup_g2(),
+            Mutation::IdentitySignature => blob.s = G2Affine::zero(),
+            Mutation::WrongSender => blob.sender_pk = next_key(&blob.sender_pk),
+            Mutation::MismatchedReceiver => blob.rec_pk = next_key(&blob.rec_pk),
+            Mutation::FlippedSignatureBit | Mutation::Truncated => {}
+        }
+        let mut data = to_bytes(&blob);
+        match self {
+            Mutation::FlippedSignatureBit => {
+                let end = blob.sender_pk.uncompressed_size() + blob.c.uncompressed_size() + blob.s.uncompressed_size();
+                data[end - 1] ^= 1;
+            }
+            Mutation::Truncated => {
+                data.pop();
+            }
+            _ => {}
+        }
+        data
+    }
+}
@@ -0,0 +1,125 @@
+//! In-memory end-to-end scenarios for integration tests of downstream
+//! consumers (auditors, indexers, decoders).
+//!
+//! A scenario is a sender, a receiver, a message space and a list of blobs
+//! encrypting random messages from it, some of them corrupted with a
+//! `tamper::Mutation`. Every blob carries the verdict a correct auditor must
+//! reach on it and, for the uncorrupted ones, the plaintext index the attack
+//! must recover.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::UniformRand;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::Rng;
+use core::ops::Mul;
+
+use crate::{
+    message_space::MessageSpace,
+    tamper::{Mutation, Verdict},
+    Blob, Error, Receiver, SchemeConfig, Sender,
+};
+
+#[derive(Debug, Clone)]
+pub struct ScenarioBlob {
+    /// Uncompressed encoding, corrupted if `mutation` is set.
+    pub data: Vec<u8>,
+    /// Index of the encrypted message in the scenario's message space.
+    pub message_index: usize,
+    pub mutation: Option<Mutation>,
+    /// Verdict of an auditor using the scenario's `config` and `aad`.
+    pub expected: Verdict,
+}
+
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub sender: Sender,
+    pub receiver: Receiver,
+    /// The receiver's secret key, for consumers that decrypt.
+    pub receiver_sk: Fr,
+    pub config: SchemeConfig,
+    pub aad: Vec<u8>,
+    pub space: MessageSpace,
+    pub blobs: Vec<ScenarioBlob>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioBuilder {
+    config: Option<SchemeConfig>,
+    aad: Vec<u8>,
+    space: Option<MessageSpace>,
+    blobs: usize,
+    injections: Vec<(usize, Mutation)>,
+}
+
+impl ScenarioBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scheme configuration used by the sender. Defaults to `SchemeConfig::default()`.
+    pub fn config(mut self, config: SchemeConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Application context the blobs are tagged under. Defaults to empty.
+    pub fn aad(mut self, aad: &[u8]) -> Self {
+        self.aad = aad.to_vec();
+        self
+    }
+
+    /// Defaults to `MessageSpace::puzzle()`.
+    pub fn message_space(mut self, space: MessageSpace) -> Self {
+        self.space = Some(space);
+        self
+    }
+
+    pub fn blobs(mut self, n: usize) -> Self {
+        self.blobs = n;
+        self
+    }
+
+    /// Corrupts blob `index` with `mutation`. The last injection for an index wins.
+    pub fn inject(mut self, index: usize, mutation: Mutation) -> Self {
+        self.injections.push((index, mutation));
+        self
+    }
+
+    /// Fails with `InvalidConfig` if the message space is empty or an
+    /// injection targets a blob that doesn't exist.
+    pub fn build<R: Rng>(self, rng: &mut R) -> Result<Scenario, Error> {
+        let space = self.space.unwrap_or_else(MessageSpace::puzzle);
+        if space.is_empty() || self.injections.iter().any(|&(i, _)| i >= self.blobs) {
+            return Err(Error::InvalidConfig);
+        }
+        let config = self.config.unwrap_or_default();
+
+        let sender = Sender::with_config(Fr::rand(rng), config.clone());
+        let receiver_sk = Fr::rand(rng);
+        let receiver = Receiver::new(G1Affine::generator().mul(receiver_sk).into_affine());
+
+        let blobs = (0..self.blobs)
+            .map(|i| {
+                let message_index = rng.gen_range(0..space.len());
+                let c = sender.send(space.messages()[message_index], &receiver);
+                let s = sender.authenticate(&c, &self.aad);
+                let blob = Blob { sender_pk: sender.pk, c, s, rec_pk: receiver.pk() };
+
+                let mutation = self.injections.iter().rev().find(|&&(j, _)| j == i).map(|&(_, m)| m);
+                let (data, expected) = match mutation {
+                    Some(m) => (m.apply(&blob), m.expected_verdict()),
+                    None => {
+                        let mut data = Vec::new();
+                        blob.serialize_uncompressed(&mut data).unwrap();
+                        (data, Verdict::Valid)
+                    }
+                };
+                ScenarioBlob { data, message_index, mutation, expected }
+            })
+            .collect();
+
+        Ok(Scenario { sender, receiver, receiver_sk, config, aad: self.aad, space, blobs })
+    }
+}
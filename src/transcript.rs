@@ -0,0 +1,134 @@
+//! Recorded protocol runs that can be replayed and re-verified.
+//!
+//! A `Transcript` is the sequence of everything the parties exchanged: key
+//! announcements, tagged blobs with their context, and the auditor's
+//! verdicts. `replay` re-verifies every blob and reports where the recorded
+//! verdicts disagree, for debugging interop partners and for deterministic
+//! regression runs.
+//!
+//! The file starts with `MAGIC`, followed by entries, each a tag byte and its
+//! payload: points compressed, integers little-endian, byte strings prefixed
+//! with their length as a `u64`.
+
+use alloc::vec::Vec;
+use ark_bls12_381::G1Affine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{Auditor, Blob, Error};
+
+pub const MAGIC: &[u8; 8] = b"CTTRANS1";
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum Entry {
+    SenderKey(G1Affine),
+    ReceiverKey(G1Affine),
+    /// A tagged blob and the `aad` it was tagged under.
+    Blob { blob: Blob, aad: Vec<u8> },
+    /// The auditor's verdict on the `index`-th blob of the transcript.
+    Verdict { index: u64, valid: bool },
+}
+
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Transcript {
+    pub entries: Vec<Entry>,
+}
+
+/// Outcome of `replay`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub blobs: usize,
+    pub verdicts: usize,
+    /// Indices of blobs whose recorded verdict differs from the replayed one,
+    /// or that a verdict refers to without existing.
+    pub mismatches: Vec<u64>,
+}
+
+impl ReplayReport {
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    pub fn blobs(&self) -> impl Iterator<Item = (&Blob, &[u8])> {
+        self.entries.iter().filter_map(|e| match e {
+            Entry::Blob { blob, aad } => Some((blob, aad.as_slice())),
+            _ => None,
+        })
+    }
+
+    /// Re-verifies every blob with `auditor` and compares with the recorded verdicts.
+    pub fn replay(&self, auditor: &Auditor) -> ReplayReport {
+        let verdicts: Vec<bool> =
+            self.blobs().map(|(blob, aad)| auditor.verify(blob.sender_pk, &blob.c, blob.s, aad)).collect();
+        let mut report = ReplayReport { blobs: verdicts.len(), ..ReplayReport::default() };
+        for entry in &self.entries {
+            if let Entry::Verdict { index, valid } = entry {
+                report.verdicts += 1;
+                if verdicts.get(*index as usize) != Some(valid) {
+                    report.mismatches.push(*index);
+                }
+            }
+        }
+        report
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        for entry in &self.entries {
+            match entry {
+                Entry::SenderKey(pk) => {
+                    out.push(0);
+                    pk.serialize_compressed(&mut out).unwrap();
+                }
+                Entry::ReceiverKey(pk) => {
+                    out.push(1);
+                    pk.serialize_compressed(&mut out).unwrap();
+                }
+                Entry::Blob { blob, aad } => {
+                    out.push(2);
+                    blob.serialize_compressed(&mut out).unwrap();
+                    aad.serialize_compressed(&mut out).unwrap();
+                }
+                Entry::Verdict { index, valid } => {
+                    out.push(3);
+                    out.extend(index.to_le_bytes());
+                    out.push(*valid as u8);
+                }
+            }
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut rest = bytes.strip_prefix(MAGIC.as_slice()).ok_or(Error::InvalidEncoding)?;
+        let invalid = |_| Error::InvalidEncoding;
+        let mut transcript = Transcript::new();
+        while let Some((&tag, tail)) = rest.split_first() {
+            rest = tail;
+            let entry = match tag {
+                0 => Entry::SenderKey(G1Affine::deserialize_compressed(&mut rest).map_err(invalid)?),
+                1 => Entry::ReceiverKey(G1Affine::deserialize_compressed(&mut rest).map_err(invalid)?),
+                2 => Entry::Blob {
+                    blob: Blob::deserialize_compressed(&mut rest).map_err(invalid)?,
+                    aad: Vec::deserialize_compressed(&mut rest).map_err(invalid)?,
+                },
+                3 => Entry::Verdict {
+                    index: u64::deserialize_compressed(&mut rest).map_err(invalid)?,
+                    valid: bool::deserialize_compressed(&mut rest).map_err(invalid)?,
+                },
+                _ => return Err(Error::InvalidEncoding),
+            };
+            transcript.record(entry);
+        }
+        Ok(transcript)
+    }
+}
@@ -0,0 +1,287 @@
+//! Append-only transparency log in the style of Certificate Transparency
+//! (RFC 9162).
+//!
+//! Entries are hashed into a Merkle tree. The log operator periodically
+//! signs the current tree head with its BLS key; anyone holding two signed
+//! heads can ask for a consistency proof showing the newer tree extends the
+//! older one, which is how gossiped heads are checked for split views. The
+//! binary keeps one over the audit log file, an entry per event line
+//! (`from_lines`), signed by `audit --sign-head` and `sign-head`.
+
+use alloc::vec::Vec;
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::{Digest, Sha256};
+
+use crate::{bls, Error};
+
+pub const TREE_HEAD_DST: &[u8] = b"chaos-theory-tree-head-v1";
+
+pub type Hash = [u8; 32];
+
+pub fn leaf_hash(data: &[u8]) -> Hash {
+    Sha256::new().chain_update([0u8]).chain_update(data).finalize().into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    Sha256::new().chain_update([1u8]).chain_update(left).chain_update(right).finalize().into()
+}
+
+// largest power of two strictly smaller than n, for n > 1
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k << 1 < n {
+        k <<= 1;
+    }
+    k
+}
+
+fn root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => Sha256::digest(b"").into(),
+        1 => leaves[0],
+        n => {
+            let k = split_point(n);
+            node_hash(&root(&leaves[..k]), &root(&leaves[k..]))
+        }
+    }
+}
+
+fn subproof(m: usize, leaves: &[Hash], complete: bool, proof: &mut Vec<Hash>) {
+    let n = leaves.len();
+    if m == n {
+        if !complete {
+            proof.push(root(leaves));
+        }
+        return;
+    }
+    let k = split_point(n);
+    if m <= k {
+        subproof(m, &leaves[..k], complete, proof);
+        proof.push(root(&leaves[k..]));
+    } else {
+        subproof(m - k, &leaves[k..], false, proof);
+        proof.push(root(&leaves[..k]));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeHead {
+    pub size: u64,
+    pub root: Hash,
+    pub timestamp: u64,
+}
+
+pub const TREE_HEAD_LEN: usize = 8 + 32 + 8;
+
+impl TreeHead {
+    pub fn to_bytes(&self) -> [u8; TREE_HEAD_LEN] {
+        let mut out = [0u8; TREE_HEAD_LEN];
+        out[..8].copy_from_slice(&self.size.to_be_bytes());
+        out[8..40].copy_from_slice(&self.root);
+        out[40..].copy_from_slice(&self.timestamp.to_be_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != TREE_HEAD_LEN {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(TreeHead {
+            size: u64::from_be_bytes(bytes[..8].try_into().unwrap()),
+            root: bytes[8..40].try_into().unwrap(),
+            timestamp: u64::from_be_bytes(bytes[40..].try_into().unwrap()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedTreeHead {
+    pub head: TreeHead,
+    pub signature: G2Affine,
+}
+
+impl SignedTreeHead {
+    pub fn verify(&self, log_pk: &G1Affine) -> bool {
+        bls::verify(log_pk, TREE_HEAD_DST, &self.head.to_bytes(), &self.signature)
+    }
+
+    /// The head followed by the compressed signature.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.head.to_bytes().to_vec();
+        self.signature.serialize_compressed(&mut out).unwrap();
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < TREE_HEAD_LEN {
+            return Err(Error::InvalidEncoding);
+        }
+        let (head, signature) = bytes.split_at(TREE_HEAD_LEN);
+        Ok(SignedTreeHead {
+            head: TreeHead::from_bytes(head)?,
+            signature: G2Affine::deserialize_compressed(signature).map_err(|_| Error::InvalidEncoding)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TransparencyLog {
+    leaves: Vec<Hash>,
+}
+
+impl TransparencyLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The log of the lines of `text`, e.g. a JSON-lines audit log, each
+    /// line an entry without its line break.
+    pub fn from_lines(text: &str) -> Self {
+        let mut log = Self::new();
+        for line in text.lines() {
+            log.append(line.as_bytes());
+        }
+        log
+    }
+
+    /// Appends an entry and returns its index.
+    pub fn append(&mut self, data: &[u8]) -> u64 {
+        self.leaves.push(leaf_hash(data));
+        self.leaves.len() as u64 - 1
+    }
+
+    pub fn size(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    pub fn root(&self) -> Hash {
+        root(&self.leaves)
+    }
+
+    pub fn sign_head(&self, log_sk: &Fr, timestamp: u64) -> SignedTreeHead {
+        let head = TreeHead { size: self.size(), root: self.root(), timestamp };
+        SignedTreeHead { head, signature: bls::sign(log_sk, TREE_HEAD_DST, &head.to_bytes()) }
+    }
+
+    /// Proof that the tree of the first `old_size` entries is a prefix of the
+    /// tree of the first `new_size` entries.
+    pub fn consistency_proof(&self, old_size: u64, new_size: u64) -> Result<Vec<Hash>, Error> {
+        if old_size > new_size || new_size > self.size() {
+            return Err(Error::InvalidProof);
+        }
+        let mut proof = Vec::new();
+        if old_size > 0 && old_size < new_size {
+            subproof(old_size as usize, &self.leaves[..new_size as usize], true, &mut proof);
+        }
+        Ok(proof)
+    }
+}
+
+/// Checks a consistency proof between two tree heads (RFC 9162, 2.1.4.2).
+pub fn verify_consistency(old: &TreeHead, new: &TreeHead, proof: &[Hash]) -> bool {
+    if old.size > new.size {
+        return false;
+    }
+    if old.size == new.size {
+        return proof.is_empty() && old.root == new.root;
+    }
+    if old.size == 0 {
+        return proof.is_empty();
+    }
+
+    let mut path = proof.iter();
+    let (mut fr, mut sr) = if old.size.is_power_of_two() {
+        (old.root, old.root)
+    } else {
+        match path.next() {
+            Some(first) => (*first, *first),
+            None => return false,
+        }
+    };
+
+    let (mut fn_, mut sn) = (old.size - 1, new.size - 1);
+    while fn_ & 1 == 1 {
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    for c in path {
+        if sn == 0 {
+            return false;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            fr = node_hash(c, &fr);
+            sr = node_hash(c, &sr);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            sr = node_hash(&sr, c);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    fr == old.root && sr == new.root && sn == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+
+    fn log(n: usize) -> TransparencyLog {
+        let mut log = TransparencyLog::new();
+        for i in 0..n {
+            log.append(format!("{{\"event\":\"audit\",\"n\":{i}}}").as_bytes());
+        }
+        log
+    }
+
+    #[test]
+    fn consistency_proofs_verify() {
+        let log = log(13);
+        for old in 0..=13 {
+            for new in old..=13 {
+                let heads = (log.prefix_head(old), log.prefix_head(new));
+                let proof = log.consistency_proof(old, new).unwrap();
+                assert!(verify_consistency(&heads.0, &heads.1, &proof), "{old} -> {new}");
+                if !proof.is_empty() {
+                    let mut tampered = proof.clone();
+                    tampered[0][0] ^= 1;
+                    assert!(!verify_consistency(&heads.0, &heads.1, &tampered), "{old} -> {new}");
+                }
+            }
+        }
+        assert!(log.consistency_proof(5, 14).is_err());
+    }
+
+    #[test]
+    fn signed_heads_verify_and_round_trip() {
+        let rng = &mut ark_std::test_rng();
+        let sk = Fr::rand(rng);
+        let head = log(5).sign_head(&sk, 1700000000);
+        assert!(head.verify(&bls::public_key(&sk)));
+        assert!(!head.verify(&bls::public_key(&Fr::rand(rng))));
+        assert_eq!(SignedTreeHead::from_bytes(&head.to_bytes()).unwrap(), head);
+
+        let mut forged = head;
+        forged.head.size += 1;
+        assert!(!forged.verify(&bls::public_key(&sk)));
+    }
+
+    #[test]
+    fn lines_are_entries() {
+        let text = "{\"a\":1}\n{\"b\":2}\n";
+        let mut log = TransparencyLog::new();
+        log.append(b"{\"a\":1}");
+        log.append(b"{\"b\":2}");
+        assert_eq!(TransparencyLog::from_lines(text).root(), log.root());
+    }
+
+    impl TransparencyLog {
+        fn prefix_head(&self, size: u64) -> TreeHead {
+            TreeHead { size, root: root(&self.leaves[..size as usize]), timestamp: 0 }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Throughput calibration for sizing solver runs.
+//!
+//! `calibrate` times GT multiplications (one step of `dlog::brute_force`) and
+//! pairings on one core. The CLI persists the rates to the settings file,
+//! where cost estimates and thread-pool sizing pick them up.
+
+use ark_bls12_381::{Bls12_381, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use std::{hint::black_box, thread, time::Instant};
+
+const GT_MULS: u32 = 20_000;
+const PAIRINGS: u32 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    /// GT multiplications per second on one core.
+    pub gt_mul_rate: f64,
+    /// Pairings per second on one core.
+    pub pairing_rate: f64,
+    /// Hardware threads available to the process.
+    pub cores: usize,
+}
+
+impl Calibration {
+    /// Threads to give the rayon pool: one per core.
+    pub fn recommended_threads(&self) -> usize {
+        self.cores
+    }
+}
+
+pub fn calibrate() -> Calibration {
+    let (g1, g2) = (G1Affine::generator(), G2Affine::generator());
+
+    let start = Instant::now();
+    let mut pairing = Bls12_381::pairing(g1, g2);
+    for _ in 1..PAIRINGS {
+        pairing = black_box(Bls12_381::pairing(black_box(g1), black_box(g2)));
+    }
+    let pairing_rate = PAIRINGS as f64 / start.elapsed().as_secs_f64();
+
+    let start = Instant::now();
+    let mut acc = pairing;
+    for _ in 0..GT_MULS {
+        acc += black_box(pairing);
+    }
+    black_box(acc);
+    let gt_mul_rate = GT_MULS as f64 / start.elapsed().as_secs_f64();
+
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+    Calibration { gt_mul_rate, pairing_rate, cores }
+}